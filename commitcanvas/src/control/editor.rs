@@ -0,0 +1,18 @@
+use std::error::Error;
+
+use crate::model::{Guid, ShapeConfig};
+use crate::settings::GridConfig;
+
+/// The input laid over a text shape while its content is edited. Dropping it
+/// closes the input.
+pub trait Editor {
+    fn new(
+        guid: Guid,
+        config: &ShapeConfig,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>>
+    where
+        Self: Sized;
+    /// The content as typed so far.
+    fn content(&self) -> String;
+}
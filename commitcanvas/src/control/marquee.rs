@@ -0,0 +1,15 @@
+use std::error::Error;
+
+use crate::types::PointPixel;
+
+/// The rectangle drawn while selecting shapes by dragging over the canvas.
+pub trait Marquee {
+    fn new() -> Result<Self, Box<dyn Error + Send + Sync>>
+    where
+        Self: Sized;
+    fn update(
+        &self,
+        start: PointPixel,
+        end: PointPixel,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
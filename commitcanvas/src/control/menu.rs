@@ -1,12 +1,68 @@
 use std::error::Error;
 
+use crate::model::{
+    ArrowDetails, Color, FillStyle, FontSize, Head, Options, PartialDetails, Routing, TextAlign,
+    VerticalAlign,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum MainMenuButton {
     Arrow,
     Rect,
+    Ellipse,
     Text,
     #[default]
     Select,
 }
 
 pub type MainMenuUpdate = Box<dyn Fn(MainMenuButton) -> Result<(), Box<dyn Error + Send + Sync>>>;
+
+/// The style shown in the style menu: that of the first selected shape, or
+/// the one new shapes are created with if nothing is selected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub options: Options,
+    pub background: Color,
+    pub fill: FillStyle,
+    pub font_size: FontSize,
+    pub align: TextAlign,
+    pub vertical_align: VerticalAlign,
+    pub routing: Routing,
+    pub start_head: Head,
+    pub end_head: Head,
+}
+
+/// The style of a new drawing: the defaults of every shape.
+impl Default for Style {
+    fn default() -> Self {
+        let arrow = ArrowDetails::default();
+        Style {
+            options: Options::default(),
+            background: Color::default(),
+            fill: FillStyle::default(),
+            font_size: FontSize::default(),
+            align: TextAlign::default(),
+            vertical_align: VerticalAlign::default(),
+            routing: arrow.routing,
+            start_head: arrow.start_head,
+            end_head: arrow.end_head,
+        }
+    }
+}
+
+impl Style {
+    pub fn details(&self) -> PartialDetails {
+        PartialDetails {
+            background: Some(self.background),
+            fill: Some(self.fill),
+            font_size: Some(self.font_size),
+            align: Some(self.align),
+            vertical_align: Some(self.vertical_align),
+            routing: Some(self.routing),
+            start_head: Some(self.start_head),
+            end_head: Some(self.end_head),
+        }
+    }
+}
+
+pub type StyleMenuUpdate = Box<dyn Fn(&Style) -> Result<(), Box<dyn Error + Send + Sync>>>;
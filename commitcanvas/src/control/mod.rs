@@ -1,18 +1,25 @@
-use menu::MainMenuUpdate;
+use std::collections::HashMap;
+
+use menu::{MainMenuUpdate, Style, StyleMenuUpdate};
 
 use self::menu::MainMenuButton;
-use crate::types::{Point, PointPixel};
+use crate::types::{Point, PointGrid, PointPixel, VecGrid};
 
+use crate::settings::Settings;
+use crate::text::{ApproximateMeasure, TextLayout, TextMeasure};
 use crate::view::View;
 use crate::{
     model::{
-        ArrowDetails, Event, Guid, Model, Options, PartialShapeConfig, RectDetails, ShapeConfig,
-        ShapeDetails, TextDetails,
+        reshuffle, ArrowDetails, Binding, Document, DocumentError, EllipseDetails, Event, Guid,
+        Model, PartialDetails, PartialOptions, PartialShapeConfig, RectDetails, Routing,
+        ShapeConfig, ShapeDetails, TextDetails,
     },
     utils::{coords_to_pixels, pixels_to_coords},
 };
 
+pub mod editor;
 pub mod marker;
+pub mod marquee;
 pub mod menu;
 pub mod selection;
 
@@ -26,6 +33,13 @@ pub enum ModificationType {
     R,
     B,
     L,
+    /// Translates the whole shape, keeping its size.
+    Move,
+    /// Moves the waypoint at this index of the selected arrow.
+    Waypoint(usize),
+    /// Bends the selected arrow at a new waypoint inserted at this index,
+    /// between the points the segment through the handle connects.
+    Midpoint(usize),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -57,29 +71,223 @@ enum State {
     #[default]
     Normal,
     Selected {
-        guid: Guid,
+        guids: Vec<Guid>,
     },
-    Modifying {
+    Modifying(Drag),
+    /// Dragging a marquee over the canvas.
+    Selecting {
+        start: PointGrid,
+    },
+    /// Typing into the text shape `guid`.
+    Editing {
         guid: Guid,
-        modification_type: ModificationType,
     },
 }
 
-pub struct Control<M: marker::Marker, S: selection::Selection> {
+/// A resize or move of one or more shapes in progress.
+#[derive(Debug)]
+struct Drag {
+    modification_type: ModificationType,
+    /// Pointer position when the drag started.
+    anchor: PointGrid,
+    /// Bounds of the dragged shapes when the drag started, see [`bounds`].
+    start: PointGrid,
+    end: PointGrid,
+    /// The dragged shapes as they were when the drag started.
+    shapes: Vec<(Guid, ShapeConfig)>,
+}
+
+impl Drag {
+    fn new(
+        modification_type: ModificationType,
+        anchor: PointGrid,
+        shapes: Vec<(Guid, ShapeConfig)>,
+    ) -> Option<Self> {
+        let (start, end) = bounds(shapes.iter().map(|(_, config)| config))?;
+        Some(Drag {
+            modification_type,
+            anchor,
+            start,
+            end,
+            shapes,
+        })
+    }
+
+    fn guids(&self) -> Vec<Guid> {
+        self.shapes.iter().map(|(guid, _)| *guid).collect()
+    }
+
+    /// The modifications that bring the shapes to the pointer position `p`.
+    ///
+    /// The handles move the corners and edges of the bounds, every shape is
+    /// scaled along with them. The waypoint handles only move the points of
+    /// an arrow.
+    fn events(&self, p: PointGrid) -> Vec<Event> {
+        if let ModificationType::Waypoint(index) | ModificationType::Midpoint(index) =
+            self.modification_type
+        {
+            return self.waypoint_events(index, p);
+        }
+        let (start, end) = (self.start, self.end);
+        let (new_start, new_end) = match self.modification_type {
+            ModificationType::TL => (p, end),
+            ModificationType::TR => (
+                PointGrid { x: start.x, y: p.y },
+                PointGrid { x: p.x, y: end.y },
+            ),
+            ModificationType::BR => (start, p),
+            ModificationType::BL => (
+                PointGrid { x: p.x, y: start.y },
+                PointGrid { x: end.x, y: p.y },
+            ),
+            ModificationType::T => (PointGrid { x: start.x, y: p.y }, end),
+            ModificationType::R => (start, PointGrid { x: p.x, y: end.y }),
+            ModificationType::B => (start, PointGrid { x: end.x, y: p.y }),
+            ModificationType::L => (PointGrid { x: p.x, y: start.y }, end),
+            ModificationType::Move => {
+                let delta = p - self.anchor;
+                (start + delta, end + delta)
+            }
+            ModificationType::Waypoint(_) | ModificationType::Midpoint(_) => (start, end),
+        };
+
+        let single = self.shapes.len() == 1;
+        let scale = |q: PointGrid| {
+            let axis = |q: i32, from: (i32, i32), to: (i32, i32)| {
+                if from.0 == from.1 {
+                    to.0 + (q - from.0)
+                } else {
+                    to.0 + ((q - from.0) as f32 * (to.1 - to.0) as f32 / (from.1 - from.0) as f32)
+                        .round() as i32
+                }
+            };
+            PointGrid {
+                x: axis(q.x, (start.x, end.x), (new_start.x, new_end.x)),
+                y: axis(q.y, (start.y, end.y), (new_start.y, new_end.y)),
+            }
+        };
+        let delta = p - self.anchor;
+        self.shapes
+            .iter()
+            .map(|(guid, config)| {
+                // A single shape follows the handles with its own points, so
+                // an arrow keeps its waypoints unless it is moved.
+                let moved = if !single {
+                    config.map_points(scale)
+                } else if let ModificationType::Move = self.modification_type {
+                    config.map_points(|q| q + delta)
+                } else {
+                    config.clone()
+                };
+                let bent = matches!(
+                    &config.details,
+                    ShapeDetails::Arrow(arrow) if !arrow.waypoints.is_empty()
+                );
+                Event::Modify {
+                    guid: *guid,
+                    config: PartialShapeConfig {
+                        start: Some(if single { new_start } else { moved.start }),
+                        end: Some(if single { new_end } else { moved.end }),
+                        details: bent.then_some(moved.details),
+                        options: None,
+                        seed: None,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// The modification moving or inserting the waypoint `index` of the
+    /// dragged arrow to `p`. A new waypoint only appears once the pointer left
+    /// the handle.
+    fn waypoint_events(&self, index: usize, p: PointGrid) -> Vec<Event> {
+        let [(guid, config)] = self.shapes.as_slice() else {
+            return Vec::new();
+        };
+        let ShapeDetails::Arrow(arrow) = &config.details else {
+            return Vec::new();
+        };
+        let mut arrow = arrow.clone();
+        match self.modification_type {
+            ModificationType::Waypoint(_) if index < arrow.waypoints.len() => {
+                arrow.waypoints[index] = p;
+            }
+            ModificationType::Midpoint(_) if index <= arrow.waypoints.len() => {
+                if p != self.anchor {
+                    arrow.waypoints.insert(index, p);
+                }
+            }
+            _ => return Vec::new(),
+        }
+        vec![Event::Modify {
+            guid: *guid,
+            config: PartialShapeConfig {
+                start: None,
+                end: None,
+                details: Some(ShapeDetails::Arrow(arrow)),
+                options: None,
+                seed: None,
+            },
+        }]
+    }
+}
+
+/// The points the selection handles are placed on: the shape's own points for
+/// a single shape, otherwise the box around all shapes.
+fn bounds<'a>(
+    mut shapes: impl ExactSizeIterator<Item = &'a ShapeConfig>,
+) -> Option<(PointGrid, PointGrid)> {
+    if shapes.len() == 1 {
+        return shapes.next().map(|config| (config.start, config.end));
+    }
+    shapes
+        .flat_map(|config| config.points())
+        .fold(None, |bounds, p| {
+            let (min, max) = bounds.unwrap_or((p, p));
+            Some((
+                PointGrid {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                PointGrid {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            ))
+        })
+}
+
+pub struct Control<
+    M: marker::Marker,
+    S: selection::Selection,
+    Q: marquee::Marquee,
+    E: editor::Editor,
+> {
     button_state: MainMenuButton,
     mouse_pixel_coords: PointPixel,
-    mouse_coords: Point<i32>,
-    #[allow(dead_code)]
+    mouse_coords: PointGrid,
     selection: Option<S>,
     model: Model,
     state: State,
-    copied_shape: Option<ShapeConfig>,
+    copied_shapes: Vec<(Guid, ShapeConfig)>,
+    /// Style of the next created shape.
+    style: Style,
     main_menu_update: MainMenuUpdate,
+    style_menu_update: StyleMenuUpdate,
     marker: Option<M>,
+    marquee: Option<Q>,
+    editor: Option<E>,
+    text_measure: Box<dyn TextMeasure>,
 }
 
-impl<MARKER: marker::Marker, SELECTION: selection::Selection> Control<MARKER, SELECTION> {
-    pub fn new(main_menu_update: MainMenuUpdate) -> Self {
+impl<
+        MARKER: marker::Marker,
+        SELECTION: selection::Selection,
+        MARQUEE: marquee::Marquee,
+        EDITOR: editor::Editor,
+    > Control<MARKER, SELECTION, MARQUEE, EDITOR>
+{
+    pub fn new(main_menu_update: MainMenuUpdate, style_menu_update: StyleMenuUpdate) -> Self {
         log::info!("starting contol setup");
         let button_state = MainMenuButton::default();
 
@@ -90,11 +298,16 @@ impl<MARKER: marker::Marker, SELECTION: selection::Selection> Control<MARKER, SE
             mouse_pixel_coords: PointPixel { x: 0.0, y: 0.0 },
             mouse_coords: Point { x: 0, y: 0 },
             marker: None,
+            marquee: None,
+            editor: None,
+            text_measure: Box::new(ApproximateMeasure::default()),
             selection: None,
             main_menu_update,
             model,
             state: State::default(),
-            copied_shape: None,
+            copied_shapes: Vec::new(),
+            style: Style::default(),
+            style_menu_update,
         }
     }
 
@@ -104,8 +317,12 @@ impl<MARKER: marker::Marker, SELECTION: selection::Selection> Control<MARKER, SE
 
     pub fn set_button_state(&mut self, state: MainMenuButton) {
         log::info!("setting button state to {:?}", state);
+        self.stop_editing(true);
         self.button_state = state;
         self.selection = None;
+        if let State::Selected { .. } = self.state {
+            self.state = State::Normal;
+        }
         match state {
             MainMenuButton::Arrow => {
                 self.marker = Some(MARKER::new().expect("failed to create marker"));
@@ -113,6 +330,9 @@ impl<MARKER: marker::Marker, SELECTION: selection::Selection> Control<MARKER, SE
             MainMenuButton::Rect => {
                 self.marker = Some(MARKER::new().expect("failed to create marker"));
             }
+            MainMenuButton::Ellipse => {
+                self.marker = Some(MARKER::new().expect("failed to create marker"));
+            }
             MainMenuButton::Text => {
                 self.marker = Some(MARKER::new().expect("failed to create marker"));
             }
@@ -121,6 +341,42 @@ impl<MARKER: marker::Marker, SELECTION: selection::Selection> Control<MARKER, SE
             }
         }
         self._update_menu();
+        self._update_style_menu();
+    }
+
+    /// Replaces the drawing with the one in `document`.
+    pub fn load_document(&mut self, document: Document) -> Result<(), DocumentError> {
+        log::info!("loading document");
+        self.model.load_document(document)?;
+        self.state = State::Normal;
+        self.editor = None;
+        self.selection = None;
+        self.marquee = None;
+        Ok(())
+    }
+
+    /// The drawing as a document, for saving and exporting.
+    pub fn to_document(&self) -> Document {
+        self.model.to_document()
+    }
+
+    /// Replaces the measurement used to grow text shapes to their content.
+    /// It should match the one the views lay out text with.
+    pub fn set_text_measure(&mut self, measure: Box<dyn TextMeasure>) {
+        self.text_measure = measure;
+    }
+
+    pub fn settings(&self) -> &Settings {
+        self.model.settings()
+    }
+
+    /// Changes the settings of the current document, e.g. the grid.
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.model.set_settings(settings);
+        self.mouse_coords = pixels_to_coords(self.mouse_pixel_coords, &settings.grid);
+        if let State::Selected { guids } = &self.state {
+            self.set_selection(guids.clone());
+        }
     }
 
     fn _update_menu(&self) {
@@ -129,305 +385,715 @@ impl<MARKER: marker::Marker, SELECTION: selection::Selection> Control<MARKER, SE
         }
     }
 
+    fn _update_style_menu(&self) {
+        if let Err(e) = (self.style_menu_update)(&self.style()) {
+            log::error!("failed to update style menu: {:?}", e);
+        }
+    }
+
+    /// The style of the first selected shape, or of the next created shape if
+    /// nothing is selected.
+    pub fn style(&self) -> Style {
+        let mut style = self.style.clone();
+        if let Some(config) = self
+            .selected()
+            .first()
+            .and_then(|guid| self.model.get_shape(*guid))
+        {
+            style.options = config.options.clone();
+            match &config.details {
+                ShapeDetails::Arrow(arrow) => {
+                    style.routing = arrow.routing;
+                    style.start_head = arrow.start_head;
+                    style.end_head = arrow.end_head;
+                }
+                ShapeDetails::Rect(rect) => {
+                    style.background = rect.background;
+                    style.fill = rect.fill;
+                }
+                ShapeDetails::Ellipse(ellipse) => {
+                    style.background = ellipse.background;
+                    style.fill = ellipse.fill;
+                }
+                ShapeDetails::Text(text) => {
+                    style.font_size = text.font_size;
+                    style.align = text.align;
+                    style.vertical_align = text.vertical_align;
+                }
+            }
+        }
+        style
+    }
+
+    /// Changes the options of all selected shapes in one undo step. The new
+    /// values also apply to shapes created afterwards.
+    pub fn set_options(&mut self, options: PartialOptions) {
+        log::info!("setting options: {:?}", options);
+        self.style.options.update(options);
+        let events: Vec<Event> = self
+            .selected()
+            .iter()
+            .filter_map(|guid| {
+                let mut new_options = self.model.get_shape(*guid)?.options.clone();
+                new_options.update(options);
+                Some(Event::Modify {
+                    guid: *guid,
+                    config: PartialShapeConfig {
+                        start: None,
+                        end: None,
+                        details: None,
+                        options: Some(new_options),
+                        seed: None,
+                    },
+                })
+            })
+            .collect();
+        if !events.is_empty() {
+            self.model.process_events(events);
+        }
+        self._update_style_menu();
+    }
+
+    /// Changes the details of all selected shapes that have the given fields
+    /// in one undo step. The new values also apply to shapes created
+    /// afterwards.
+    pub fn set_details(&mut self, details: PartialDetails) {
+        log::info!("setting details: {:?}", details);
+        if let Some(background) = details.background {
+            self.style.background = background;
+        }
+        if let Some(fill) = details.fill {
+            self.style.fill = fill;
+        }
+        if let Some(font_size) = details.font_size {
+            self.style.font_size = font_size;
+        }
+        if let Some(align) = details.align {
+            self.style.align = align;
+        }
+        if let Some(vertical_align) = details.vertical_align {
+            self.style.vertical_align = vertical_align;
+        }
+        if let Some(routing) = details.routing {
+            self.style.routing = routing;
+        }
+        if let Some(start_head) = details.start_head {
+            self.style.start_head = start_head;
+        }
+        if let Some(end_head) = details.end_head {
+            self.style.end_head = end_head;
+        }
+        let events: Vec<Event> = self
+            .selected()
+            .iter()
+            .filter_map(|guid| {
+                let mut new_details = self.model.get_shape(*guid)?.details.clone();
+                new_details.update(details);
+                self.modify_details(*guid, new_details)
+            })
+            .collect();
+        if !events.is_empty() {
+            self.model.process_events(events);
+        }
+        self._update_style_menu();
+    }
+
+    /// Redraws the strokes of all selected shapes with new seeds in one undo
+    /// step, keeping everything else.
+    pub fn reshuffle(&mut self) {
+        log::info!("reshuffling strokes");
+        let events: Vec<Event> = self
+            .selected()
+            .iter()
+            .filter_map(|guid| {
+                let seed = self.model.get_shape(*guid)?.seed;
+                Some(Event::Modify {
+                    guid: *guid,
+                    config: PartialShapeConfig {
+                        start: None,
+                        end: None,
+                        details: None,
+                        options: None,
+                        seed: Some(reshuffle(seed)),
+                    },
+                })
+            })
+            .collect();
+        if !events.is_empty() {
+            self.model.process_events(events);
+        }
+    }
+
     pub fn mouse_update(&mut self, (x, y): (f32, f32)) {
         log::debug!("mouse update: ({}, {})", x, y);
         self.mouse_pixel_coords = Point { x, y };
-        let new_coords = pixels_to_coords(Point { x, y });
+        let grid = self.model.settings().grid;
+        let new_coords = pixels_to_coords(Point { x, y }, &grid);
         if new_coords != self.mouse_coords {
             self.mouse_coords = new_coords;
             if let Some(marker) = &mut self.marker {
                 marker
-                    .update(coords_to_pixels(new_coords))
+                    .update(coords_to_pixels(new_coords, &grid))
                     .expect("failed to update marker");
             }
 
-            if let State::Modifying {
-                guid,
-                modification_type,
-            } = self.state
-            {
-                let config = self.model.get_shape(guid).expect("failed to get shape");
-                let p = coords_to_pixels(self.mouse_coords);
-                let event = match modification_type {
-                    ModificationType::TL => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: Some(p),
-                            end: None,
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::TR => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: Some(PointPixel {
-                                x: config.start.x,
-                                y: p.y,
-                            }),
-                            end: Some(PointPixel {
-                                x: p.x,
-                                y: config.end.y,
-                            }),
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::BR => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: None,
-                            end: Some(p),
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::BL => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: Some(PointPixel {
-                                x: p.x,
-                                y: config.start.y,
-                            }),
-                            end: Some(PointPixel {
-                                x: config.end.x,
-                                y: p.y,
-                            }),
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::T => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: Some(PointPixel {
-                                x: config.start.x,
-                                y: p.y,
-                            }),
-                            end: None,
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::R => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: None,
-                            end: Some(PointPixel {
-                                x: p.x,
-                                y: config.end.y,
-                            }),
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::B => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: None,
-                            end: Some(PointPixel {
-                                x: config.end.x,
-                                y: p.y,
-                            }),
-                            details: None,
-                            options: None,
-                        },
-                    },
-                    ModificationType::L => Event::Modify {
-                        guid,
-                        config: PartialShapeConfig {
-                            start: Some(PointPixel {
-                                x: p.x,
-                                y: config.start.y,
-                            }),
-                            end: None,
-                            details: None,
-                            options: None,
-                        },
-                    },
-                };
-                self.model.process_event(event);
-                if let Some(selection) = &mut self.selection {
-                    selection
-                        .update(self.model.get_shape(guid).expect("failed to get shape"))
-                        .expect("failed to update selection");
+            match &self.state {
+                State::Modifying(drag) => {
+                    let events = drag.events(self.mouse_coords);
+                    let guids = drag.guids();
+                    for event in events {
+                        self.model.process_event(event);
+                    }
+                    self.update_selection(&guids);
+                }
+                State::Selecting { start } => {
+                    if let Some(marquee) = &self.marquee {
+                        marquee
+                            .update(
+                                coords_to_pixels(*start, &grid),
+                                coords_to_pixels(new_coords, &grid),
+                            )
+                            .expect("failed to update marquee");
+                    }
                 }
+                _ => {}
             }
         }
     }
 
     pub fn mouse_down(&mut self, button: MouseButton) {
         log::debug!("mouse down");
+        if let Some(guid) = self.stop_editing(true) {
+            // The click only closes the editor.
+            self.select(guid);
+            return;
+        }
         if button == MouseButton::Right {
             // TODO: cancel shape creation or modification
             self.state = State::Normal;
+            self.marquee = None;
             self.set_button_state(MainMenuButton::default());
             return;
         }
         if button == MouseButton::Left {
             if let State::Selected { .. } = self.state {
                 self.state = State::Normal;
+                self.selection = None;
+                self._update_style_menu();
             }
         }
-        match self.button_state {
-            MainMenuButton::Arrow => {
-                self.marker = None;
-                let mouse = coords_to_pixels(self.mouse_coords);
-                let event = Event::Add {
-                    guid: None,
-                    config: ShapeConfig {
-                        start: mouse,
-                        end: mouse,
-                        details: ShapeDetails::Arrow(ArrowDetails::default()),
-                        options: Options::default(),
-                    },
-                };
-                if let Some(guid) = self.model.process_event(event) {
-                    self.state = State::Modifying {
-                        guid,
-                        modification_type: ModificationType::BR,
+        let mut details = match self.button_state {
+            MainMenuButton::Arrow => ShapeDetails::Arrow(ArrowDetails::default()),
+            MainMenuButton::Rect => ShapeDetails::Rect(RectDetails::default()),
+            MainMenuButton::Ellipse => ShapeDetails::Ellipse(EllipseDetails::default()),
+            MainMenuButton::Text => ShapeDetails::Text(TextDetails::default()),
+            MainMenuButton::Select => {
+                if button == MouseButton::Left {
+                    self.state = State::Selecting {
+                        start: self.mouse_coords,
                     };
+                    self.marquee = Some(MARQUEE::new().expect("failed to create marquee"));
                 }
+                return;
             }
-            MainMenuButton::Rect => {
-                self.marker = None;
-                let mouse = coords_to_pixels(self.mouse_coords);
-                let event = Event::Add {
-                    guid: None,
-                    config: ShapeConfig {
-                        start: mouse,
-                        end: mouse,
-                        details: ShapeDetails::Rect(RectDetails::default()),
-                        options: Options::default(),
-                    },
-                };
-                if let Some(guid) = self.model.process_event(event) {
-                    self.state = State::Modifying {
-                        guid,
-                        modification_type: ModificationType::BR,
-                    };
+        };
+
+        details.update(self.style.details());
+        self.marker = None;
+        let mouse = self.mouse_coords;
+        let end = match details {
+            ShapeDetails::Text(_) => mouse + VecGrid { x: 12, y: 4 },
+            _ => mouse,
+        };
+        let config = ShapeConfig {
+            start: mouse,
+            end,
+            details,
+            options: self.style.options.clone(),
+            seed: 0,
+        };
+        let event = Event::Add {
+            guid: None,
+            config: config.clone(),
+        };
+        if let Some(guid) = self.model.process_event(event) {
+            if let Some(drag) = Drag::new(ModificationType::BR, mouse, vec![(guid, config)]) {
+                self.state = State::Modifying(drag);
+            }
+        }
+    }
+
+    pub fn mouse_up(&mut self) {
+        log::debug!("mouse up");
+        match std::mem::take(&mut self.state) {
+            State::Modifying(drag) => {
+                let created_text = self.button_state == MainMenuButton::Text;
+                // Resizing may leave text without room for its lines.
+                for guid in drag.guids() {
+                    if let Some((start, end)) = self
+                        .model
+                        .get_shape(guid)
+                        .and_then(|config| self.fit_text(config))
+                    {
+                        self.model.process_event(Event::Modify {
+                            guid,
+                            config: PartialShapeConfig {
+                                start: Some(start),
+                                end: Some(end),
+                                details: None,
+                                options: None,
+                                seed: None,
+                            },
+                        });
+                    }
+                }
+                if let ModificationType::Waypoint(_) | ModificationType::Midpoint(_) =
+                    drag.modification_type
+                {
+                    self.straighten(&drag.guids());
+                }
+                self.attach(&drag.guids());
+                self.set_button_state(MainMenuButton::default());
+                self.model.process_event(Event::Checkpoint);
+                self.set_selection(drag.guids());
+                if created_text {
+                    for guid in drag.guids() {
+                        self.edit_text(guid);
+                    }
                 }
             }
-            MainMenuButton::Text => {
-                self.marker = None;
-                let mouse = coords_to_pixels(self.mouse_coords);
-                let event = Event::Add {
-                    guid: None,
-                    config: ShapeConfig {
-                        start: mouse,
-                        end: PointPixel {
-                            x: mouse.x + 150.0,
-                            y: mouse.y + 50.0,
-                        },
-                        details: ShapeDetails::Text(TextDetails::default()),
-                        options: Options::default(),
+            State::Selecting { start } => {
+                self.marquee = None;
+                let end = self.mouse_coords;
+                if start != end {
+                    let mut guids: Vec<Guid> = self
+                        .model
+                        .shapes()
+                        .filter(|(_, config)| config.intersects(start, end))
+                        .map(|(guid, _)| *guid)
+                        .collect();
+                    guids.sort();
+                    self.set_selection(guids);
+                }
+            }
+            state => self.state = state,
+        }
+    }
+
+    /// Drops the waypoints of the arrows `guids` that were dragged back onto
+    /// the line between their neighbours.
+    fn straighten(&mut self, guids: &[Guid]) {
+        for guid in guids {
+            let Some(config) = self.model.get_shape(*guid) else {
+                continue;
+            };
+            let ShapeDetails::Arrow(arrow) = &config.details else {
+                continue;
+            };
+            let mut straight = arrow.clone();
+            straight.simplify(config.start, config.end);
+            if straight != *arrow {
+                self.model.process_event(Event::Modify {
+                    guid: *guid,
+                    config: PartialShapeConfig {
+                        start: None,
+                        end: None,
+                        details: Some(ShapeDetails::Arrow(straight)),
+                        options: None,
+                        seed: None,
                     },
-                };
-                if let Some(guid) = self.model.process_event(event) {
-                    self.state = State::Modifying {
-                        guid,
-                        modification_type: ModificationType::BR,
-                    };
+                });
+            }
+        }
+    }
+
+    /// Attaches the ends of the arrows `guids` to the shapes they were dropped
+    /// on, and lets go of the shapes they were dragged off.
+    fn attach(&mut self, guids: &[Guid]) {
+        for guid in guids {
+            let Some(config) = self.model.get_shape(*guid) else {
+                continue;
+            };
+            let ShapeDetails::Arrow(arrow) = &config.details else {
+                continue;
+            };
+            let mut attached = arrow.clone();
+            attached.start_binding = self.binding_at(*guid, config.start, arrow.start_binding);
+            attached.end_binding = self.binding_at(*guid, config.end, arrow.end_binding);
+            if attached != *arrow {
+                self.model.process_event(Event::Modify {
+                    guid: *guid,
+                    config: PartialShapeConfig {
+                        start: None,
+                        end: None,
+                        details: Some(ShapeDetails::Arrow(attached)),
+                        options: None,
+                        seed: None,
+                    },
+                });
+            }
+        }
+    }
+
+    /// The binding of an end of the arrow `guid` at `p` to the topmost shape
+    /// under it. The shape it is `bound` to wins while the end is still on it.
+    fn binding_at(&self, guid: Guid, p: PointGrid, bound: Option<Binding>) -> Option<Binding> {
+        if let Some(bound) = bound {
+            if let Some(target) = self.model.get_shape(bound.guid) {
+                if target.anchor_point(bound.anchor) == p {
+                    return Some(bound);
+                }
+                if let Some(anchor) = target.anchor(p) {
+                    return Some(Binding {
+                        guid: bound.guid,
+                        anchor,
+                    });
                 }
             }
-            _ => {}
         }
+        self.model
+            .shapes()
+            .filter(|(target, _)| **target != guid)
+            .filter_map(|(target, config)| Some((*target, config.anchor(p)?)))
+            .max_by_key(|(target, _)| *target)
+            .map(|(guid, anchor)| Binding { guid, anchor })
     }
 
-    pub fn mouse_up(&mut self) {
-        log::debug!("mouse up");
-        if let State::Modifying { guid, .. } = self.state {
-            self.set_button_state(MainMenuButton::default());
-            self.model.process_event(Event::Checkpoint);
+    /// Starts resizing or moving the selected shapes.
+    pub fn modify(&mut self, modification_type: ModificationType) {
+        log::info!("modifying selection: {:?}", modification_type);
+        if let State::Selected { guids } = &self.state {
+            let shapes = guids
+                .iter()
+                .filter_map(|guid| Some((*guid, self.model.get_shape(*guid)?.clone())))
+                .collect();
+            if let Some(drag) = Drag::new(modification_type, self.mouse_coords, shapes) {
+                self.state = State::Modifying(drag);
+            }
+        }
+    }
+
+    /// Starts dragging the shape `guid`, together with the rest of the
+    /// selection if it is selected, if the select tool is active.
+    ///
+    /// Returns whether the drag started. Otherwise the click belongs to the
+    /// active drawing tool.
+    pub fn start_move(&mut self, guid: Guid) -> bool {
+        if self.button_state != MainMenuButton::Select {
+            return false;
+        }
+        if !self.selected().contains(&guid) {
             self.select(guid);
         }
+        self.modify(ModificationType::Move);
+        true
     }
 
-    pub fn modify(&mut self, guid: Guid, modification_type: ModificationType) {
-        log::info!("modifying shape: {:?} {:?}", guid, modification_type);
-        self.state = State::Modifying {
-            guid,
-            modification_type,
-        };
+    /// Adds `guid` to the selection or removes it, if the select tool is
+    /// active.
+    ///
+    /// Returns whether the selection changed.
+    pub fn toggle_selection(&mut self, guid: Guid) -> bool {
+        if self.button_state != MainMenuButton::Select {
+            return false;
+        }
+        log::info!("toggling selection of shape: {:?}", guid);
+        let mut guids = self.selected().to_vec();
+        if let Some(index) = guids.iter().position(|selected| *selected == guid) {
+            guids.remove(index);
+        } else {
+            guids.push(guid);
+        }
+        self.set_selection(guids);
+        true
     }
 
     pub fn select(&mut self, guid: Guid) {
         log::info!("selecting shape: {:?}", guid);
-        self.state = State::Selected { guid };
-        let shape = self.model.get_shape(guid).expect("failed to get shape");
-        self.selection = Some(SELECTION::new(guid, shape).expect("failed to create selection"));
+        self.set_selection(vec![guid]);
+    }
+
+    /// The currently selected shapes, in the order they were selected. A
+    /// text shape counts as selected while it is edited.
+    pub fn selected(&self) -> &[Guid] {
+        match &self.state {
+            State::Selected { guids } => guids,
+            State::Editing { guid } => std::slice::from_ref(guid),
+            _ => &[],
+        }
+    }
+
+    /// Opens the editor on the text shape `guid`, if the select tool is
+    /// active.
+    ///
+    /// Returns whether the editor opened.
+    pub fn edit_text(&mut self, guid: Guid) -> bool {
+        if self.button_state != MainMenuButton::Select {
+            return false;
+        }
+        self.stop_editing(true);
+        let Some(config) = self.model.get_shape(guid) else {
+            return false;
+        };
+        if !matches!(config.details, ShapeDetails::Text(_)) {
+            return false;
+        }
+        log::info!("editing text of shape: {:?}", guid);
+        self.selection = None;
+        self.editor = Some(
+            EDITOR::new(guid, config, &self.model.settings().grid)
+                .expect("failed to create editor"),
+        );
+        self.state = State::Editing { guid };
+        self._update_style_menu();
+        true
+    }
+
+    pub fn is_editing(&self) -> bool {
+        matches!(self.state, State::Editing { .. })
+    }
+
+    /// Closes the editor and stores the typed content in one undo step.
+    pub fn commit_edit(&mut self) {
+        if let Some(guid) = self.stop_editing(true) {
+            self.select(guid);
+        }
+    }
+
+    /// Closes the editor and discards the typed content.
+    pub fn cancel_edit(&mut self) {
+        if let Some(guid) = self.stop_editing(false) {
+            self.select(guid);
+        }
+    }
+
+    /// Closes the editor, if open, and returns the edited shape.
+    fn stop_editing(&mut self, commit: bool) -> Option<Guid> {
+        let State::Editing { guid } = self.state else {
+            return None;
+        };
+        self.state = State::Normal;
+        let editor = self.editor.take()?;
+        if !commit {
+            return Some(guid);
+        }
+        let content = editor.content();
+        drop(editor);
+        if let Some(ShapeDetails::Text(text)) =
+            self.model.get_shape(guid).map(|config| &config.details)
+        {
+            if text.content != content {
+                log::info!("changing text of shape: {:?}", guid);
+                let details = ShapeDetails::Text(TextDetails {
+                    content,
+                    ..text.clone()
+                });
+                if let Some(event) = self.modify_details(guid, details) {
+                    self.model.process_events(vec![event]);
+                }
+            }
+        }
+        Some(guid)
+    }
+
+    /// The event changing the details of `guid`, growing text shapes so the
+    /// new content fits.
+    fn modify_details(&self, guid: Guid, details: ShapeDetails) -> Option<Event> {
+        let mut config = self.model.get_shape(guid)?.clone();
+        config.details = details;
+        let (start, end) = self.fit_text(&config).unzip();
+        Some(Event::Modify {
+            guid,
+            config: PartialShapeConfig {
+                start,
+                end,
+                details: Some(config.details),
+                options: None,
+                seed: None,
+            },
+        })
+    }
+
+    /// New bounds for a text shape whose lines overflow it, extending it
+    /// downwards to whole grid cells.
+    fn fit_text(&self, config: &ShapeConfig) -> Option<(PointGrid, PointGrid)> {
+        let ShapeDetails::Text(text) = &config.details else {
+            return None;
+        };
+        let grid = &self.model.settings().grid;
+        let layout = TextLayout::new(
+            text,
+            coords_to_pixels(config.start, grid),
+            coords_to_pixels(config.end, grid),
+            self.text_measure.as_ref(),
+        );
+        let rows = (layout.height / grid.spacing).ceil() as i32;
+        let (top, bottom) = (
+            config.start.y.min(config.end.y),
+            config.start.y.max(config.end.y),
+        );
+        if top + rows <= bottom {
+            return None;
+        }
+        let (mut start, mut end) = (config.start, config.end);
+        if start.y > end.y {
+            start.y = top + rows;
+        } else {
+            end.y = top + rows;
+        }
+        Some((start, end))
+    }
+
+    fn set_selection(&mut self, guids: Vec<Guid>) {
+        self.stop_editing(true);
+        let guids: Vec<Guid> = guids
+            .into_iter()
+            .filter(|guid| self.model.get_shape(*guid).is_some())
+            .collect();
+        self.selection = None;
+        if guids.is_empty() {
+            self.state = State::Normal;
+            self._update_style_menu();
+            return;
+        }
+        let (start, end) = self.bounds_of(&guids).expect("failed to get bounds");
+        let grid = self.model.settings().grid;
+        let mut selection =
+            SELECTION::new(&guids, start, end, &grid).expect("failed to create selection");
+        selection
+            .set_points(&self.points_of(&guids), &grid)
+            .expect("failed to show the points of the selection");
+        self.selection = Some(selection);
+        self.state = State::Selected { guids };
+        self._update_style_menu();
+    }
+
+    fn update_selection(&mut self, guids: &[Guid]) {
+        let points = self.points_of(guids);
+        if let Some((start, end)) = self.bounds_of(guids) {
+            if let Some(selection) = &mut self.selection {
+                let grid = self.model.settings().grid;
+                selection
+                    .update(start, end, &grid)
+                    .expect("failed to update selection");
+                selection
+                    .set_points(&points, &grid)
+                    .expect("failed to show the points of the selection");
+            }
+        }
+    }
+
+    /// The points of the arrow `guids` consists of, for its waypoint handles.
+    /// The model places the waypoints of elbow arrows, they have no handles.
+    fn points_of(&self, guids: &[Guid]) -> Vec<PointGrid> {
+        let [guid] = guids else {
+            return Vec::new();
+        };
+        match self.model.get_shape(*guid) {
+            Some(config) => match &config.details {
+                ShapeDetails::Arrow(arrow) if arrow.routing != Routing::Elbow => config.points(),
+                _ => Vec::new(),
+            },
+            None => Vec::new(),
+        }
+    }
+
+    fn bounds_of(&self, guids: &[Guid]) -> Option<(PointGrid, PointGrid)> {
+        let shapes: Vec<&ShapeConfig> = guids
+            .iter()
+            .filter_map(|guid| self.model.get_shape(*guid))
+            .collect();
+        bounds(shapes.into_iter())
     }
 
     pub fn undo(&mut self) {
         log::info!("undo");
+        self.stop_editing(true);
         self.model.undo();
+        self.set_selection(self.selected().to_vec());
     }
 
     pub fn redo(&mut self) {
         log::info!("redo");
+        self.stop_editing(true);
         self.model.redo();
+        self.set_selection(self.selected().to_vec());
     }
 
     pub fn cut(&mut self) {
         log::info!("cut");
-        if let State::Selected { guid } = self.state {
-            let shape = self.model.get_shape(guid).expect("failed to get shape");
-            self.copied_shape = Some(shape.clone());
-            self.model.process_event(Event::Remove { guid });
-        }
-        if self.selection.is_some() {
-            self.selection = None;
-        }
+        self.copy();
+        self.delete();
     }
 
     pub fn copy(&mut self) {
         log::info!("copy");
-        if let State::Selected { guid } = self.state {
-            let shape = self.model.get_shape(guid).expect("failed to get shape");
-            self.copied_shape = Some(shape.clone());
+        if !self.selected().is_empty() {
+            self.copied_shapes = self
+                .selected()
+                .iter()
+                .filter_map(|guid| Some((*guid, self.model.get_shape(*guid)?.clone())))
+                .collect();
         }
     }
 
+    /// Pastes the copied shapes with the top left corner of their bounds at
+    /// the pointer. Pasted arrows stay attached to the shapes pasted with
+    /// them and let go of all others.
     pub fn paste(&mut self) {
         log::info!("paste");
-        if let Some(config) = &self.copied_shape {
-            let mouse = coords_to_pixels(self.mouse_coords);
-            let event = Event::Add {
-                guid: None,
-                config: ShapeConfig {
-                    start: mouse,
-                    end: PointPixel {
-                        x: mouse.x + config.end.x - config.start.x,
-                        y: mouse.y + config.end.y - config.start.y,
-                    },
-                    details: config.details.clone(),
-                    options: config.options.clone(),
-                },
-            };
-            let guid = self
-                .model
-                .process_event(event)
-                .expect("failed to process event");
-            let new_shape = self.model.get_shape(guid).expect("failed to get shape");
-            self.selection =
-                Some(SELECTION::new(guid, new_shape).expect("failed to create selection"));
-        }
+        let Some(origin) = self
+            .copied_shapes
+            .iter()
+            .flat_map(|(_, config)| config.points())
+            .reduce(|a, b| PointGrid {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+            })
+        else {
+            return;
+        };
+        let offset = self.mouse_coords - origin;
+        let first = self.model.next_guid();
+        let pasted: HashMap<Guid, Guid> = self
+            .copied_shapes
+            .iter()
+            .zip(first..)
+            .map(|((guid, _), pasted)| (*guid, pasted))
+            .collect();
+        let events: Vec<Event> = self
+            .copied_shapes
+            .iter()
+            .map(|(_, config)| {
+                let mut config = config.map_points(|p| p + offset);
+                if let ShapeDetails::Arrow(arrow) = &mut config.details {
+                    arrow.rebind(|guid| pasted.get(&guid).copied());
+                }
+                Event::Add { guid: None, config }
+            })
+            .collect();
+        let guids = self.model.process_events(events);
+        self.set_selection(guids);
     }
 
     pub fn delete(&mut self) {
         log::info!("delete");
-        if let State::Selected { guid } = self.state {
-            self.model.process_event(Event::Remove { guid });
-        }
-        if self.selection.is_some() {
-            self.selection = None;
+        let events: Vec<Event> = self
+            .selected()
+            .iter()
+            .map(|guid| Event::Remove { guid: *guid })
+            .collect();
+        if !events.is_empty() {
+            self.model.process_events(events);
         }
+        self.set_selection(Vec::new());
     }
 
+    /// The selected shape, if exactly one shape is selected.
     #[cfg(feature = "test-utils")]
     pub fn get_selection(&self) -> Option<Guid> {
-        match self.state {
-            State::Selected { guid } => Some(guid),
+        match self.selected() {
+            [guid] => Some(*guid),
             _ => None,
         }
     }
@@ -1,11 +1,36 @@
 use std::error::Error;
 
-use crate::model::ShapeConfig;
-use crate::types::Guid;
+use crate::settings::GridConfig;
+use crate::types::{Guid, PointGrid};
 
+/// Handles around the selected shapes.
+///
+/// For a single shape `start` and `end` are the shape's own points, otherwise
+/// the corners of the box around all selected shapes.
 pub trait Selection {
-    fn new(guid: Guid, config: &ShapeConfig) -> Result<Self, Box<dyn Error + Send + Sync>>
+    fn new(
+        guids: &[Guid],
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>>
     where
         Self: Sized;
-    fn update(&mut self, config: &ShapeConfig) -> Result<(), Box<dyn Error + Send + Sync>>;
+    fn update(
+        &mut self,
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Shows handles on the points of a single selected arrow, `start` and
+    /// `end` included, to drag and insert waypoints. Empty for any other
+    /// selection.
+    fn set_points(
+        &mut self,
+        points: &[PointGrid],
+        grid: &GridConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let _ = (points, grid);
+        Ok(())
+    }
 }
@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+
+use super::{CommitKind, CommitOptions, Location, Script, ScriptError, ScriptErrorKind, Statement};
+use crate::model::Color;
+
+/// Name of the branch checked out at the start.
+const MAIN: &str = "main";
+
+/// Colors of branches without one of their own, in order of creation.
+const BRANCH_COLORS: [Color; 8] = [
+    Color::Sky,
+    Color::Amber,
+    Color::Emerald,
+    Color::Rose,
+    Color::Purple,
+    Color::Lime,
+    Color::Teal,
+    Color::Organge,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphCommit {
+    pub id: String,
+    pub message: Option<String>,
+    pub kind: CommitKind,
+    /// The color of the commit, or of its branch if it has none.
+    pub color: Color,
+    /// First parent first.
+    pub parents: Vec<String>,
+    /// The branch the commit was made on.
+    pub branch: String,
+    pub tags: Vec<String>,
+    /// Id of the commit this one copies.
+    pub cherry_pick: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphBranch {
+    pub name: String,
+    pub color: Color,
+    /// The newest commit on the branch, `None` before the first one.
+    pub head: Option<String>,
+}
+
+/// The history a script describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitGraph {
+    /// In the order they were made.
+    pub commits: Vec<GraphCommit>,
+    /// In the order they were created, starting with `main`.
+    pub branches: Vec<GraphBranch>,
+}
+
+impl GitGraph {
+    pub fn commit(&self, id: &str) -> Option<&GraphCommit> {
+        self.commits.iter().find(|commit| commit.id == id)
+    }
+
+    pub fn branch(&self, name: &str) -> Option<&GraphBranch> {
+        self.branches.iter().find(|branch| branch.name == name)
+    }
+}
+
+/// The state of the repository while the statements run.
+struct Repository {
+    graph: GitGraph,
+    checked_out: usize,
+    tags: HashSet<String>,
+}
+
+impl Repository {
+    fn branch_index(&self, name: &str) -> Option<usize> {
+        self.graph
+            .branches
+            .iter()
+            .position(|branch| branch.name == name)
+    }
+
+    fn head(&self) -> Option<&String> {
+        self.graph.branches[self.checked_out].head.as_ref()
+    }
+
+    /// The first id of the form `c<n>` no commit has.
+    fn free_id(&self) -> String {
+        (self.graph.commits.len() + 1..)
+            .map(|n| format!("c{}", n))
+            .find(|id| self.graph.commit(id).is_none())
+            .unwrap_or_default()
+    }
+
+    fn commit(
+        &mut self,
+        location: Location,
+        options: &CommitOptions,
+        parents: Vec<String>,
+        cherry_pick: Option<String>,
+    ) -> Result<(), ScriptError> {
+        let id = match &options.id {
+            Some(id) if self.graph.commit(id).is_some() => {
+                return Err(ScriptError::new(
+                    location,
+                    ScriptErrorKind::DuplicateId(id.clone()),
+                ))
+            }
+            Some(id) => id.clone(),
+            None => self.free_id(),
+        };
+        let branch = &mut self.graph.branches[self.checked_out];
+        branch.head = Some(id.clone());
+        let commit = GraphCommit {
+            id,
+            message: options.message.clone(),
+            kind: options.kind.unwrap_or_default(),
+            color: options.color.unwrap_or(branch.color),
+            parents,
+            branch: branch.name.clone(),
+            tags: Vec::new(),
+            cherry_pick,
+        };
+        self.graph.commits.push(commit);
+        Ok(())
+    }
+
+    fn run(&mut self, location: Location, statement: &Statement) -> Result<(), ScriptError> {
+        let error = |kind| Err(ScriptError::new(location, kind));
+        match statement {
+            Statement::Commit(options) => {
+                let parents = self.head().cloned().into_iter().collect();
+                self.commit(location, options, parents, None)?;
+            }
+            Statement::Branch { name, color } => {
+                if self.branch_index(name).is_some() {
+                    return error(ScriptErrorKind::BranchExists(name.clone()));
+                }
+                let Some(head) = self.head().cloned() else {
+                    return error(ScriptErrorKind::NoCommit);
+                };
+                let count = self.graph.branches.len();
+                self.graph.branches.push(GraphBranch {
+                    name: name.clone(),
+                    color: color.unwrap_or(BRANCH_COLORS[count % BRANCH_COLORS.len()]),
+                    head: Some(head),
+                });
+                self.checked_out = count;
+            }
+            Statement::Checkout { name } => match self.branch_index(name) {
+                Some(index) => self.checked_out = index,
+                None => return error(ScriptErrorKind::UnknownBranch(name.clone())),
+            },
+            Statement::Merge { branch, options } => {
+                let Some(index) = self.branch_index(branch) else {
+                    return error(ScriptErrorKind::UnknownBranch(branch.clone()));
+                };
+                let Some(head) = self.head().cloned() else {
+                    return error(ScriptErrorKind::NoCommit);
+                };
+                let merged = self.graph.branches[index].head.clone();
+                match merged {
+                    Some(merged) if merged != head => {
+                        self.commit(location, options, vec![head, merged], None)?
+                    }
+                    _ => return error(ScriptErrorKind::NothingToMerge(branch.clone())),
+                }
+            }
+            Statement::CherryPick { id, options } => {
+                if self.graph.commit(id).is_none() {
+                    return error(ScriptErrorKind::UnknownCommit(id.clone()));
+                }
+                let Some(head) = self.head().cloned() else {
+                    return error(ScriptErrorKind::NoCommit);
+                };
+                self.commit(location, options, vec![head], Some(id.clone()))?;
+            }
+            Statement::Tag { name } => {
+                let Some(head) = self.head().cloned() else {
+                    return error(ScriptErrorKind::NoCommit);
+                };
+                if !self.tags.insert(name.clone()) {
+                    return error(ScriptErrorKind::DuplicateTag(name.clone()));
+                }
+                if let Some(commit) = self.graph.commits.iter_mut().find(|c| c.id == head) {
+                    commit.tags.push(name.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn run(script: &Script) -> Result<GitGraph, ScriptError> {
+    let mut repository = Repository {
+        graph: GitGraph {
+            commits: Vec::new(),
+            branches: vec![GraphBranch {
+                name: MAIN.to_string(),
+                color: BRANCH_COLORS[0],
+                head: None,
+            }],
+        },
+        checked_out: 0,
+        tags: HashSet::new(),
+    };
+    for (i, statement) in script.statements.iter().enumerate() {
+        repository.run(script.location(i), statement)?;
+    }
+    Ok(repository.graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(source: &str) -> Result<GitGraph, ScriptError> {
+        source.parse::<Script>()?.graph()
+    }
+
+    fn error(source: &str) -> (usize, ScriptErrorKind) {
+        let error = graph(source).unwrap_err();
+        (error.location.line, error.kind)
+    }
+
+    #[test]
+    fn test_history() {
+        let graph = graph(
+            "commit id: a msg: root\n\
+             branch develop color: lime\n\
+             commit\n\
+             checkout main\n\
+             commit color: red\n\
+             merge develop\n\
+             tag v1\n\
+             cherry-pick id: a",
+        )
+        .unwrap();
+        let summary: Vec<_> = graph
+            .commits
+            .iter()
+            .map(|c| {
+                (
+                    c.id.as_str(),
+                    c.parents.join(","),
+                    c.branch.as_str(),
+                    c.color,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("a", "".to_string(), "main", Color::Sky),
+                ("c2", "a".to_string(), "develop", Color::Lime),
+                ("c3", "a".to_string(), "main", Color::Red),
+                ("c4", "c3,c2".to_string(), "main", Color::Sky),
+                ("c5", "c4".to_string(), "main", Color::Sky),
+            ]
+        );
+        assert_eq!(graph.commit("c4").unwrap().tags, ["v1"]);
+        assert_eq!(
+            graph.commit("c5").unwrap().cherry_pick.as_deref(),
+            Some("a")
+        );
+        assert_eq!(graph.branch("main").unwrap().head.as_deref(), Some("c5"));
+        assert_eq!(graph.branch("develop").unwrap().head.as_deref(), Some("c2"));
+    }
+
+    #[test]
+    fn test_generated_ids_skip_taken_ones() {
+        let graph = graph("commit id: c2\ncommit").unwrap();
+        let ids: Vec<_> = graph.commits.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["c2", "c3"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error("branch b"), (1, ScriptErrorKind::NoCommit));
+        assert_eq!(
+            error("commit\n\ncheckout b"),
+            (3, ScriptErrorKind::UnknownBranch("b".to_string()))
+        );
+        assert_eq!(
+            error("commit\nbranch main"),
+            (2, ScriptErrorKind::BranchExists("main".to_string()))
+        );
+        assert_eq!(
+            error("commit id: a\ncommit id: a"),
+            (2, ScriptErrorKind::DuplicateId("a".to_string()))
+        );
+        assert_eq!(
+            error("commit\nbranch b\ncheckout main\nmerge b"),
+            (4, ScriptErrorKind::NothingToMerge("b".to_string()))
+        );
+        assert_eq!(
+            error("commit\ncherry-pick id: x"),
+            (2, ScriptErrorKind::UnknownCommit("x".to_string()))
+        );
+        assert_eq!(
+            error("commit\ntag v1\ntag v1"),
+            (3, ScriptErrorKind::DuplicateTag("v1".to_string()))
+        );
+    }
+}
@@ -0,0 +1,711 @@
+//! Reading and writing Mermaid `gitGraph` diagrams.
+//!
+//! [`import`] translates a Mermaid definition into a [`Script`] and draws it
+//! like any other script. Mermaid's `type:` becomes a [`CommitKind`],
+//! `tag:` options become tags and `order:` of branches is left to the
+//! layout.
+//!
+//! [`export`] works on the shapes alone, so it also picks up diagrams drawn
+//! or touched up by hand. Commits are the nodes the scripts and the git
+//! importer draw, connected by arrows, with their message or id next to them
+//! and branch names and tags stacked on the other side. The history must be
+//! one Mermaid can replay: every commit follows the newest commit of its
+//! branch and branches start at the newest commit of another branch. Shapes
+//! that aren't part of such a history are reported instead.
+
+use std::collections::HashSet;
+
+use super::parse::{self, tokenize, Token, Tokens};
+use super::shapes::{self, CHERRY_PICK, NODE_RADIUS};
+use super::{CommitKind, CommitOptions, Script, ScriptError, ScriptErrorKind, Statement};
+use crate::layout::Orientation;
+use crate::model::{Color, EllipseDetails, Guid, Model, ShapeConfig, ShapeDetails, Thickness};
+use crate::types::PointGrid;
+
+/// Name of the branch checked out at the start, unless configured otherwise.
+const MAIN: &str = "main";
+
+fn kind(name: &str) -> Option<CommitKind> {
+    match name {
+        "NORMAL" => Some(CommitKind::Normal),
+        "HIGHLIGHT" => Some(CommitKind::Highlight),
+        "REVERSE" => Some(CommitKind::Reverse),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: CommitKind) -> &'static str {
+    match kind {
+        CommitKind::Normal => "NORMAL",
+        CommitKind::Highlight => "HIGHLIGHT",
+        CommitKind::Reverse => "REVERSE",
+    }
+}
+
+/// The value of `mainBranchName` in an `%%{init: ...}%%` directive.
+fn main_branch_name(directive: &str) -> Option<String> {
+    let rest = &directive[directive.find("mainBranchName")? + "mainBranchName".len()..];
+    let rest = rest.trim_start_matches(['\'', '"', ':', ' ']);
+    let end = rest.find(['\'', '"'])?;
+    Some(rest[..end].to_string())
+}
+
+/// A Mermaid diagram as a script, with the default branch called `main`.
+struct Diagram {
+    script: Script,
+    /// What Mermaid calls the default branch.
+    main: String,
+}
+
+impl Diagram {
+    /// Swaps the configured name of the default branch with `main`, which is
+    /// what scripts call it.
+    fn swap(&self, name: String) -> String {
+        if name == self.main {
+            MAIN.to_string()
+        } else if name == MAIN {
+            self.main.clone()
+        } else {
+            name
+        }
+    }
+}
+
+fn parse(source: &str) -> Result<Diagram, ScriptError> {
+    let mut diagram = Diagram {
+        script: Script::default(),
+        main: MAIN.to_string(),
+    };
+    let mut header = false;
+    let mut front_matter = false;
+    for (i, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        // Front matter before the diagram and fences around it in Markdown.
+        if trimmed == "---" && !header {
+            front_matter = !front_matter;
+            continue;
+        }
+        if front_matter || trimmed.starts_with("```") {
+            continue;
+        }
+        if trimmed.starts_with("%%{") {
+            if let Some(main) = main_branch_name(trimmed) {
+                diagram.main = main;
+            }
+            continue;
+        }
+
+        let tokens = tokenize(line, i + 1)?;
+        if tokens.is_empty() {
+            continue;
+        }
+        let mut tokens = Tokens {
+            tokens,
+            next: 0,
+            line: i + 1,
+            end: line.chars().count() + 1,
+        };
+        let location = tokens.location();
+        let Some(Token::Word(command)) = tokens.peek().cloned() else {
+            return Err(tokens.error("a command"));
+        };
+        if !header {
+            if command != "gitGraph" {
+                return Err(tokens.error("gitGraph"));
+            }
+            tokens.next += 1;
+            diagram.script.orientation = match tokens.peek() {
+                Some(Token::Word(word)) if word == "LR" => Orientation::Horizontal,
+                // Bottom to top isn't supported, at least keep the axis.
+                Some(Token::Word(word)) if word == "TB" || word == "BT" => Orientation::Vertical,
+                Some(Token::Colon) | None => Orientation::Horizontal,
+                Some(_) => return Err(tokens.error("LR, TB or BT")),
+            };
+            if matches!(tokens.peek(), Some(Token::Word(_))) {
+                tokens.next += 1;
+            }
+            if tokens.peek() == Some(&Token::Colon) {
+                tokens.next += 1;
+            }
+            tokens.finish()?;
+            header = true;
+            continue;
+        }
+        tokens.next += 1;
+
+        let mut options = CommitOptions::default();
+        let mut tags = Vec::new();
+        let mut parse_options = |tokens: &mut Tokens, keys: &[&str]| {
+            while tokens.peek().is_some() {
+                let (key, key_location) = tokens.key(keys)?;
+                let value_location = tokens.location();
+                let value = tokens.value("a value")?;
+                let repeated = match key.as_str() {
+                    "id" => options.id.replace(value).is_some(),
+                    "msg" => options.message.replace(value).is_some(),
+                    "type" => {
+                        let Some(kind) = kind(&value) else {
+                            return Err(ScriptError::new(
+                                value_location,
+                                ScriptErrorKind::UnknownType(value),
+                            ));
+                        };
+                        options.kind.replace(kind).is_some()
+                    }
+                    "tag" => {
+                        tags.push((key_location, value));
+                        false
+                    }
+                    // `order:` of branches and `parent:` of cherry-picks.
+                    _ => false,
+                };
+                if repeated {
+                    return Err(ScriptError::new(
+                        key_location,
+                        ScriptErrorKind::RepeatedOption(key),
+                    ));
+                }
+            }
+            Ok(())
+        };
+
+        let statement = match command.as_str() {
+            "commit" => {
+                parse_options(&mut tokens, &["id", "msg", "tag", "type"])?;
+                Statement::Commit(options)
+            }
+            "branch" => {
+                let name = diagram.swap(tokens.value("a branch name")?);
+                parse_options(&mut tokens, &["order"])?;
+                Statement::Branch { name, color: None }
+            }
+            "checkout" | "switch" => {
+                let name = diagram.swap(tokens.value("a branch name")?);
+                tokens.finish()?;
+                Statement::Checkout { name }
+            }
+            "merge" => {
+                let branch = diagram.swap(tokens.value("a branch name")?);
+                parse_options(&mut tokens, &["id", "tag", "type"])?;
+                Statement::Merge { branch, options }
+            }
+            "cherry-pick" => {
+                parse_options(&mut tokens, &["id", "parent", "tag"])?;
+                let Some(id) = options.id.take() else {
+                    return Err(tokens.error("id: of the commit to pick"));
+                };
+                Statement::CherryPick { id, options }
+            }
+            _ => {
+                return Err(ScriptError::new(
+                    location,
+                    ScriptErrorKind::UnknownCommand(command),
+                ))
+            }
+        };
+        diagram.script.statements.push(statement);
+        diagram.script.locations.push(location);
+        for (location, name) in tags {
+            diagram.script.statements.push(Statement::Tag { name });
+            diagram.script.locations.push(location);
+        }
+    }
+    Ok(diagram)
+}
+
+/// Adds the Mermaid `gitGraph` in `source` to `model` as one undo step, with
+/// the first commit centered at `origin`.
+///
+/// `source` may be a whole Markdown code block, including the fences.
+/// Returns the guids of the added shapes.
+pub fn import(
+    source: &str,
+    model: &mut Model,
+    origin: PointGrid,
+) -> Result<Vec<Guid>, ScriptError> {
+    let diagram = parse(source)?;
+    let mut graph = diagram.script.graph()?;
+    for branch in &mut graph.branches {
+        branch.name = diagram.swap(std::mem::take(&mut branch.name));
+    }
+    for commit in &mut graph.commits {
+        commit.branch = diagram.swap(std::mem::take(&mut commit.branch));
+    }
+    let events = shapes::events(&graph, diagram.script.orientation, origin);
+    Ok(model.process_events(events))
+}
+
+/// The result of [`export`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Export {
+    /// The diagram as a Mermaid `gitGraph` definition.
+    pub mermaid: String,
+    /// Shapes that aren't part of the diagram, in order.
+    pub skipped: Vec<Guid>,
+}
+
+/// Smallest and largest corner of a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bounds {
+    min: PointGrid,
+    max: PointGrid,
+}
+
+impl Bounds {
+    fn new(config: &ShapeConfig) -> Self {
+        Bounds {
+            min: PointGrid {
+                x: config.start.x.min(config.end.x),
+                y: config.start.y.min(config.end.y),
+            },
+            max: PointGrid {
+                x: config.start.x.max(config.end.x),
+                y: config.start.y.max(config.end.y),
+            },
+        }
+    }
+
+    fn center(&self) -> PointGrid {
+        PointGrid {
+            x: (self.min.x + self.max.x) / 2,
+            y: (self.min.y + self.max.y) / 2,
+        }
+    }
+
+    /// Whether `point` is within `margin` grid steps of the bounds.
+    fn reaches(&self, point: PointGrid, margin: i32) -> bool {
+        self.min.x - margin <= point.x
+            && point.x <= self.max.x + margin
+            && self.min.y - margin <= point.y
+            && point.y <= self.max.y + margin
+    }
+}
+
+/// A branch name or tag next to a commit.
+struct Label {
+    name: String,
+    tag: bool,
+    guids: [Guid; 2],
+}
+
+struct Commit {
+    guid: Guid,
+    bounds: Bounds,
+    kind: CommitKind,
+    message: Option<(Guid, String)>,
+    /// Nearest first.
+    labels: Vec<(i32, Label)>,
+    /// Parents and the arrows to them, in order of the arrows.
+    parents: Vec<(usize, Guid)>,
+}
+
+/// The shapes of `model` that look like a commit graph.
+fn recognize(model: &Model) -> (Vec<Commit>, Orientation) {
+    let mut shapes: Vec<(Guid, &ShapeConfig)> = model
+        .shapes()
+        .map(|(guid, config)| (*guid, config))
+        .collect();
+    shapes.sort_by_key(|(guid, _)| *guid);
+    let size = 2 * NODE_RADIUS;
+
+    let mut commits: Vec<Commit> = Vec::new();
+    let mut rects = Vec::new();
+    let mut texts = Vec::new();
+    for &(guid, config) in &shapes {
+        let bounds = Bounds::new(config);
+        let node = bounds.max.x - bounds.min.x == size && bounds.max.y - bounds.min.y == size;
+        match &config.details {
+            ShapeDetails::Ellipse(EllipseDetails { background, .. }) if node => {
+                let kind = if config.options.thickness == Thickness::Thick {
+                    CommitKind::Highlight
+                } else if *background == Color::None && config.options.stroke_color != Color::None {
+                    CommitKind::Reverse
+                } else {
+                    CommitKind::Normal
+                };
+                commits.push(Commit {
+                    guid,
+                    bounds,
+                    kind,
+                    message: None,
+                    labels: Vec::new(),
+                    parents: Vec::new(),
+                });
+            }
+            ShapeDetails::Rect(rect) => rects.push((guid, bounds, rect.background)),
+            ShapeDetails::Text(text) => texts.push((guid, bounds, text.content.clone())),
+            _ => {}
+        }
+    }
+
+    // Arrows connect the nodes they start and end at.
+    let mut edges = Vec::new();
+    for &(guid, config) in &shapes {
+        if !matches!(config.details, ShapeDetails::Arrow(_)) {
+            continue;
+        }
+        let node = |point| {
+            commits
+                .iter()
+                .position(|commit| commit.bounds.reaches(point, 1))
+        };
+        if let (Some(a), Some(b)) = (node(config.start), node(config.end)) {
+            if a != b {
+                edges.push((guid, a, b));
+            }
+        }
+    }
+    // Commits on the same lane line up along the time axis, lanes can be
+    // further apart than commits.
+    let (mut horizontal, mut vertical) = (0, 0);
+    for &(_, a, b) in &edges {
+        let (a, b) = (commits[a].bounds.center(), commits[b].bounds.center());
+        if a.y == b.y {
+            horizontal += 1;
+        } else if a.x == b.x {
+            vertical += 1;
+        }
+    }
+    let orientation = if vertical > horizontal {
+        Orientation::Vertical
+    } else {
+        Orientation::Horizontal
+    };
+    // Position along the time axis and across it.
+    let along = |p: PointGrid| match orientation {
+        Orientation::Horizontal => (p.x, p.y),
+        Orientation::Vertical => (p.y, p.x),
+    };
+    let along_bounds = |b: &Bounds| match orientation {
+        Orientation::Horizontal => (b.min.x, b.max.x, b.min.y, b.max.y),
+        Orientation::Vertical => (b.min.y, b.max.y, b.min.x, b.max.x),
+    };
+
+    for (guid, a, b) in edges {
+        let (from, to) = (
+            along(commits[a].bounds.center()).0,
+            along(commits[b].bounds.center()).0,
+        );
+        // The older commit is the parent, whichever way the arrow points.
+        match from.cmp(&to) {
+            std::cmp::Ordering::Less => commits[b].parents.push((a, guid)),
+            std::cmp::Ordering::Greater => commits[a].parents.push((b, guid)),
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+
+    // Labels are a rect with a text of the same size on the side of the node
+    // facing away from its message.
+    let mut in_label = HashSet::new();
+    for &(rect, bounds, background) in &rects {
+        let Some((text, _, name)) = texts.iter().find(|(_, b, _)| *b == bounds) else {
+            continue;
+        };
+        let (low, high, near, far) = along_bounds(&bounds);
+        let thickness = far - near;
+        let nearest = commits
+            .iter()
+            .enumerate()
+            .filter_map(|(i, commit)| {
+                let (center, _) = along(commit.bounds.center());
+                let (_, _, near, _) = along_bounds(&commit.bounds);
+                let distance = near - far;
+                (low <= center && center <= high && 0 <= distance && distance <= 8 * thickness)
+                    .then_some((distance, i))
+            })
+            .min();
+        if let Some((distance, i)) = nearest {
+            in_label.insert(*text);
+            commits[i].labels.push((
+                distance,
+                Label {
+                    name: name.clone(),
+                    tag: background == Color::None,
+                    guids: [rect, *text],
+                },
+            ));
+        }
+    }
+    for commit in &mut commits {
+        commit.labels.sort_by_key(|(distance, _)| *distance);
+    }
+
+    for (guid, bounds, content) in texts {
+        if in_label.contains(&guid) {
+            continue;
+        }
+        let (low, high, near, _) = along_bounds(&bounds);
+        let message_of = commits.iter().position(|commit| {
+            let (center, _) = along(commit.bounds.center());
+            let (_, _, _, far) = along_bounds(&commit.bounds);
+            near == far && low <= center && center <= high
+        });
+        if let Some(i) = message_of {
+            if commits[i].message.is_none() {
+                commits[i].message = Some((guid, content));
+            }
+        }
+    }
+
+    (commits, orientation)
+}
+
+/// A Mermaid string.
+fn string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'").replace('\n', " "))
+}
+
+/// Writes the commit graph drawn in `model` as a Mermaid `gitGraph`.
+pub fn export(model: &Model) -> Export {
+    let (commits, orientation) = recognize(model);
+    let along = |commit: &Commit| {
+        let center = commit.bounds.center();
+        match orientation {
+            Orientation::Horizontal => (center.x, center.y),
+            Orientation::Vertical => (center.y, center.x),
+        }
+    };
+    let mut order: Vec<usize> = (0..commits.len()).collect();
+    order.sort_by_key(|&i| (along(&commits[i]), commits[i].guid));
+
+    let mut lanes: Vec<i32> = commits.iter().map(|c| along(c).1).collect();
+    lanes.sort_unstable();
+    lanes.dedup();
+    let lane_of: Vec<usize> = commits
+        .iter()
+        .map(|c| lanes.binary_search(&along(c).1).unwrap_or_default())
+        .collect();
+    let main_lane = order.first().map(|&i| lane_of[i]).unwrap_or_default();
+
+    // Lanes are named after the first branch label at their newest commit.
+    let mut names: Vec<Option<String>> = vec![None; lanes.len()];
+    let mut name_labels: Vec<Option<[Guid; 2]>> = vec![None; lanes.len()];
+    for &i in order.iter().rev() {
+        let lane = lane_of[i];
+        if names[lane].is_some() {
+            continue;
+        }
+        if let Some((_, label)) = commits[i].labels.iter().find(|(_, label)| !label.tag) {
+            names[lane] = Some(label.name.clone());
+            name_labels[lane] = Some(label.guids);
+        }
+    }
+    let names: Vec<String> = names
+        .into_iter()
+        .enumerate()
+        .map(|(lane, name)| match name {
+            Some(name) => name,
+            None if lane == main_lane => MAIN.to_string(),
+            None => format!("lane-{}", lane + 1),
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    if names[main_lane] != MAIN {
+        lines.push(format!(
+            "%%{{init: {{'gitGraph': {{'mainBranchName': '{}'}}}}}}%%",
+            names[main_lane]
+        ));
+    }
+    lines.push(match orientation {
+        Orientation::Horizontal => "gitGraph LR:".to_string(),
+        Orientation::Vertical => "gitGraph TB:".to_string(),
+    });
+
+    let mut used = HashSet::new();
+    let mut heads: Vec<Option<usize>> = vec![None; lanes.len()];
+    let mut created = vec![false; lanes.len()];
+    created[main_lane] = true;
+    let mut current = main_lane;
+    let mut ids = HashSet::new();
+    let mut any = false;
+    for &i in &order {
+        let commit = &commits[i];
+        let lane = lane_of[i];
+        let mut parents = commit.parents.clone();
+        parents.sort_by_key(|&(p, _)| (lane_of[p] != lane, along(&commits[p])));
+        let is_head = |p: usize| heads[lane_of[p]] == Some(p);
+        let same_lane = |p: usize| lane_of[p] == lane;
+
+        let statement = match parents.as_slice() {
+            [] if !any => Some("commit".to_string()),
+            [(p, _)] if same_lane(*p) && is_head(*p) => {
+                let picked = commit
+                    .message
+                    .as_ref()
+                    .and_then(|(_, message)| message.strip_prefix(CHERRY_PICK))
+                    .filter(|picked| ids.contains(*picked));
+                Some(match picked {
+                    Some(picked) => format!("cherry-pick id: {}", string(picked)),
+                    None => "commit".to_string(),
+                })
+            }
+            [(p, _)] if !same_lane(*p) && !created[lane] && is_head(*p) => {
+                if current != lane_of[*p] {
+                    lines.push(format!("    checkout {}", parse::name(&names[lane_of[*p]])));
+                }
+                lines.push(format!("    branch {}", parse::name(&names[lane])));
+                created[lane] = true;
+                current = lane;
+                Some("commit".to_string())
+            }
+            [(p, _), (q, _)] if same_lane(*p) && !same_lane(*q) && is_head(*p) && is_head(*q) => {
+                Some(format!("merge {}", parse::name(&names[lane_of[*q]])))
+            }
+            _ => None,
+        };
+        let Some(mut statement) = statement else {
+            continue;
+        };
+        if current != lane {
+            lines.push(format!("    checkout {}", parse::name(&names[lane])));
+            current = lane;
+        }
+
+        let cherry_pick = statement.starts_with("cherry-pick");
+        if let Some((guid, message)) = &commit.message {
+            if cherry_pick {
+                used.insert(*guid);
+            } else if ids.insert(message.clone()) {
+                statement.push_str(&format!(" id: {}", string(message)));
+                used.insert(*guid);
+            }
+        }
+        if commit.kind != CommitKind::Normal && !cherry_pick {
+            statement.push_str(&format!(" type: {}", kind_name(commit.kind)));
+        }
+        for (_, label) in commit.labels.iter().filter(|(_, label)| label.tag) {
+            statement.push_str(&format!(" tag: {}", string(&label.name)));
+            used.extend(label.guids);
+        }
+        lines.push(format!("    {}", statement));
+
+        used.insert(commit.guid);
+        used.extend(parents.iter().map(|(_, arrow)| *arrow));
+        heads[lane] = Some(i);
+        any = true;
+    }
+    for (lane, guids) in name_labels.into_iter().enumerate() {
+        if let (Some(guids), Some(head)) = (guids, heads[lane]) {
+            if commits[head].labels.iter().any(|(_, l)| l.guids == guids) {
+                used.extend(guids);
+            }
+        }
+    }
+
+    let mut skipped: Vec<Guid> = model
+        .shapes()
+        .map(|(guid, _)| *guid)
+        .filter(|guid| !used.contains(guid))
+        .collect();
+    skipped.sort_unstable();
+    lines.push(String::new());
+    Export {
+        mermaid: lines.join("\n"),
+        skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitgraph::Location;
+
+    #[test]
+    fn test_main_branch_name() {
+        assert_eq!(
+            main_branch_name("%%{init: { 'gitGraph': {'mainBranchName': 'master'}} }%%"),
+            Some("master".to_string())
+        );
+        assert_eq!(
+            main_branch_name(r#"%%{init: {"gitGraph": {"mainBranchName": "trunk"}}}%%"#),
+            Some("trunk".to_string())
+        );
+        assert_eq!(main_branch_name("%%{init: {'theme': 'base'}}%%"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let diagram = parse(
+            "```mermaid\n\
+             ---\n\
+             title: Example\n\
+             ---\n\
+             gitGraph TB:\n\
+             \x20  commit id: \"Alpha\" type: HIGHLIGHT tag: \"v1\" tag: v1.0\n\
+             \x20  branch develop order: 2\n\
+             \x20  switch develop\n\
+             \x20  merge main type: REVERSE\n\
+             \x20  cherry-pick id: \"Alpha\" parent: \"x\"\n\
+             ```",
+        )
+        .unwrap();
+        assert_eq!(diagram.script.orientation, Orientation::Vertical);
+        assert_eq!(
+            diagram.script.statements,
+            [
+                Statement::Commit(CommitOptions {
+                    id: Some("Alpha".to_string()),
+                    kind: Some(CommitKind::Highlight),
+                    ..Default::default()
+                }),
+                Statement::Tag {
+                    name: "v1".to_string()
+                },
+                Statement::Tag {
+                    name: "v1.0".to_string()
+                },
+                Statement::Branch {
+                    name: "develop".to_string(),
+                    color: None
+                },
+                Statement::Checkout {
+                    name: "develop".to_string()
+                },
+                Statement::Merge {
+                    branch: "main".to_string(),
+                    options: CommitOptions {
+                        kind: Some(CommitKind::Reverse),
+                        ..Default::default()
+                    }
+                },
+                Statement::CherryPick {
+                    id: "Alpha".to_string(),
+                    options: CommitOptions::default()
+                },
+            ]
+        );
+        assert_eq!(diagram.script.locations[4], Location { line: 8, column: 4 });
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source: &str| {
+            let error = parse(source).err().unwrap();
+            (error.location.line, error.location.column, error.kind)
+        };
+        assert_eq!(
+            error("commit"),
+            (
+                1,
+                1,
+                ScriptErrorKind::Expected {
+                    expected: "gitGraph",
+                    found: "commit".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            error("gitGraph\n  commit type: BOLD"),
+            (2, 16, ScriptErrorKind::UnknownType("BOLD".to_string()))
+        );
+        assert_eq!(
+            error("gitGraph\n  commit color: red"),
+            (2, 10, ScriptErrorKind::UnknownOption("color".to_string()))
+        );
+        assert_eq!(
+            error("gitGraph\n  reset HEAD"),
+            (2, 3, ScriptErrorKind::UnknownCommand("reset".to_string()))
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! A small language for drawing branching histories by hand.
+//!
+//! A script lists what happens to a repository, one statement per line:
+//!
+//! ```text
+//! gitGraph LR:
+//! commit msg: "Initial commit"
+//! branch develop color: amber
+//! commit id: "feature"
+//! checkout main
+//! merge develop msg: "Release"
+//! tag v1.0
+//! branch hotfix
+//! cherry-pick id: "feature" color: rose
+//! ```
+//!
+//! * `gitGraph` optionally starts the script, `LR` lays time out from left to
+//!   right and `TB` from top to bottom.
+//! * `commit` adds a commit to the checked out branch, `main` at first.
+//! * `branch <name>` starts a branch at the checked out commit and checks it
+//!   out.
+//! * `checkout <name>` switches to an existing branch.
+//! * `merge <name>` adds a commit merging the branch into the checked out
+//!   one.
+//! * `cherry-pick id: <id>` copies the commit `id` onto the checked out
+//!   branch.
+//! * `tag <name>` tags the checked out commit.
+//!
+//! Commits take the options `id:`, `msg:`, `type:` and `color:`, where the
+//! type is `normal`, `highlight` or `reverse`. Branches take `color:`. Names
+//! and values are single words or double quoted strings, colors are the names
+//! of [`Color`]. `%%` starts a comment.
+//!
+//! [`Script`] is the parsed form. It prints back as a script that parses to
+//! the same statements, so diagrams can be kept and regenerated as text.
+//! [`mermaid`] reads and writes the Mermaid flavor of the language.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::layout::Orientation;
+use crate::model::{Color, Event, Guid, Model};
+use crate::types::PointGrid;
+
+mod graph;
+pub mod mermaid;
+mod parse;
+mod shapes;
+
+pub use graph::{GitGraph, GraphBranch, GraphCommit};
+
+/// How a commit is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitKind {
+    #[default]
+    Normal,
+    /// Stands out with a thick outline.
+    Highlight,
+    /// Undoes another commit, drawn hollow.
+    Reverse,
+}
+
+impl CommitKind {
+    pub const ALL: [CommitKind; 3] = [
+        CommitKind::Normal,
+        CommitKind::Highlight,
+        CommitKind::Reverse,
+    ];
+
+    /// Lowercase name, as used in scripts.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommitKind::Normal => "normal",
+            CommitKind::Highlight => "highlight",
+            CommitKind::Reverse => "reverse",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<CommitKind> {
+        CommitKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// Options of statements adding a commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    pub id: Option<String>,
+    pub message: Option<String>,
+    pub kind: Option<CommitKind>,
+    pub color: Option<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Commit(CommitOptions),
+    Branch {
+        name: String,
+        color: Option<Color>,
+    },
+    Checkout {
+        name: String,
+    },
+    Merge {
+        branch: String,
+        options: CommitOptions,
+    },
+    /// Copies the commit `id`, `options.id` is never set.
+    CherryPick {
+        id: String,
+        options: CommitOptions,
+    },
+    Tag {
+        name: String,
+    },
+}
+
+/// Line and column of a statement or token, both starting at 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A parsed script.
+///
+/// Scripts are equal if their orientation and statements are, wherever the
+/// statements were written.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    pub orientation: Orientation,
+    pub statements: Vec<Statement>,
+    /// Where every statement starts, for errors found after parsing.
+    locations: Vec<Location>,
+}
+
+impl PartialEq for Script {
+    fn eq(&self, other: &Self) -> bool {
+        self.orientation == other.orientation && self.statements == other.statements
+    }
+}
+
+impl Script {
+    pub fn new(orientation: Orientation, statements: Vec<Statement>) -> Self {
+        Self {
+            orientation,
+            statements,
+            locations: Vec::new(),
+        }
+    }
+
+    /// Where statement `index` starts; on its own line as printed if the
+    /// script wasn't parsed.
+    fn location(&self, index: usize) -> Location {
+        self.locations.get(index).copied().unwrap_or(Location {
+            line: index + 2,
+            column: 1,
+        })
+    }
+
+    /// Runs the statements.
+    pub fn graph(&self) -> Result<GitGraph, ScriptError> {
+        graph::run(self)
+    }
+
+    /// The shapes drawing the script, with the first commit centered at
+    /// `origin`.
+    pub fn events(&self, origin: PointGrid) -> Result<Vec<Event>, ScriptError> {
+        Ok(shapes::events(&self.graph()?, self.orientation, origin))
+    }
+}
+
+impl FromStr for Script {
+    type Err = ScriptError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        parse::parse(source)
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let orientation = match self.orientation {
+            Orientation::Horizontal => "LR",
+            Orientation::Vertical => "TB",
+        };
+        writeln!(f, "gitGraph {}:", orientation)?;
+        for statement in &self.statements {
+            match statement {
+                Statement::Commit(options) => write!(f, "commit{}", options)?,
+                Statement::Branch { name, color } => {
+                    write!(f, "branch {}", parse::name(name))?;
+                    if let Some(color) = color {
+                        write!(f, " color: {}", color.name())?;
+                    }
+                }
+                Statement::Checkout { name } => write!(f, "checkout {}", parse::name(name))?,
+                Statement::Merge { branch, options } => {
+                    write!(f, "merge {}{}", parse::name(branch), options)?
+                }
+                Statement::CherryPick { id, options } => {
+                    write!(f, "cherry-pick id: {}{}", parse::quote(id), options)?
+                }
+                Statement::Tag { name } => write!(f, "tag {}", parse::name(name))?,
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CommitOptions {
+    /// The options with a leading space each.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(id) = &self.id {
+            write!(f, " id: {}", parse::quote(id))?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, " msg: {}", parse::quote(message))?;
+        }
+        if let Some(kind) = &self.kind {
+            write!(f, " type: {}", kind.name())?;
+        }
+        if let Some(color) = &self.color {
+            write!(f, " color: {}", color.name())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptErrorKind {
+    UnexpectedCharacter(char),
+    UnterminatedString,
+    /// Something else than `expected` was `found`.
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+    UnknownCommand(String),
+    /// `gitGraph` after the first statement.
+    MisplacedHeader,
+    UnknownOption(String),
+    RepeatedOption(String),
+    UnknownColor(String),
+    UnknownType(String),
+    UnknownBranch(String),
+    BranchExists(String),
+    UnknownCommit(String),
+    DuplicateId(String),
+    DuplicateTag(String),
+    /// The statement needs a commit on the checked out branch.
+    NoCommit,
+    /// The merged branch has no commits the checked out one lacks.
+    NothingToMerge(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub location: Location,
+    pub kind: ScriptErrorKind,
+}
+
+impl ScriptError {
+    fn new(location: Location, kind: ScriptErrorKind) -> Self {
+        Self { location, kind }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: ",
+            self.location.line, self.location.column
+        )?;
+        match &self.kind {
+            ScriptErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            ScriptErrorKind::UnterminatedString => write!(f, "string is never closed"),
+            ScriptErrorKind::Expected { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            ScriptErrorKind::UnknownCommand(command) => write!(f, "unknown command {}", command),
+            ScriptErrorKind::MisplacedHeader => {
+                write!(f, "gitGraph must come before all other statements")
+            }
+            ScriptErrorKind::UnknownOption(option) => write!(f, "unknown option {}", option),
+            ScriptErrorKind::RepeatedOption(option) => write!(f, "option {} given twice", option),
+            ScriptErrorKind::UnknownColor(color) => write!(f, "unknown color {}", color),
+            ScriptErrorKind::UnknownType(kind) => write!(f, "unknown commit type {}", kind),
+            ScriptErrorKind::UnknownBranch(name) => write!(f, "no branch named {}", name),
+            ScriptErrorKind::BranchExists(name) => write!(f, "branch {} already exists", name),
+            ScriptErrorKind::UnknownCommit(id) => write!(f, "no commit with id {}", id),
+            ScriptErrorKind::DuplicateId(id) => write!(f, "commit id {} is already taken", id),
+            ScriptErrorKind::DuplicateTag(name) => write!(f, "tag {} already exists", name),
+            ScriptErrorKind::NoCommit => write!(f, "the checked out branch has no commits"),
+            ScriptErrorKind::NothingToMerge(name) => {
+                write!(f, "branch {} has nothing to merge", name)
+            }
+        }
+    }
+}
+
+impl Error for ScriptError {}
+
+/// Adds the diagram described by `source` to `model` as one undo step, with
+/// the first commit centered at `origin`.
+///
+/// Returns the guids of the added shapes.
+pub fn draw(source: &str, model: &mut Model, origin: PointGrid) -> Result<Vec<Guid>, ScriptError> {
+    let script: Script = source.parse()?;
+    Ok(model.process_events(script.events(origin)?))
+}
@@ -0,0 +1,455 @@
+use std::borrow::Cow;
+
+use super::{CommitKind, CommitOptions, Location, Script, ScriptError, ScriptErrorKind, Statement};
+use crate::layout::Orientation;
+use crate::model::Color;
+
+/// Options of statements adding a commit.
+const COMMIT_OPTIONS: &[&str] = &["id", "msg", "type", "color"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    Word(String),
+    String(String),
+    Colon,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(word) => word.clone(),
+            Token::String(string) => quote(string),
+            Token::Colon => "`:`".to_string(),
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/')
+}
+
+/// `name` as a word if it is one, quoted otherwise.
+pub(super) fn name(name: &str) -> Cow<'_, str> {
+    if !name.is_empty() && name.chars().all(is_word_char) {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(quote(name))
+    }
+}
+
+pub(super) fn quote(string: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Splits a line into tokens and the column each starts at.
+pub(super) fn tokenize(line: &str, number: usize) -> Result<Vec<(Token, usize)>, ScriptError> {
+    let at = |column| Location {
+        line: number,
+        column,
+    };
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '%' if chars.get(i + 1) == Some(&'%') => break,
+            ':' => {
+                tokens.push((Token::Colon, column));
+                i += 1;
+            }
+            '"' => {
+                let mut string = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(ScriptError::new(
+                                at(column),
+                                ScriptErrorKind::UnterminatedString,
+                            ))
+                        }
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('n') => string.push('\n'),
+                                Some(&c) => string.push(c),
+                                None => {
+                                    return Err(ScriptError::new(
+                                        at(column),
+                                        ScriptErrorKind::UnterminatedString,
+                                    ))
+                                }
+                            }
+                            i += 1;
+                        }
+                        Some(&c) => string.push(c),
+                    }
+                    i += 1;
+                }
+                tokens.push((Token::String(string), column));
+                i += 1;
+            }
+            c if is_word_char(c) => {
+                let start = i;
+                while i < chars.len() && is_word_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push((Token::Word(chars[start..i].iter().collect()), column));
+            }
+            c => {
+                return Err(ScriptError::new(
+                    at(column),
+                    ScriptErrorKind::UnexpectedCharacter(c),
+                ))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// The tokens of one statement.
+pub(super) struct Tokens {
+    pub tokens: Vec<(Token, usize)>,
+    pub next: usize,
+    pub line: usize,
+    /// Column after the last character of the line.
+    pub end: usize,
+}
+
+impl Tokens {
+    pub(super) fn location(&self) -> Location {
+        Location {
+            line: self.line,
+            column: self
+                .tokens
+                .get(self.next)
+                .map_or(self.end, |(_, column)| *column),
+        }
+    }
+
+    pub(super) fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    pub(super) fn error(&self, expected: &'static str) -> ScriptError {
+        let found = self
+            .peek()
+            .map_or("end of line".to_string(), Token::describe);
+        ScriptError::new(
+            self.location(),
+            ScriptErrorKind::Expected { expected, found },
+        )
+    }
+
+    /// A word or a string.
+    pub(super) fn value(&mut self, expected: &'static str) -> Result<String, ScriptError> {
+        match self.peek() {
+            Some(Token::Word(value) | Token::String(value)) => {
+                let value = value.clone();
+                self.next += 1;
+                Ok(value)
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    fn color(&mut self) -> Result<Color, ScriptError> {
+        let location = self.location();
+        let name = self.value("a color")?;
+        Color::from_name(&name)
+            .ok_or_else(|| ScriptError::new(location, ScriptErrorKind::UnknownColor(name)))
+    }
+
+    fn colon(&mut self) -> Result<(), ScriptError> {
+        match self.peek() {
+            Some(Token::Colon) => {
+                self.next += 1;
+                Ok(())
+            }
+            _ => Err(self.error("`:`")),
+        }
+    }
+
+    pub(super) fn finish(&self) -> Result<(), ScriptError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("end of line")),
+        }
+    }
+
+    /// The key of a `key: value` pair, where `keys` are the accepted keys,
+    /// and where it starts.
+    pub(super) fn key(&mut self, keys: &[&str]) -> Result<(String, Location), ScriptError> {
+        let location = self.location();
+        let Some(Token::Word(key)) = self.peek().cloned() else {
+            return Err(self.error("an option"));
+        };
+        if !keys.contains(&key.as_str()) {
+            return Err(ScriptError::new(
+                location,
+                ScriptErrorKind::UnknownOption(key),
+            ));
+        }
+        self.next += 1;
+        self.colon()?;
+        Ok((key, location))
+    }
+
+    fn kind(&mut self) -> Result<CommitKind, ScriptError> {
+        let location = self.location();
+        let name = self.value("a commit type")?;
+        CommitKind::from_name(&name)
+            .ok_or_else(|| ScriptError::new(location, ScriptErrorKind::UnknownType(name)))
+    }
+
+    /// `key: value` pairs up to the end of the line, where `keys` are the
+    /// accepted keys.
+    fn options(&mut self, keys: &[&str]) -> Result<CommitOptions, ScriptError> {
+        let mut options = CommitOptions::default();
+        while self.peek().is_some() {
+            let (key, location) = self.key(keys)?;
+            let repeated = match key.as_str() {
+                "id" => options.id.replace(self.value("an id")?).is_some(),
+                "msg" => options.message.replace(self.value("a message")?).is_some(),
+                "type" => options.kind.replace(self.kind()?).is_some(),
+                _ => options.color.replace(self.color()?).is_some(),
+            };
+            if repeated {
+                return Err(ScriptError::new(
+                    location,
+                    ScriptErrorKind::RepeatedOption(key),
+                ));
+            }
+        }
+        Ok(options)
+    }
+}
+
+pub(super) fn parse(source: &str) -> Result<Script, ScriptError> {
+    let mut script = Script::default();
+    let mut header = false;
+    for (i, line) in source.lines().enumerate() {
+        let tokens = tokenize(line, i + 1)?;
+        if tokens.is_empty() {
+            continue;
+        }
+        let mut tokens = Tokens {
+            tokens,
+            next: 0,
+            line: i + 1,
+            end: line.chars().count() + 1,
+        };
+        let location = tokens.location();
+        let Some(Token::Word(command)) = tokens.peek().cloned() else {
+            return Err(tokens.error("a command"));
+        };
+        tokens.next += 1;
+
+        let statement = match command.as_str() {
+            "gitGraph" => {
+                if header || !script.statements.is_empty() {
+                    return Err(ScriptError::new(location, ScriptErrorKind::MisplacedHeader));
+                }
+                header = true;
+                script.orientation = match tokens.peek() {
+                    Some(Token::Word(word)) if word == "LR" => Orientation::Horizontal,
+                    Some(Token::Word(word)) if word == "TB" => Orientation::Vertical,
+                    Some(Token::Colon) | None => Orientation::Horizontal,
+                    Some(_) => return Err(tokens.error("LR or TB")),
+                };
+                if matches!(tokens.peek(), Some(Token::Word(_))) {
+                    tokens.next += 1;
+                }
+                if tokens.peek() == Some(&Token::Colon) {
+                    tokens.next += 1;
+                }
+                tokens.finish()?;
+                continue;
+            }
+            "commit" => Statement::Commit(tokens.options(COMMIT_OPTIONS)?),
+            "branch" => {
+                let name = tokens.value("a branch name")?;
+                let color = tokens.options(&["color"])?.color;
+                Statement::Branch { name, color }
+            }
+            "checkout" => {
+                let name = tokens.value("a branch name")?;
+                tokens.finish()?;
+                Statement::Checkout { name }
+            }
+            "merge" => {
+                let branch = tokens.value("a branch name")?;
+                let options = tokens.options(COMMIT_OPTIONS)?;
+                Statement::Merge { branch, options }
+            }
+            "cherry-pick" => {
+                let mut options = tokens.options(COMMIT_OPTIONS)?;
+                let Some(id) = options.id.take() else {
+                    return Err(tokens.error("id: of the commit to pick"));
+                };
+                Statement::CherryPick { id, options }
+            }
+            "tag" => {
+                let name = tokens.value("a tag name")?;
+                tokens.finish()?;
+                Statement::Tag { name }
+            }
+            _ => {
+                return Err(ScriptError::new(
+                    location,
+                    ScriptErrorKind::UnknownCommand(command),
+                ))
+            }
+        };
+        script.statements.push(statement);
+        script.locations.push(location);
+    }
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> (usize, usize, ScriptErrorKind) {
+        let error = parse(source).unwrap_err();
+        (error.location.line, error.location.column, error.kind)
+    }
+
+    #[test]
+    fn test_parse() {
+        let script = parse(
+            "gitGraph TB:\n\
+             commit id: a msg: \"First \\\"one\\\"\" type: highlight %% the root\n\
+             \n\
+             branch \"feature/x\" color: amber\n\
+             checkout main\n\
+             merge feature/x color: orange\n\
+             cherry-pick id: \"a\" msg: picked\n\
+             tag v1.0",
+        )
+        .unwrap();
+        assert_eq!(script.orientation, Orientation::Vertical);
+        assert_eq!(
+            script.statements,
+            [
+                Statement::Commit(CommitOptions {
+                    id: Some("a".to_string()),
+                    message: Some("First \"one\"".to_string()),
+                    kind: Some(CommitKind::Highlight),
+                    color: None,
+                }),
+                Statement::Branch {
+                    name: "feature/x".to_string(),
+                    color: Some(Color::Amber),
+                },
+                Statement::Checkout {
+                    name: "main".to_string()
+                },
+                Statement::Merge {
+                    branch: "feature/x".to_string(),
+                    options: CommitOptions {
+                        color: Some(Color::Organge),
+                        ..Default::default()
+                    },
+                },
+                Statement::CherryPick {
+                    id: "a".to_string(),
+                    options: CommitOptions {
+                        message: Some("picked".to_string()),
+                        ..Default::default()
+                    },
+                },
+                Statement::Tag {
+                    name: "v1.0".to_string()
+                },
+            ]
+        );
+        assert_eq!(script.locations[2], Location { line: 5, column: 1 });
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = "commit\n\
+                      branch \"two words\" color: rose\n\
+                      commit msg: \"multi\\nline \\\\ text\"\n\
+                      checkout main\n\
+                      merge \"two words\" id: m\n\
+                      tag \"release 1\"";
+        let script = parse(source).unwrap();
+        let printed = script.to_string();
+        assert!(printed.starts_with("gitGraph LR:\n"));
+        assert_eq!(parse(&printed).unwrap(), script);
+        assert_eq!(parse(&printed).unwrap().to_string(), printed);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert_eq!(
+            error("commit\n  push"),
+            (2, 3, ScriptErrorKind::UnknownCommand("push".to_string()))
+        );
+        assert_eq!(
+            error("commit msg: \"open"),
+            (1, 13, ScriptErrorKind::UnterminatedString)
+        );
+        assert_eq!(
+            error("commit id a"),
+            (
+                1,
+                11,
+                ScriptErrorKind::Expected {
+                    expected: "`:`",
+                    found: "a".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            error("checkout"),
+            (
+                1,
+                9,
+                ScriptErrorKind::Expected {
+                    expected: "a branch name",
+                    found: "end of line".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            error("branch b tag: x"),
+            (1, 10, ScriptErrorKind::UnknownOption("tag".to_string()))
+        );
+        assert_eq!(
+            error("commit color: mauve"),
+            (1, 15, ScriptErrorKind::UnknownColor("mauve".to_string()))
+        );
+        assert_eq!(
+            error("commit type: merge"),
+            (1, 14, ScriptErrorKind::UnknownType("merge".to_string()))
+        );
+        assert_eq!(
+            error("commit id: a id: b"),
+            (1, 14, ScriptErrorKind::RepeatedOption("id".to_string()))
+        );
+        assert_eq!(
+            error("commit\ngitGraph"),
+            (2, 1, ScriptErrorKind::MisplacedHeader)
+        );
+        assert_eq!(
+            error("commit; commit"),
+            (1, 7, ScriptErrorKind::UnexpectedCharacter(';'))
+        );
+    }
+}
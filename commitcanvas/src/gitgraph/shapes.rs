@@ -0,0 +1,257 @@
+use super::{CommitKind, GitGraph};
+use crate::layout::{layout, Branch, Dag, LayoutConfig, Node, Orientation};
+use crate::model::{
+    ArrowDetails, Color, EllipseDetails, Event, FontSize, Options, RectDetails, ShapeConfig,
+    ShapeDetails, TextAlign, TextDetails, Thickness, VerticalAlign,
+};
+use crate::types::{PointGrid, VecGrid};
+
+/// Half the width and height of a commit node.
+pub(super) const NODE_RADIUS: i32 = 1;
+/// Width of commit messages and of labels.
+const LABEL_WIDTH: i32 = 8;
+/// Height of commit messages and of labels.
+const LABEL_HEIGHT: i32 = 2;
+/// Starts the message of cherry-picked commits without one, followed by the
+/// id of the picked commit.
+pub(super) const CHERRY_PICK: &str = "cherry-pick: ";
+
+fn config(orientation: Orientation) -> LayoutConfig {
+    match orientation {
+        // Messages go below the nodes, between the lanes.
+        Orientation::Horizontal => LayoutConfig {
+            orientation,
+            commit_spacing: LABEL_WIDTH + 2,
+            lane_spacing: 3 * LABEL_HEIGHT,
+        },
+        // Messages go right of the nodes, between the lanes.
+        Orientation::Vertical => LayoutConfig {
+            orientation,
+            commit_spacing: 2 * LABEL_HEIGHT,
+            lane_spacing: LABEL_WIDTH + 4,
+        },
+    }
+}
+
+fn add(start: PointGrid, end: PointGrid, details: ShapeDetails) -> Event {
+    Event::Add {
+        guid: None,
+        config: ShapeConfig {
+            start,
+            end,
+            details,
+            options: Options::default(),
+            seed: 0,
+        },
+    }
+}
+
+/// The end of a label or message starting at `start`.
+fn label_end(start: PointGrid) -> PointGrid {
+    start
+        + VecGrid {
+            x: LABEL_WIDTH,
+            y: LABEL_HEIGHT,
+        }
+}
+
+/// A line of text at `start`, the size of a label.
+fn text(start: PointGrid, content: &str) -> Event {
+    add(
+        start,
+        label_end(start),
+        ShapeDetails::Text(TextDetails {
+            content: content.to_string(),
+            font_size: FontSize::Small,
+            align: TextAlign::Center,
+            vertical_align: VerticalAlign::Middle,
+        }),
+    )
+}
+
+fn label(start: PointGrid, content: &str, background: Color) -> [Event; 2] {
+    [
+        add(
+            start,
+            label_end(start),
+            ShapeDetails::Rect(RectDetails {
+                background,
+                ..Default::default()
+            }),
+        ),
+        text(start, content),
+    ]
+}
+
+/// The shapes drawing `graph` with the first commit centered at `origin`.
+///
+/// Every commit is a circle in the color of the commit with its message next to
+/// it. Commits without a message show their id, or the picked commit for
+/// cherry-picks. Arrows point from parents to their children along the routes
+/// of the layout. Branch names and tags are stacked on the other side of the
+/// node they point at.
+pub(super) fn events(graph: &GitGraph, orientation: Orientation, origin: PointGrid) -> Vec<Event> {
+    let dag = Dag {
+        nodes: graph
+            .commits
+            .iter()
+            .map(|commit| Node {
+                id: commit.id.clone(),
+                parents: commit.parents.clone(),
+            })
+            .collect(),
+        branches: graph
+            .branches
+            .iter()
+            .filter_map(|branch| {
+                branch.head.as_ref().map(|head| Branch {
+                    name: branch.name.clone(),
+                    head: head.clone(),
+                })
+            })
+            .collect(),
+    };
+    // Scripts only ever refer to existing commits and can't create cycles.
+    let Ok(layout) = layout(&dag, &config(orientation)) else {
+        return Vec::new();
+    };
+    let offset = VecGrid {
+        x: origin.x,
+        y: origin.y,
+    };
+    let center = |id: &str| layout.node(id).map(|node| node.position + offset);
+    // One step along the time axis.
+    let along = match orientation {
+        Orientation::Horizontal => VecGrid { x: 1, y: 0 },
+        Orientation::Vertical => VecGrid { x: 0, y: 1 },
+    };
+    let scale = |v: VecGrid, by: i32| VecGrid {
+        x: v.x * by,
+        y: v.y * by,
+    };
+    let radius = VecGrid {
+        x: NODE_RADIUS,
+        y: NODE_RADIUS,
+    };
+
+    let mut events = Vec::new();
+    for commit in &graph.commits {
+        let Some(c) = center(&commit.id) else {
+            continue;
+        };
+        let (background, options) = match commit.kind {
+            CommitKind::Normal => (commit.color, Options::default()),
+            CommitKind::Highlight => (
+                commit.color,
+                Options {
+                    thickness: Thickness::Thick,
+                    ..Default::default()
+                },
+            ),
+            CommitKind::Reverse => (
+                Color::None,
+                Options {
+                    stroke_color: commit.color,
+                    ..Default::default()
+                },
+            ),
+        };
+        events.push(Event::Add {
+            guid: None,
+            config: ShapeConfig {
+                start: c + scale(radius, -1),
+                end: c + radius,
+                details: ShapeDetails::Ellipse(EllipseDetails {
+                    background,
+                    ..Default::default()
+                }),
+                options,
+                seed: 0,
+            },
+        });
+        let message_start = match orientation {
+            Orientation::Horizontal => {
+                c + VecGrid {
+                    x: -LABEL_WIDTH / 2,
+                    y: NODE_RADIUS,
+                }
+            }
+            Orientation::Vertical => {
+                c + VecGrid {
+                    x: NODE_RADIUS,
+                    y: -LABEL_HEIGHT / 2,
+                }
+            }
+        };
+        let message = match (&commit.message, &commit.cherry_pick) {
+            (Some(message), _) => message.clone(),
+            (None, Some(picked)) => format!("{}{}", CHERRY_PICK, picked),
+            (None, None) => commit.id.clone(),
+        };
+        events.push(text(message_start, &message));
+    }
+
+    for edge in &layout.edges {
+        let (Some(parent), Some(child)) = (center(&edge.parent), center(&edge.child)) else {
+            continue;
+        };
+        // The route of the layout, between the first and last point.
+        let waypoints = edge.points[1..edge.points.len() - 1]
+            .iter()
+            .map(|&point| point + offset)
+            .collect();
+        events.push(add(
+            parent + scale(along, NODE_RADIUS),
+            child + scale(along, -NODE_RADIUS),
+            ShapeDetails::Arrow(ArrowDetails {
+                waypoints,
+                ..Default::default()
+            }),
+        ));
+    }
+
+    let branches = graph.branches.iter().filter_map(|branch| {
+        branch
+            .head
+            .as_deref()
+            .map(|head| (head, branch.name.as_str(), branch.color))
+    });
+    let tags = graph.commits.iter().flat_map(|commit| {
+        commit
+            .tags
+            .iter()
+            .map(|tag| (commit.id.as_str(), tag.as_str(), Color::None))
+    });
+    let mut stacked: Vec<(&str, i32)> = Vec::new();
+    for (target, name, color) in branches.chain(tags) {
+        let Some(c) = center(target) else {
+            continue;
+        };
+        let level = match stacked.iter_mut().find(|(id, _)| *id == target) {
+            Some((_, level)) => {
+                *level += 1;
+                *level
+            }
+            None => {
+                stacked.push((target, 1));
+                1
+            }
+        };
+        let start = match orientation {
+            Orientation::Horizontal => {
+                c + VecGrid {
+                    x: -LABEL_WIDTH / 2,
+                    y: -NODE_RADIUS - level * LABEL_HEIGHT,
+                }
+            }
+            Orientation::Vertical => {
+                c + VecGrid {
+                    x: -NODE_RADIUS - level * LABEL_WIDTH,
+                    y: -LABEL_HEIGHT / 2,
+                }
+            }
+        };
+        events.extend(label(start, name, color));
+    }
+    events
+}
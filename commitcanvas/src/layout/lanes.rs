@@ -0,0 +1,187 @@
+use super::route::Route;
+use super::Graph;
+
+/// The lane of every node, before the lanes are ordered.
+pub(super) struct Lanes {
+    pub of_node: Vec<usize>,
+    /// Index of the first branch whose commits every lane holds.
+    pub branches: Vec<Option<usize>>,
+}
+
+/// Splits the nodes into chains, then puts the chains into lanes.
+///
+/// A chain is opened for every branch, then for every commit no chain holds
+/// yet, newest first. It takes the first parent chain from where it was
+/// opened up to the first commit another chain holds.
+///
+/// The first chain has the first lane to itself. Every other chain goes
+/// into the first lane free from where its first edge leaves its parent to
+/// where its last edge reaches a child, so a lane is used again once the
+/// chain in it has ended.
+pub(super) fn assign(graph: &Graph, steps: &[usize]) -> Lanes {
+    let count = steps.len();
+    let mut of_node = vec![usize::MAX; count];
+    let mut chain_branches: Vec<Option<usize>> = Vec::new();
+    let mut open = |start: usize, branch: Option<usize>| {
+        if of_node[start] != usize::MAX {
+            return;
+        }
+        let chain = chain_branches.len();
+        chain_branches.push(branch);
+        let mut node = Some(start);
+        while let Some(i) = node.filter(|&i| of_node[i] == usize::MAX) {
+            of_node[i] = chain;
+            node = graph.parents[i].first().copied();
+        }
+    };
+
+    for (branch, &head) in graph.heads.iter().enumerate() {
+        open(head, Some(branch));
+    }
+    let mut newest_first: Vec<usize> = (0..count).collect();
+    newest_first.sort_by_key(|&i| std::cmp::Reverse(steps[i]));
+    for node in newest_first {
+        open(node, None);
+    }
+
+    // The steps every chain spans with its nodes and edges.
+    let mut spans = vec![(usize::MAX, 0); chain_branches.len()];
+    for (node, parents) in graph.parents.iter().enumerate() {
+        let chain = of_node[node];
+        spans[chain].0 = spans[chain].0.min(steps[node]);
+        spans[chain].1 = spans[chain].1.max(steps[node]);
+        for &parent in parents {
+            spans[chain].0 = spans[chain].0.min(steps[parent]);
+            let parent_chain = of_node[parent];
+            spans[parent_chain].1 = spans[parent_chain].1.max(steps[node]);
+        }
+    }
+
+    let mut lane_of_chain = vec![0; chain_branches.len()];
+    // The step every lane is used up to.
+    let mut ends: Vec<usize> = Vec::new();
+    let mut branches: Vec<Option<usize>> = Vec::new();
+    if !chain_branches.is_empty() {
+        ends.push(usize::MAX);
+        branches.push(chain_branches[0]);
+    }
+    let mut by_start: Vec<usize> = (1..chain_branches.len()).collect();
+    by_start.sort_by_key(|&chain| spans[chain].0);
+    for chain in by_start {
+        let (start, end) = spans[chain];
+        let lane = match (1..ends.len()).find(|&lane| ends[lane] <= start) {
+            Some(lane) => lane,
+            None => {
+                ends.push(0);
+                branches.push(None);
+                ends.len() - 1
+            }
+        };
+        ends[lane] = end;
+        branches[lane] = branches[lane].or(chain_branches[chain]);
+        lane_of_chain[chain] = lane;
+    }
+
+    Lanes {
+        of_node: of_node.iter().map(|&chain| lane_of_chain[chain]).collect(),
+        branches,
+    }
+}
+
+/// Where an ordering of the lanes is worse than another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Cost {
+    /// Times an edge passes between two lanes over a third lane in use.
+    crossings: usize,
+    /// Lanes passed by all edges together.
+    length: usize,
+}
+
+/// Whether `step` lies in one of the sorted, disjoint `spans`.
+fn in_use(spans: &[(i32, i32)], step: i32) -> bool {
+    let i = spans.partition_point(|&(_, end)| end < step);
+    spans.get(i).is_some_and(|&(start, _)| start <= step)
+}
+
+/// Orders the lanes so edges cross as few other lanes as possible, keeping
+/// the first lane in place. Returns the position of every lane.
+///
+/// Lanes are inserted one at a time in the order they were opened, each at
+/// the position that costs the least among the lanes inserted so far. On a
+/// tie the lane goes as far out as it can.
+pub(super) fn order(lanes: &Lanes, routes: &[Route], steps: &[usize]) -> Vec<usize> {
+    let count = lanes.branches.len();
+    // Half steps along the time axis each lane is in use, from nodes and the
+    // parts of edges running along it.
+    let mut used: Vec<Vec<(i32, i32)>> = vec![Vec::new(); count];
+    // Half step and lanes of every part of an edge running between lanes.
+    let mut crossing = Vec::new();
+    for route in routes {
+        for pair in route.points.windows(2) {
+            let ((from, a), (to, b)) = (pair[0], pair[1]);
+            if a == b {
+                used[a].push((from.min(to), from.max(to)));
+            } else {
+                crossing.push((from, a, b));
+            }
+        }
+    }
+    for (node, &lane) in lanes.of_node.iter().enumerate() {
+        let step = 2 * steps[node] as i32;
+        used[lane].push((step, step));
+    }
+    for spans in &mut used {
+        spans.sort_unstable();
+        let mut merged: Vec<(i32, i32)> = Vec::with_capacity(spans.len());
+        for &(start, end) in spans.iter() {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        *spans = merged;
+    }
+
+    // The cost of the edges between the lanes of `sequence`.
+    let cost = |sequence: &[usize]| {
+        let mut position = vec![usize::MAX; count];
+        for (i, &lane) in sequence.iter().enumerate() {
+            position[lane] = i;
+        }
+        let mut cost = Cost {
+            crossings: 0,
+            length: 0,
+        };
+        for &(step, a, b) in &crossing {
+            if position[a] == usize::MAX || position[b] == usize::MAX {
+                continue;
+            }
+            let (low, high) = (position[a].min(position[b]), position[a].max(position[b]));
+            cost.length += high - low;
+            cost.crossings += sequence[low + 1..high]
+                .iter()
+                .filter(|&&lane| in_use(&used[lane], step))
+                .count();
+        }
+        cost
+    };
+
+    let mut sequence: Vec<usize> = (0..count.min(1)).collect();
+    for lane in 1..count {
+        let at = (1..=sequence.len())
+            .rev()
+            .min_by_key(|&at| {
+                let mut candidate = sequence.clone();
+                candidate.insert(at, lane);
+                cost(&candidate)
+            })
+            .unwrap_or(sequence.len());
+        sequence.insert(at, lane);
+    }
+
+    let mut position = vec![0; count];
+    for (i, &lane) in sequence.iter().enumerate() {
+        position[lane] = i;
+    }
+    position
+}
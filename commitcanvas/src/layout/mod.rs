@@ -0,0 +1,588 @@
+//! Places a commit graph on the grid.
+//!
+//! Commits are ordered topologically along the time axis, one commit per
+//! step, so every parent comes before its children. Each branch gets a lane
+//! holding the commits reachable from its head through first parents;
+//! commits no branch claims share lanes the same way. A lane is used again
+//! once the commits in it and their edges have ended, so a graph is only as
+//! wide as the branches alive at the same time. The lanes are then ordered
+//! to keep edges from crossing other lanes, with the first branch as the
+//! main line in the first lane.
+//!
+//! Edges within a lane are straight. Edges between lanes bend once between
+//! two steps: a fork leaves its parent and runs in the lane of the new
+//! branch, a merge runs in the lane of the merged branch until just before
+//! the merge commit.
+//!
+//! The result is the same for the same input, positions are grid
+//! coordinates as used by shapes.
+
+use std::collections::{BinaryHeap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+use crate::types::PointGrid;
+
+mod lanes;
+mod route;
+
+/// A commit and the ids of its parents, first parent first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub id: String,
+    pub parents: Vec<String>,
+}
+
+/// A named branch pointing at the node `head`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    pub name: String,
+    pub head: String,
+}
+
+/// The graph to lay out.
+///
+/// Among commits that could come next along the time axis, the one earlier
+/// in `nodes` goes first, so nodes listed oldest first keep their order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dag {
+    pub nodes: Vec<Node>,
+    /// The first branch is the main line.
+    pub branches: Vec<Branch>,
+}
+
+/// Direction of the time axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// Time runs left to right, lanes are stacked top to bottom.
+    #[default]
+    Horizontal,
+    /// Time runs top to bottom, lanes are placed left to right.
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutConfig {
+    pub orientation: Orientation,
+    /// Grid steps between two consecutive commits. Edges between lanes bend
+    /// halfway, so this should be even.
+    pub commit_spacing: i32,
+    /// Grid steps between two neighbouring lanes.
+    pub lane_spacing: i32,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            orientation: Orientation::Horizontal,
+            commit_spacing: 8,
+            lane_spacing: 6,
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Grid position of `half_step` halves of a step along the time axis in
+    /// `lane`.
+    fn point(&self, half_step: i32, lane: usize) -> PointGrid {
+        let along = half_step * self.commit_spacing / 2;
+        let across = lane as i32 * self.lane_spacing;
+        match self.orientation {
+            Orientation::Horizontal => PointGrid {
+                x: along,
+                y: across,
+            },
+            Orientation::Vertical => PointGrid {
+                x: across,
+                y: along,
+            },
+        }
+    }
+}
+
+/// A node at its place in the layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedNode {
+    pub id: String,
+    /// Position along the time axis, the oldest node is at 0.
+    pub step: usize,
+    /// Index into [`Layout::lanes`].
+    pub lane: usize,
+    /// Center of the node.
+    pub position: PointGrid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// Parent and child share a lane.
+    Straight,
+    /// The child starts a lane off its first parent.
+    Fork,
+    /// The child merges a parent from another lane.
+    Merge,
+}
+
+/// The route from a parent to one of its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub parent: String,
+    pub child: String,
+    pub kind: EdgeKind,
+    /// From the center of the parent to the center of the child, including
+    /// both.
+    pub points: Vec<PointGrid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lane {
+    /// The first branch whose commits the lane holds, `None` if it only
+    /// holds commits no branch reaches through first parents.
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Layout {
+    /// Oldest first.
+    pub nodes: Vec<PlacedNode>,
+    /// Ordered by child, then by parent.
+    pub edges: Vec<Edge>,
+    /// Ordered from the main line outwards.
+    pub lanes: Vec<Lane>,
+}
+
+impl Layout {
+    pub fn node(&self, id: &str) -> Option<&PlacedNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    DuplicateNode(String),
+    UnknownParent {
+        node: String,
+        parent: String,
+    },
+    UnknownHead {
+        branch: String,
+        head: String,
+    },
+    /// The nodes can't be ordered because some of them are their own
+    /// ancestors.
+    Cycle,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::DuplicateNode(id) => write!(f, "commit {} appears twice", id),
+            LayoutError::UnknownParent { node, parent } => {
+                write!(f, "parent {} of commit {} does not exist", parent, node)
+            }
+            LayoutError::UnknownHead { branch, head } => {
+                write!(f, "branch {} points at unknown commit {}", branch, head)
+            }
+            LayoutError::Cycle => write!(f, "commit graph contains a cycle"),
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+/// The graph with nodes referenced by index.
+struct Graph {
+    parents: Vec<Vec<usize>>,
+    /// Index of the head of every branch.
+    heads: Vec<usize>,
+}
+
+impl Graph {
+    fn new(dag: &Dag) -> Result<Self, LayoutError> {
+        let mut index = HashMap::new();
+        for (i, node) in dag.nodes.iter().enumerate() {
+            if index.insert(node.id.as_str(), i).is_some() {
+                return Err(LayoutError::DuplicateNode(node.id.clone()));
+            }
+        }
+        let parents = dag
+            .nodes
+            .iter()
+            .map(|node| {
+                node.parents
+                    .iter()
+                    .map(|parent| {
+                        index.get(parent.as_str()).copied().ok_or_else(|| {
+                            LayoutError::UnknownParent {
+                                node: node.id.clone(),
+                                parent: parent.clone(),
+                            }
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        let heads =
+            dag.branches
+                .iter()
+                .map(|branch| {
+                    index.get(branch.head.as_str()).copied().ok_or_else(|| {
+                        LayoutError::UnknownHead {
+                            branch: branch.name.clone(),
+                            head: branch.head.clone(),
+                        }
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+        Ok(Graph { parents, heads })
+    }
+
+    /// The step of every node along the time axis.
+    fn steps(&self) -> Result<Vec<usize>, LayoutError> {
+        let count = self.parents.len();
+        let mut children = vec![Vec::new(); count];
+        let mut waiting: Vec<usize> = self.parents.iter().map(|p| p.len()).collect();
+        for (child, parents) in self.parents.iter().enumerate() {
+            for &parent in parents {
+                children[parent].push(child);
+            }
+        }
+
+        // Min heap of nodes whose parents are all placed.
+        let mut ready: BinaryHeap<_> = (0..count)
+            .filter(|&i| waiting[i] == 0)
+            .map(std::cmp::Reverse)
+            .collect();
+        let mut steps = vec![0; count];
+        let mut step = 0;
+        while let Some(std::cmp::Reverse(node)) = ready.pop() {
+            steps[node] = step;
+            step += 1;
+            for &child in &children[node] {
+                waiting[child] -= 1;
+                if waiting[child] == 0 {
+                    ready.push(std::cmp::Reverse(child));
+                }
+            }
+        }
+        if step < count {
+            return Err(LayoutError::Cycle);
+        }
+        Ok(steps)
+    }
+}
+
+/// Places the nodes of `dag` with the oldest one at `(0, 0)`.
+pub fn layout(dag: &Dag, config: &LayoutConfig) -> Result<Layout, LayoutError> {
+    let graph = Graph::new(dag)?;
+    let steps = graph.steps()?;
+    let lanes = lanes::assign(&graph, &steps);
+    let routes = route::routes(&graph, &steps, &lanes.of_node);
+    let order = lanes::order(&lanes, &routes, &steps);
+
+    let mut nodes: Vec<PlacedNode> = dag
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let lane = order[lanes.of_node[i]];
+            PlacedNode {
+                id: node.id.clone(),
+                step: steps[i],
+                lane,
+                position: config.point(2 * steps[i] as i32, lane),
+            }
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.step);
+
+    let mut edges: Vec<(usize, Edge)> = routes
+        .iter()
+        .map(|route| {
+            (
+                steps[route.child],
+                Edge {
+                    parent: dag.nodes[route.parent].id.clone(),
+                    child: dag.nodes[route.child].id.clone(),
+                    kind: route.kind,
+                    points: route
+                        .points
+                        .iter()
+                        .map(|&(half_step, lane)| config.point(half_step, order[lane]))
+                        .collect(),
+                },
+            )
+        })
+        .collect();
+    // Stable, so the parents of a child stay in order.
+    edges.sort_by_key(|(step, _)| *step);
+
+    let mut placed_lanes = vec![Lane { branch: None }; order.len()];
+    for (lane, branch) in lanes.branches.into_iter().enumerate() {
+        placed_lanes[order[lane]].branch = branch.map(|b| dag.branches[b].name.clone());
+    }
+
+    Ok(Layout {
+        nodes,
+        edges: edges.into_iter().map(|(_, edge)| edge).collect(),
+        lanes: placed_lanes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dag(nodes: &[(&str, &[&str])], branches: &[(&str, &str)]) -> Dag {
+        Dag {
+            nodes: nodes
+                .iter()
+                .map(|(id, parents)| Node {
+                    id: id.to_string(),
+                    parents: parents.iter().map(|p| p.to_string()).collect(),
+                })
+                .collect(),
+            branches: branches
+                .iter()
+                .map(|(name, head)| Branch {
+                    name: name.to_string(),
+                    head: head.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Draws the nodes as their ids, one row per lane and one column per
+    /// step.
+    fn snapshot(layout: &Layout) -> String {
+        let steps = layout.nodes.len();
+        let mut rows = vec![vec!["."; steps]; layout.lanes.len()];
+        for node in &layout.nodes {
+            rows[node.lane][node.step] = &node.id;
+        }
+        rows.iter()
+            .zip(&layout.lanes)
+            .map(|(row, lane)| {
+                format!(
+                    "{:8}{}",
+                    lane.branch.as_deref().unwrap_or("-"),
+                    row.join(" ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn feature_branch() -> Dag {
+        dag(
+            &[
+                ("a", &[]),
+                ("b", &["a"]),
+                ("c", &["b"]),
+                ("d", &["c"]),
+                ("e", &["b"]),
+                ("f", &["d", "e"]),
+            ],
+            &[("main", "f"), ("feature", "e")],
+        )
+    }
+
+    #[test]
+    fn test_lanes() {
+        let layout = layout(&feature_branch(), &LayoutConfig::default()).unwrap();
+        assert_eq!(
+            snapshot(&layout),
+            "main    a b c d . f\n\
+             feature . . . . e ."
+        );
+    }
+
+    #[test]
+    fn test_routes() {
+        let layout = layout(&feature_branch(), &LayoutConfig::default()).unwrap();
+        let edge = |parent: &str, child: &str| {
+            layout
+                .edges
+                .iter()
+                .find(|edge| edge.parent == parent && edge.child == child)
+                .unwrap()
+        };
+        let p = |x, y| PointGrid { x, y };
+
+        assert_eq!(edge("a", "b").kind, EdgeKind::Straight);
+        assert_eq!(edge("a", "b").points, [p(0, 0), p(8, 0)]);
+
+        // The fork bends right after b and runs in the feature lane.
+        assert_eq!(edge("b", "e").kind, EdgeKind::Fork);
+        assert_eq!(
+            edge("b", "e").points,
+            [p(8, 0), p(12, 0), p(12, 6), p(32, 6)]
+        );
+
+        // The merge runs in the feature lane until right before f.
+        assert_eq!(edge("e", "f").kind, EdgeKind::Merge);
+        assert_eq!(
+            edge("e", "f").points,
+            [p(32, 6), p(36, 6), p(36, 0), p(40, 0)]
+        );
+
+        let children: Vec<_> = layout.edges.iter().map(|e| e.child.as_str()).collect();
+        assert_eq!(children, ["b", "c", "d", "e", "f", "f"]);
+    }
+
+    #[test]
+    fn test_vertical() {
+        let config = LayoutConfig {
+            orientation: Orientation::Vertical,
+            ..Default::default()
+        };
+        let layout = layout(&feature_branch(), &config).unwrap();
+        assert_eq!(
+            layout.node("e").unwrap().position,
+            PointGrid { x: 6, y: 32 }
+        );
+        assert_eq!(
+            layout.node("f").unwrap().position,
+            PointGrid { x: 0, y: 40 }
+        );
+    }
+
+    #[test]
+    fn test_topological_order() {
+        // Children listed before their parents still come after them.
+        let dag = dag(&[("c", &["b"]), ("b", &["a"]), ("a", &[])], &[]);
+        let layout = layout(&dag, &LayoutConfig::default()).unwrap();
+        let ids: Vec<_> = layout.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(snapshot(&layout), "-       a b c");
+    }
+
+    #[test]
+    fn test_unbranched_commits_get_lanes() {
+        // x is only reachable as the second parent of the merge.
+        let dag = dag(
+            &[("a", &[]), ("x", &["a"]), ("b", &["a"]), ("m", &["b", "x"])],
+            &[("main", "m")],
+        );
+        let layout = layout(&dag, &LayoutConfig::default()).unwrap();
+        assert_eq!(
+            snapshot(&layout),
+            "main    a . b m\n\
+             -       . x . ."
+        );
+    }
+
+    #[test]
+    fn test_lanes_avoid_crossings() {
+        // The short lived `fix` comes after the long running `develop` in
+        // branch order, but below it both of its edges would cross `develop`.
+        let dag = dag(
+            &[
+                ("a", &[]),
+                ("d", &["a"]),
+                ("b", &["a"]),
+                ("f", &["b"]),
+                ("c", &["b", "f"]),
+                ("e", &["d"]),
+                ("m", &["c", "e"]),
+            ],
+            &[("main", "m"), ("develop", "e"), ("fix", "f")],
+        );
+        let layout = layout(&dag, &LayoutConfig::default()).unwrap();
+        assert_eq!(
+            snapshot(&layout),
+            "main    a . b . c . m\n\
+             fix     . . . f . . .\n\
+             develop . d . . . e ."
+        );
+    }
+
+    /// A main line merging `topics` branches of two commits each, one after
+    /// the other.
+    fn topics(topics: usize) -> Dag {
+        let node = |id: String, parents: Vec<String>| Node { id, parents };
+        let mut nodes = vec![node("m0".to_string(), Vec::new())];
+        let mut branches = vec![Branch {
+            name: "main".to_string(),
+            head: format!("m{}", topics),
+        }];
+        for i in 0..topics {
+            let (base, first, second) = (format!("m{}", i), format!("t{}a", i), format!("t{}b", i));
+            nodes.push(node(first.clone(), vec![base.clone()]));
+            nodes.push(node(second.clone(), vec![first]));
+            nodes.push(node(format!("m{}", i + 1), vec![base, second.clone()]));
+            branches.push(Branch {
+                name: format!("topic{}", i),
+                head: second,
+            });
+        }
+        Dag { nodes, branches }
+    }
+
+    #[test]
+    fn test_lanes_are_reused() {
+        let placed = layout(&topics(200), &LayoutConfig::default()).unwrap();
+        assert_eq!(placed.nodes.len(), 601);
+        // Every topic ends before the next one starts.
+        assert_eq!(placed.lanes.len(), 2);
+        assert_eq!(placed.lanes[0].branch.as_deref(), Some("main"));
+        assert_eq!(placed.lanes[1].branch.as_deref(), Some("topic0"));
+        assert!(placed
+            .nodes
+            .iter()
+            .all(|node| (node.lane == 0) == node.id.starts_with('m')));
+
+        // Topics that overlap need lanes of their own.
+        let dag = dag(
+            &[
+                ("a", &[]),
+                ("x", &["a"]),
+                ("y", &["a"]),
+                ("b", &["a", "x"]),
+                ("c", &["b", "y"]),
+            ],
+            &[("main", "c")],
+        );
+        assert_eq!(
+            snapshot(&layout(&dag, &LayoutConfig::default()).unwrap()),
+            "main    a . . b c\n\
+             -       . x . . .\n\
+             -       . . y . ."
+        );
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let config = LayoutConfig::default();
+        let first = layout(&feature_branch(), &config).unwrap();
+        for _ in 0..10 {
+            assert_eq!(layout(&feature_branch(), &config).unwrap(), first);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let config = LayoutConfig::default();
+        assert_eq!(
+            layout(&dag(&[("a", &[]), ("a", &[])], &[]), &config),
+            Err(LayoutError::DuplicateNode("a".to_string()))
+        );
+        assert_eq!(
+            layout(&dag(&[("a", &["z"])], &[]), &config),
+            Err(LayoutError::UnknownParent {
+                node: "a".to_string(),
+                parent: "z".to_string()
+            })
+        );
+        assert_eq!(
+            layout(&dag(&[("a", &[])], &[("main", "z")]), &config),
+            Err(LayoutError::UnknownHead {
+                branch: "main".to_string(),
+                head: "z".to_string()
+            })
+        );
+        assert_eq!(
+            layout(&dag(&[("a", &["b"]), ("b", &["a"])], &[]), &config),
+            Err(LayoutError::Cycle)
+        );
+    }
+}
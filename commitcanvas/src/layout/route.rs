@@ -0,0 +1,77 @@
+use super::{EdgeKind, Graph};
+
+/// An edge with its points given as half steps along the time axis and
+/// lanes, before the lanes are ordered.
+pub(super) struct Route {
+    pub parent: usize,
+    pub child: usize,
+    pub kind: EdgeKind,
+    pub points: Vec<(i32, usize)>,
+}
+
+/// Routes every edge of `graph`, ordered by child and parent.
+pub(super) fn routes(graph: &Graph, steps: &[usize], lanes: &[usize]) -> Vec<Route> {
+    // The steps of the nodes in every lane, in order.
+    let count = lanes.iter().max().map_or(0, |lane| lane + 1);
+    let mut occupied = vec![Vec::new(); count];
+    for (node, &lane) in lanes.iter().enumerate() {
+        occupied[lane].push(steps[node]);
+    }
+    for lane_steps in &mut occupied {
+        lane_steps.sort_unstable();
+    }
+    // No node of `lane` lies strictly between the steps `from` and `to`.
+    let free = |lane: usize, from: usize, to: usize| {
+        let lane_steps: &Vec<usize> = &occupied[lane];
+        let i = lane_steps.partition_point(|&step| step <= from);
+        lane_steps.get(i).is_none_or(|&step| step >= to)
+    };
+
+    let mut routes = Vec::new();
+    for (child, parents) in graph.parents.iter().enumerate() {
+        for (i, &parent) in parents.iter().enumerate() {
+            let (from, to) = (steps[parent], steps[child]);
+            let (parent_lane, child_lane) = (lanes[parent], lanes[child]);
+            let start = (2 * from as i32, parent_lane);
+            let end = (2 * to as i32, child_lane);
+            if parent_lane == child_lane {
+                routes.push(Route {
+                    parent,
+                    child,
+                    kind: EdgeKind::Straight,
+                    points: vec![start, end],
+                });
+                continue;
+            }
+
+            let kind = if i == 0 {
+                EdgeKind::Fork
+            } else {
+                EdgeKind::Merge
+            };
+            // Forks prefer the lane of the child, merges the lane of the
+            // parent, as long as the edge doesn't run through other nodes.
+            let (preferred, other) = match kind {
+                EdgeKind::Fork => (child_lane, parent_lane),
+                _ => (parent_lane, child_lane),
+            };
+            let via = if !free(preferred, from, to) && free(other, from, to) {
+                other
+            } else {
+                preferred
+            };
+            let bend = if via == child_lane {
+                2 * from as i32 + 1
+            } else {
+                2 * to as i32 - 1
+            };
+            routes.push(Route {
+                parent,
+                child,
+                kind,
+                points: vec![start, (bend, parent_lane), (bend, child_lane), end],
+            });
+        }
+    }
+    routes
+}
@@ -1,6 +1,10 @@
 pub mod control;
+pub mod gitgraph;
+pub mod layout;
 pub mod model;
+pub mod routing;
 pub mod settings;
+pub mod text;
 pub mod types;
-mod utils;
+pub mod utils;
 pub mod view;
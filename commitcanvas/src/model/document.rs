@@ -0,0 +1,264 @@
+//! On-disk document format of a [`Model`](super::Model).
+//!
+//! A document stores every shape together with its GUID, as well as the next
+//! GUID the model would hand out, so that a loaded model continues exactly
+//! where the saved one stopped. The undo history is not part of a document.
+//!
+//! Documents can be written as YAML or JSON. A YAML document looks like this:
+//!
+//! ```yaml
+//! version: 10
+//! next_guid: 2
+//! settings:
+//!   grid: { spacing: 12.0, origin: { x: 6.0, y: 6.0 }, snapping: true, subdivision: 1 }
+//! shapes:
+//! - guid: 0
+//!   config:
+//!     start: { x: 0, y: 0 }
+//!     end: { x: 2, y: 1 }
+//!     details: { type: rect, background: Blue }
+//!     options: { stroke_color: None, roughness: Medium, thickness: Thin }
+//!     seed: 3793791033
+//! - guid: 1
+//!   config:
+//!     start: { x: 0, y: 2 }
+//!     end: { x: 4, y: 2 }
+//!     details: { type: arrow }
+//!     options: { stroke_color: None, roughness: Medium, thickness: Thin }
+//!     seed: 2433363436
+//! ```
+//!
+//! Positions are grid coordinates on the grid described by `settings`, see
+//! [`coords_to_pixels`](crate::utils::coords_to_pixels). The
+//! `start_binding` and `end_binding` of an arrow refer to the shapes its ends
+//! are attached to by GUID.
+//!
+//! `version` identifies the layout of the document and is bumped whenever the
+//! layout changes. Documents of older versions are upgraded by
+//! [`migrate`](super::migrate) while parsing. Shapes are written in ascending GUID order so that saving
+//! the same model twice yields the same document.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::migrate::{migrate, MigrationError};
+use super::shape::ShapeConfig;
+use crate::settings::Settings;
+use crate::types::Guid;
+
+/// The serialization formats a [`Document`] can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Yaml,
+    Json,
+}
+
+#[derive(Debug)]
+pub enum DocumentError {
+    Yaml(serde_yaml::Error),
+    Json(serde_json::Error),
+    Migration(MigrationError),
+    Schema(serde_json::Error),
+    UnsupportedVersion(u32),
+    DuplicateGuid(Guid),
+    GuidOutOfRange(Guid),
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DocumentError::Yaml(e) => write!(f, "invalid YAML document: {}", e),
+            DocumentError::Json(e) => write!(f, "invalid JSON document: {}", e),
+            DocumentError::Migration(e) => write!(f, "{}", e),
+            DocumentError::Schema(e) => write!(f, "malformed document: {}", e),
+            DocumentError::UnsupportedVersion(version) => {
+                write!(f, "unsupported document version: {}", version)
+            }
+            DocumentError::DuplicateGuid(guid) => {
+                write!(f, "shape {} appears more than once", guid)
+            }
+            DocumentError::GuidOutOfRange(guid) => {
+                write!(f, "shape {} is not below the document's next_guid", guid)
+            }
+        }
+    }
+}
+
+impl Error for DocumentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DocumentError::Yaml(e) => Some(e),
+            DocumentError::Json(e) => Some(e),
+            DocumentError::Migration(e) => Some(e),
+            DocumentError::Schema(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DocumentShape {
+    pub guid: Guid,
+    pub config: ShapeConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Document {
+    pub version: u32,
+    pub next_guid: Guid,
+    pub settings: Settings,
+    pub shapes: Vec<DocumentShape>,
+}
+
+impl Document {
+    /// The document version written by this build.
+    pub const VERSION: u32 = 10;
+
+    pub fn serialize(&self, format: DocumentFormat) -> Result<String, DocumentError> {
+        match format {
+            DocumentFormat::Yaml => serde_yaml::to_string(self).map_err(DocumentError::Yaml),
+            DocumentFormat::Json => serde_json::to_string_pretty(self).map_err(DocumentError::Json),
+        }
+    }
+
+    /// Parses a document of any supported version, upgrading it to
+    /// [`Document::VERSION`] on the way.
+    pub fn parse(input: &str, format: DocumentFormat) -> Result<Self, DocumentError> {
+        let value: serde_json::Value = match format {
+            DocumentFormat::Yaml => serde_yaml::from_str(input).map_err(DocumentError::Yaml)?,
+            DocumentFormat::Json => serde_json::from_str(input).map_err(DocumentError::Json)?,
+        };
+        let value = migrate(value).map_err(DocumentError::Migration)?;
+        serde_json::from_value(value).map_err(DocumentError::Schema)
+    }
+
+    /// Checks that the document can be loaded into a model without GUID
+    /// collisions.
+    pub fn validate(&self) -> Result<(), DocumentError> {
+        if self.version != Self::VERSION {
+            return Err(DocumentError::UnsupportedVersion(self.version));
+        }
+        let mut seen = std::collections::HashSet::new();
+        for shape in &self.shapes {
+            if !seen.insert(shape.guid) {
+                return Err(DocumentError::DuplicateGuid(shape.guid));
+            }
+            if shape.guid < 0 || shape.guid >= self.next_guid {
+                return Err(DocumentError::GuidOutOfRange(shape.guid));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        ArrowDetails, Event, Model, Options, RectDetails, ShapeDetails, TextDetails,
+    };
+    use crate::types::Point;
+
+    fn sample_model() -> Model {
+        let mut model = Model::new();
+        let shapes = [
+            ShapeDetails::Arrow(ArrowDetails::default()),
+            ShapeDetails::Rect(RectDetails::default()),
+            ShapeDetails::Text(TextDetails {
+                content: "hello: world".to_string(),
+                ..Default::default()
+            }),
+        ];
+        for (i, details) in shapes.into_iter().enumerate() {
+            model.process_event(Event::Add {
+                guid: None,
+                config: ShapeConfig {
+                    start: Point { x: 0, y: i as i32 },
+                    end: Point { x: 2, y: 1 },
+                    details,
+                    options: Options::default(),
+                    seed: 0,
+                },
+            });
+        }
+        model.process_event(Event::Remove { guid: 1 });
+        model
+    }
+
+    #[test]
+    fn test_round_trip_yaml() {
+        let model = sample_model();
+        let document = model.to_document();
+        let text = document.serialize(DocumentFormat::Yaml).unwrap();
+        let parsed = Document::parse(&text, DocumentFormat::Yaml).unwrap();
+        assert_eq!(parsed, document);
+
+        let loaded = Model::from_document(parsed).unwrap();
+        assert_eq!(loaded.get_shape(0), model.get_shape(0));
+        assert_eq!(loaded.get_shape(2), model.get_shape(2));
+        assert!(loaded.get_shape(1).is_none());
+    }
+
+    #[test]
+    fn test_round_trip_json() {
+        let document = sample_model().to_document();
+        let text = document.serialize(DocumentFormat::Json).unwrap();
+        let parsed = Document::parse(&text, DocumentFormat::Json).unwrap();
+        assert_eq!(parsed, document);
+    }
+
+    #[test]
+    fn test_guid_generator_state() {
+        let document = sample_model().to_document();
+        assert_eq!(document.next_guid, 3);
+        assert_eq!(
+            document.shapes.iter().map(|s| s.guid).collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+
+        let mut loaded = Model::from_document(document).unwrap();
+        let guid = loaded.process_event(Event::Add {
+            guid: None,
+            config: loaded.get_shape(0).unwrap().clone(),
+        });
+        assert_eq!(guid, Some(3));
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        let mut document = sample_model().to_document();
+        document.version = Document::VERSION + 1;
+        assert!(matches!(
+            Model::from_document(document.clone()),
+            Err(DocumentError::UnsupportedVersion(_))
+        ));
+
+        document.version = Document::VERSION;
+        document.shapes.push(document.shapes[0].clone());
+        assert!(matches!(
+            Model::from_document(document.clone()),
+            Err(DocumentError::DuplicateGuid(0))
+        ));
+
+        document.shapes.pop();
+        document.next_guid = 1;
+        assert!(matches!(
+            Model::from_document(document),
+            Err(DocumentError::GuidOutOfRange(2))
+        ));
+
+        assert!(matches!(
+            Document::parse("version: [", DocumentFormat::Yaml),
+            Err(DocumentError::Yaml(_))
+        ));
+        assert!(matches!(
+            Document::parse(
+                &format!("version: {}\nshapes: 3", Document::VERSION),
+                DocumentFormat::Yaml
+            ),
+            Err(DocumentError::Schema(_))
+        ));
+    }
+}
@@ -8,6 +8,7 @@ pub enum Event {
     Add {
         /// For internal uses only. Don't set this field from the controller.
         guid: Option<Guid>,
+        /// Without a `guid`, the seed is replaced by that of the new GUID.
         config: ShapeConfig,
     },
     Remove {
@@ -89,6 +90,7 @@ impl From<EventHistory> for Event {
                     end: Some(to.end),
                     details: Some(to.details),
                     options: Some(to.options),
+                    seed: Some(to.seed),
                 },
             },
             EventHistory::Checkpoint => Event::Checkpoint,
@@ -0,0 +1,202 @@
+//! Compact encoding of a [`Document`] for the fragment part of a URL.
+//!
+//! A fragment consists of the [`PREFIX`] followed by the document as compact
+//! JSON, compressed with raw deflate and encoded as URL safe base64 without
+//! padding. The prefix identifies the encoding itself; the document inside
+//! carries its own version and is migrated like any other saved document.
+
+use std::error::Error;
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use super::{Document, DocumentError, DocumentFormat};
+
+/// Marks a fragment written with the current encoding.
+pub const PREFIX: &str = "cc1.";
+
+/// Longest fragment `encode` produces. Longer links are truncated or
+/// rejected by some chat tools and servers.
+pub const MAX_FRAGMENT_LENGTH: usize = 8000;
+
+/// Upper bound on the decompressed size, so a crafted link can't exhaust
+/// memory.
+const MAX_DECOMPRESSED_LENGTH: usize = 1 << 20;
+
+const COMPRESSION_LEVEL: u8 = 9;
+
+#[derive(Debug)]
+pub enum FragmentError {
+    /// The encoded drawing exceeds [`MAX_FRAGMENT_LENGTH`].
+    TooLarge {
+        length: usize,
+    },
+    /// The fragment does not start with [`PREFIX`].
+    UnknownEncoding,
+    InvalidBase64(base64::DecodeError),
+    InvalidCompression,
+    InvalidDocument(DocumentError),
+}
+
+impl fmt::Display for FragmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FragmentError::TooLarge { length } => write!(
+                f,
+                "drawing needs {} characters, more than the {} that fit into a link",
+                length, MAX_FRAGMENT_LENGTH
+            ),
+            FragmentError::UnknownEncoding => write!(f, "link does not contain a drawing"),
+            FragmentError::InvalidBase64(e) => write!(f, "corrupted link: {}", e),
+            FragmentError::InvalidCompression => write!(f, "corrupted link: invalid data"),
+            FragmentError::InvalidDocument(e) => write!(f, "corrupted link: {}", e),
+        }
+    }
+}
+
+impl Error for FragmentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FragmentError::InvalidBase64(e) => Some(e),
+            FragmentError::InvalidDocument(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes `document` into a URL fragment, without the leading `#`.
+pub fn encode(document: &Document) -> Result<String, FragmentError> {
+    let json = serde_json::to_vec(document)
+        .map_err(|e| FragmentError::InvalidDocument(DocumentError::Json(e)))?;
+    let compressed = miniz_oxide::deflate::compress_to_vec(&json, COMPRESSION_LEVEL);
+    let fragment = format!("{}{}", PREFIX, URL_SAFE_NO_PAD.encode(compressed));
+    if fragment.len() > MAX_FRAGMENT_LENGTH {
+        return Err(FragmentError::TooLarge {
+            length: fragment.len(),
+        });
+    }
+    Ok(fragment)
+}
+
+/// Decodes a fragment produced by `encode`. A leading `#` is ignored.
+pub fn decode(fragment: &str) -> Result<Document, FragmentError> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let payload = fragment
+        .strip_prefix(PREFIX)
+        .ok_or(FragmentError::UnknownEncoding)?;
+    let compressed = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(FragmentError::InvalidBase64)?;
+    let json =
+        miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DECOMPRESSED_LENGTH)
+            .map_err(|_| FragmentError::InvalidCompression)?;
+    let json = String::from_utf8(json).map_err(|_| FragmentError::InvalidCompression)?;
+    Document::parse(&json, DocumentFormat::Json).map_err(FragmentError::InvalidDocument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DocumentShape, Options, ShapeConfig, ShapeDetails, TextDetails};
+    use crate::types::Point;
+
+    fn text_document(contents: &[String]) -> Document {
+        Document {
+            version: Document::VERSION,
+            next_guid: contents.len() as i32,
+            settings: Default::default(),
+            shapes: contents
+                .iter()
+                .enumerate()
+                .map(|(i, content)| DocumentShape {
+                    guid: i as i32,
+                    config: ShapeConfig {
+                        start: Point { x: 0, y: i as i32 },
+                        end: Point {
+                            x: 12,
+                            y: i as i32 + 4,
+                        },
+                        details: ShapeDetails::Text(TextDetails {
+                            content: content.clone(),
+                            ..Default::default()
+                        }),
+                        options: Options::default(),
+                        seed: 0,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let document = text_document(&["main".to_string(), "feature/#1 & more".to_string()]);
+        let fragment = encode(&document).unwrap();
+        assert!(fragment.starts_with(PREFIX));
+        assert!(fragment
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-_.".contains(&b)));
+        assert_eq!(decode(&fragment).unwrap(), document);
+        assert_eq!(decode(&format!("#{}", fragment)).unwrap(), document);
+    }
+
+    #[test]
+    fn test_size_budget() {
+        // Pseudo random content doesn't compress well.
+        let mut state: u32 = 1;
+        let contents: Vec<String> = (0..200)
+            .map(|_| {
+                (0..40)
+                    .map(|_| {
+                        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                        char::from(b'a' + ((state >> 16) % 26) as u8)
+                    })
+                    .collect()
+            })
+            .collect();
+        assert!(matches!(
+            encode(&text_document(&contents)),
+            Err(FragmentError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_corrupted_fragments() {
+        let fragment = encode(&text_document(&["main".to_string()])).unwrap();
+
+        assert!(matches!(decode(""), Err(FragmentError::UnknownEncoding)));
+        assert!(matches!(
+            decode("section-2"),
+            Err(FragmentError::UnknownEncoding)
+        ));
+        assert!(matches!(
+            decode(&format!("{}!!", PREFIX)),
+            Err(FragmentError::InvalidBase64(_))
+        ));
+        let compressed = URL_SAFE_NO_PAD.decode(&fragment[PREFIX.len()..]).unwrap();
+        let truncated = URL_SAFE_NO_PAD.encode(&compressed[..compressed.len() / 2]);
+        assert!(matches!(
+            decode(&format!("{}{}", PREFIX, truncated)),
+            Err(FragmentError::InvalidCompression)
+        ));
+
+        let not_a_document = miniz_oxide::deflate::compress_to_vec(b"{\"version\": 1}", 9);
+        assert!(matches!(
+            decode(&format!(
+                "{}{}",
+                PREFIX,
+                URL_SAFE_NO_PAD.encode(not_a_document)
+            )),
+            Err(FragmentError::InvalidDocument(_))
+        ));
+    }
+
+    #[test]
+    fn test_decompression_limit() {
+        let bomb = miniz_oxide::deflate::compress_to_vec(&vec![b' '; 4 << 20], 9);
+        assert!(matches!(
+            decode(&format!("{}{}", PREFIX, URL_SAFE_NO_PAD.encode(bomb))),
+            Err(FragmentError::InvalidCompression)
+        ));
+    }
+}
@@ -9,6 +9,16 @@ impl GuidGenerator {
         GuidGenerator { counter: 0 }
     }
 
+    /// Creates a generator that continues handing out GUIDs at `counter`.
+    pub fn starting_at(counter: Guid) -> GuidGenerator {
+        GuidGenerator { counter }
+    }
+
+    /// Returns the GUID the next call to `next` will hand out.
+    pub fn peek(&self) -> Guid {
+        self.counter
+    }
+
     pub fn next(&mut self) -> Guid {
         let guid = self.counter;
         self.counter += 1;
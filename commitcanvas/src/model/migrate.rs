@@ -0,0 +1,377 @@
+//! Upgrades saved documents to the current [`Document::VERSION`].
+//!
+//! Every document carries a top level `version` field. Documents written by an
+//! older build are upgraded one version at a time by the steps in
+//! [`MIGRATIONS`] before they are deserialized, so a step only ever has to
+//! know the layout of two adjacent versions. Migrations work on an untyped
+//! [`Value`] because the old layout can no longer be expressed with the
+//! current shape types.
+//!
+//! When changing the document layout:
+//!
+//! 1. bump [`Document::VERSION`],
+//! 2. append a [`Migration`] from the previous version to [`MIGRATIONS`],
+//! 3. add fixtures of the new version to `tests/fixtures`, keeping the old
+//!    ones so every historical version stays loadable.
+
+use std::error::Error;
+use std::fmt;
+
+use serde_json::Value;
+
+use super::{seed_for, Document, Guid};
+
+/// The oldest document version that can still be loaded.
+pub const FIRST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    /// The document has no numeric top level `version` field.
+    MissingVersion,
+    /// The document was written by a newer build.
+    TooNew { version: u32, supported: u32 },
+    /// The version predates [`FIRST_VERSION`].
+    UnknownVersion(u32),
+    /// A migration step rejected the document.
+    Step { from: u32, message: String },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrationError::MissingVersion => write!(f, "document has no version"),
+            MigrationError::TooNew { version, supported } => write!(
+                f,
+                "document version {} is newer than the supported version {}",
+                version, supported
+            ),
+            MigrationError::UnknownVersion(version) => {
+                write!(f, "unknown document version: {}", version)
+            }
+            MigrationError::Step { from, message } => write!(
+                f,
+                "failed to upgrade document from version {}: {}",
+                from, message
+            ),
+        }
+    }
+}
+
+impl Error for MigrationError {}
+
+/// Upgrades a document from version `from` to version `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub apply: fn(&mut Value) -> Result<(), String>,
+}
+
+/// All migration steps, ordered by `from`.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        apply: pixels_to_grid,
+    },
+    Migration {
+        from: 2,
+        apply: add_settings,
+    },
+    // Version 4 added ellipses.
+    Migration {
+        from: 3,
+        apply: unchanged,
+    },
+    // Version 5 added fill styles.
+    Migration {
+        from: 4,
+        apply: unchanged,
+    },
+    // Version 6 added the seeds of the rough strokes.
+    Migration {
+        from: 5,
+        apply: add_seeds,
+    },
+    // Version 7 added arrow waypoints and routing.
+    Migration {
+        from: 6,
+        apply: unchanged,
+    },
+    // Version 8 added arrow bindings.
+    Migration {
+        from: 7,
+        apply: unchanged,
+    },
+    // Version 9 added elbow routing.
+    Migration {
+        from: 8,
+        apply: unchanged,
+    },
+    // Version 10 added arrowheads and dashed outlines.
+    Migration {
+        from: 9,
+        apply: unchanged,
+    },
+];
+
+/// Version 1 stored `start` and `end` of every shape in pixels.
+fn pixels_to_grid(document: &mut Value) -> Result<(), String> {
+    // The grid spacing all version 1 documents were drawn with.
+    const PIXEL_STEP: f64 = 6.;
+
+    let shapes = document
+        .get_mut("shapes")
+        .and_then(Value::as_array_mut)
+        .ok_or("missing shapes")?;
+    for shape in shapes {
+        for key in ["start", "end"] {
+            let point = shape
+                .get_mut("config")
+                .and_then(|config| config.get_mut(key))
+                .ok_or_else(|| format!("shape without {}", key))?;
+            for axis in ["x", "y"] {
+                let pixels = point
+                    .get(axis)
+                    .and_then(Value::as_f64)
+                    .ok_or_else(|| format!("invalid {} position", key))?;
+                point[axis] =
+                    Value::from(((pixels - PIXEL_STEP) / (2. * PIXEL_STEP)).round() as i64);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Version 3 added per document settings. Older documents were drawn on the
+/// fixed grid of earlier builds.
+fn add_settings(document: &mut Value) -> Result<(), String> {
+    let document = document.as_object_mut().ok_or("document is not a map")?;
+    document.insert(
+        "settings".to_string(),
+        serde_json::json!({
+            "grid": {
+                "spacing": 12.0,
+                "origin": { "x": 6.0, "y": 6.0 },
+                "snapping": true,
+                "subdivision": 1,
+            },
+        }),
+    );
+    Ok(())
+}
+
+/// Version 6 added the seed of the rough strokes to every shape. Older
+/// shapes get the seed a shape added with their GUID gets.
+fn add_seeds(document: &mut Value) -> Result<(), String> {
+    let shapes = document
+        .get_mut("shapes")
+        .and_then(Value::as_array_mut)
+        .ok_or("missing shapes")?;
+    for shape in shapes {
+        let guid = shape
+            .get("guid")
+            .and_then(Value::as_i64)
+            .and_then(|guid| Guid::try_from(guid).ok())
+            .ok_or("shape without guid")?;
+        let config = shape
+            .get_mut("config")
+            .and_then(Value::as_object_mut)
+            .ok_or("shape without config")?;
+        config.insert("seed".to_string(), Value::from(seed_for(guid)));
+    }
+    Ok(())
+}
+
+/// The version only added fields and values that older documents can do
+/// without, so they load unchanged.
+fn unchanged(_document: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Reads the `version` field of an untyped document.
+pub fn version_of(document: &Value) -> Result<u32, MigrationError> {
+    document
+        .get("version")
+        .and_then(Value::as_u64)
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or(MigrationError::MissingVersion)
+}
+
+/// Upgrades `document` to [`Document::VERSION`].
+pub fn migrate(document: Value) -> Result<Value, MigrationError> {
+    migrate_with(document, MIGRATIONS, Document::VERSION)
+}
+
+fn migrate_with(
+    mut document: Value,
+    migrations: &[Migration],
+    target: u32,
+) -> Result<Value, MigrationError> {
+    let mut version = version_of(&document)?;
+    if version > target {
+        return Err(MigrationError::TooNew {
+            version,
+            supported: target,
+        });
+    }
+    if version < FIRST_VERSION {
+        return Err(MigrationError::UnknownVersion(version));
+    }
+
+    while version < target {
+        let migration = migrations
+            .iter()
+            .find(|m| m.from == version)
+            .ok_or_else(|| MigrationError::Step {
+                from: version,
+                message: "no migration available".to_string(),
+            })?;
+        log::info!("migrating document from version {}", version);
+        (migration.apply)(&mut document).map_err(|message| MigrationError::Step {
+            from: version,
+            message,
+        })?;
+        version += 1;
+        document["version"] = Value::from(version);
+    }
+
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_shapes(document: &mut Value) -> Result<(), String> {
+        let items = document
+            .as_object_mut()
+            .and_then(|o| o.remove("items"))
+            .ok_or("missing items")?;
+        document["shapes"] = items;
+        Ok(())
+    }
+
+    fn add_next_guid(document: &mut Value) -> Result<(), String> {
+        document["next_guid"] = json!(0);
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            from: 1,
+            apply: rename_shapes,
+        },
+        Migration {
+            from: 2,
+            apply: add_next_guid,
+        },
+    ];
+
+    #[test]
+    fn test_migrates_step_by_step() {
+        let document = json!({ "version": 1, "items": [] });
+        let migrated = migrate_with(document, TEST_MIGRATIONS, 3).unwrap();
+        assert_eq!(
+            migrated,
+            json!({ "version": 3, "shapes": [], "next_guid": 0 })
+        );
+
+        let document = json!({ "version": 2, "shapes": [] });
+        let migrated = migrate_with(document, TEST_MIGRATIONS, 3).unwrap();
+        assert_eq!(
+            migrated,
+            json!({ "version": 3, "shapes": [], "next_guid": 0 })
+        );
+    }
+
+    #[test]
+    fn test_current_version_is_untouched() {
+        let document = json!({ "version": 3, "anything": true });
+        let migrated = migrate_with(document.clone(), TEST_MIGRATIONS, 3).unwrap();
+        assert_eq!(migrated, document);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            migrate_with(json!({ "version": 4 }), TEST_MIGRATIONS, 3),
+            Err(MigrationError::TooNew {
+                version: 4,
+                supported: 3
+            })
+        );
+        assert_eq!(
+            migrate_with(json!({ "version": 0 }), TEST_MIGRATIONS, 3),
+            Err(MigrationError::UnknownVersion(0))
+        );
+        assert_eq!(
+            migrate_with(json!({ "shapes": [] }), TEST_MIGRATIONS, 3),
+            Err(MigrationError::MissingVersion)
+        );
+        assert!(matches!(
+            migrate_with(json!({ "version": 1 }), TEST_MIGRATIONS, 3),
+            Err(MigrationError::Step { from: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_pixels_to_grid() {
+        fn shape(start: Value, end: Value) -> Value {
+            json!({ "guid": 0, "config": { "start": start, "end": end } })
+        }
+
+        let mut document = json!({
+            "version": 1,
+            "shapes": [
+                shape(json!({ "x": 6.0, "y": 18.0 }), json!({ "x": 408.0, "y": 80.0 })),
+                shape(json!({ "x": -30.0, "y": 11.9 }), json!({ "x": 0.0, "y": 0.0 })),
+            ],
+        });
+        pixels_to_grid(&mut document).unwrap();
+        assert_eq!(
+            document["shapes"],
+            json!([
+                shape(json!({ "x": 0, "y": 1 }), json!({ "x": 34, "y": 6 })),
+                shape(json!({ "x": -3, "y": 0 }), json!({ "x": -1, "y": -1 })),
+            ])
+        );
+
+        let mut document = json!({ "version": 1, "shapes": [{ "config": {} }] });
+        assert!(pixels_to_grid(&mut document).is_err());
+    }
+
+    #[test]
+    fn test_add_settings() {
+        let mut document = json!({ "version": 2, "shapes": [] });
+        add_settings(&mut document).unwrap();
+        let settings: crate::settings::Settings =
+            serde_json::from_value(document["settings"].clone()).unwrap();
+        assert_eq!(settings, crate::settings::Settings::default());
+
+        assert!(add_settings(&mut json!([])).is_err());
+    }
+
+    #[test]
+    fn test_add_seeds() {
+        let mut document = json!({
+            "version": 5,
+            "shapes": [
+                { "guid": 0, "config": { "start": { "x": 0, "y": 0 } } },
+                { "guid": 7, "config": {} },
+            ],
+        });
+        add_seeds(&mut document).unwrap();
+        assert_eq!(document["shapes"][0]["config"]["seed"], json!(seed_for(0)));
+        assert_eq!(document["shapes"][1]["config"]["seed"], json!(seed_for(7)));
+
+        let mut document = json!({ "version": 5, "shapes": [{ "config": {} }] });
+        assert!(add_seeds(&mut document).is_err());
+    }
+
+    #[test]
+    fn test_migrations_are_contiguous() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from, FIRST_VERSION + i as u32);
+        }
+        assert_eq!(FIRST_VERSION + MIGRATIONS.len() as u32, Document::VERSION);
+    }
+}
@@ -1,23 +1,40 @@
+mod document;
 mod events;
+pub mod fragment;
 mod guid;
+pub mod migrate;
 mod shape;
 
 use std::collections::HashMap;
 
+use crate::settings::Settings;
 pub use crate::types::Guid;
+use crate::types::PointGrid;
 pub use crate::view::Event as ViewEvent;
 use crate::view::View;
 
+pub use document::{Document, DocumentError, DocumentFormat, DocumentShape};
 pub use events::{Event, EventHistory};
 
-pub use shape::{ArrowDetails, Options, RectDetails, ShapeDetails, TextDetails};
-pub use shape::{PartialShapeConfig, ShapeConfig};
+pub use shape::{
+    reshuffle, seed_for, Anchor, Binding, PartialDetails, PartialOptions, PartialShapeConfig,
+    ShapeConfig,
+};
+pub use shape::{ArrowDetails, EllipseDetails, Options, RectDetails, ShapeDetails, TextDetails};
+pub use shape::{
+    Color, Dash, FillStyle, FontSize, Head, Roughness, Routing, TextAlign, Thickness, VerticalAlign,
+};
 
 pub struct Model {
     guid_generator: guid::GuidGenerator,
     shapes: HashMap<Guid, shape::ShapeConfig>,
-    history: Vec<EventHistory>,
+    settings: Settings,
+    /// Undo steps, each holding the events of one user action.
+    history: Vec<Vec<EventHistory>>,
     history_index: usize,
+    /// Whether further modifications are merged into the last step until the
+    /// next checkpoint.
+    modifying: bool,
     views: Vec<Box<dyn View>>,
 }
 
@@ -27,37 +44,220 @@ impl Default for Model {
     }
 }
 
+/// Whether a shape changed from `from` to `to` needs a new route: elbow
+/// arrows do when their ends move or they become elbow arrows.
+fn reroute(from: &ShapeConfig, to: &ShapeConfig) -> bool {
+    let routing = |config: &ShapeConfig| match &config.details {
+        ShapeDetails::Arrow(arrow) => Some(arrow.routing),
+        _ => None,
+    };
+    routing(to) == Some(Routing::Elbow)
+        && (from.start != to.start || from.end != to.end || routing(from) != routing(to))
+}
+
 impl Model {
     pub fn new() -> Self {
         Self {
             guid_generator: guid::GuidGenerator::new(),
             shapes: HashMap::new(),
+            settings: Settings::default(),
             history: Vec::new(),
             history_index: 0,
+            modifying: false,
             views: Vec::new(),
         }
     }
 
-    fn add_to_history(&mut self, history: EventHistory) {
+    /// Records `history`, either as a new undo step or as part of the last
+    /// one if `join` is set.
+    fn add_to_history(&mut self, history: EventHistory, join: bool) {
+        if join && self.history_index == self.history.len() {
+            if let Some(step) = self.history.last_mut() {
+                if let EventHistory::Modify { guid, to, .. } = &history {
+                    let previous = step.iter_mut().find_map(|event| match event {
+                        EventHistory::Modify {
+                            guid: shape_guid,
+                            to,
+                            ..
+                        } if shape_guid == guid => Some(to),
+                        _ => None,
+                    });
+                    if let Some(previous) = previous {
+                        *previous = to.clone();
+                        return;
+                    }
+                }
+                step.push(history);
+                return;
+            }
+        }
         self.history.truncate(self.history_index);
         self.history_index += 1;
-        self.history.push(history);
+        self.history.push(vec![history]);
     }
 
     pub fn process_event(&mut self, event: Event) -> Option<Guid> {
-        self.apply(event).and_then(|history| {
-            self.add_to_history(history.clone());
+        let checkpoint = matches!(event, Event::Checkpoint);
+        let modify = matches!(event, Event::Modify { .. });
+        let join = modify && self.modifying;
+        let guid = self.apply(event).and_then(|history| {
+            self.add_to_history(history.clone(), join);
+            self.update_dependents(&history);
             history.guid()
+        });
+        self.modifying = modify;
+        if checkpoint {
+            self.notify_checkpoint();
+        }
+        guid
+    }
+
+    /// Processes `events` as a single user action, undone in one step.
+    pub fn process_events(&mut self, events: impl IntoIterator<Item = Event>) -> Vec<Guid> {
+        self.modifying = false;
+        let mut guids = Vec::new();
+        let mut join = false;
+        for event in events {
+            if let Some(history) = self.apply(event) {
+                guids.extend(history.guid());
+                self.add_to_history(history.clone(), join);
+                self.update_dependents(&history);
+                join = true;
+            }
+        }
+        self.notify_checkpoint();
+        guids
+    }
+
+    fn notify_checkpoint(&mut self) {
+        let document = self.to_document();
+        for view in self.views.iter_mut() {
+            if let Err(e) = view.process_event(crate::view::Event::Checkpoint {
+                document: &document,
+            }) {
+                log::warn!("Error updating view {:?}", e);
+            }
+        }
+    }
+
+    /// Moves the ends of the arrows bound to the shape changed by `history`
+    /// along with it, or lets them go if it was removed, and routes elbow
+    /// arrows whose ends moved anew, all in the same undo step. Undo and redo
+    /// replay these changes from the history instead.
+    fn update_dependents(&mut self, history: &EventHistory) {
+        let (guid, moved) = match history {
+            EventHistory::Add { guid, .. } => (*guid, true),
+            EventHistory::Modify { guid, from, to } => (*guid, reroute(from, to)),
+            EventHistory::Remove { guid, .. } => (*guid, false),
+            EventHistory::Checkpoint => return,
+        };
+        let mut rerouted = Vec::new();
+        if moved {
+            rerouted.push(guid);
+        }
+        for event in self.dependent_events(guid) {
+            if let Some(history) = self.apply(event) {
+                rerouted.extend(history.guid());
+                self.add_to_history(history, true);
+            }
+        }
+        for guid in rerouted {
+            if let Some(history) = self.route_event(guid).and_then(|event| self.apply(event)) {
+                self.add_to_history(history, true);
+            }
+        }
+    }
+
+    /// The modification putting the waypoints of the elbow arrow `guid` on
+    /// its route around all other shapes, if they aren't yet.
+    fn route_event(&self, guid: Guid) -> Option<Event> {
+        let config = self.shapes.get(&guid)?;
+        let ShapeDetails::Arrow(arrow) = &config.details else {
+            return None;
+        };
+        if arrow.routing != Routing::Elbow {
+            return None;
+        }
+        let obstacles: Vec<(PointGrid, PointGrid)> = self
+            .shapes
+            .values()
+            .filter(|shape| !matches!(shape.details, ShapeDetails::Arrow(_)))
+            .map(|shape| (shape.start, shape.end))
+            .collect();
+        let route = crate::routing::elbow(config.start, config.end, &obstacles);
+        let waypoints = &route[1..route.len() - 1];
+        if waypoints == arrow.waypoints.as_slice() {
+            return None;
+        }
+        Some(Event::Modify {
+            guid,
+            config: PartialShapeConfig {
+                start: None,
+                end: None,
+                details: Some(ShapeDetails::Arrow(ArrowDetails {
+                    waypoints: waypoints.to_vec(),
+                    ..arrow.clone()
+                })),
+                options: None,
+                seed: None,
+            },
         })
     }
 
+    /// The modifications of the arrows bound to the shape `guid`, in the
+    /// order of their GUIDs.
+    fn dependent_events(&self, guid: Guid) -> Vec<Event> {
+        let target = self.shapes.get(&guid);
+        let follow = |point: &mut PointGrid, binding: &mut Option<shape::Binding>| {
+            if let Some(bound) = binding.filter(|bound| bound.guid == guid) {
+                match target {
+                    Some(target) => *point = target.anchor_point(bound.anchor),
+                    None => *binding = None,
+                }
+            }
+        };
+        let mut events: Vec<(Guid, Event)> = self
+            .shapes
+            .iter()
+            .filter_map(|(arrow_guid, config)| {
+                let ShapeDetails::Arrow(arrow) = &config.details else {
+                    return None;
+                };
+                let (mut start, mut end, mut bound) = (config.start, config.end, arrow.clone());
+                follow(&mut start, &mut bound.start_binding);
+                follow(&mut end, &mut bound.end_binding);
+                let unbound = bound != *arrow;
+                if start == config.start && end == config.end && !unbound {
+                    return None;
+                }
+                Some((
+                    *arrow_guid,
+                    Event::Modify {
+                        guid: *arrow_guid,
+                        config: PartialShapeConfig {
+                            start: Some(start),
+                            end: Some(end),
+                            details: unbound.then_some(ShapeDetails::Arrow(bound)),
+                            options: None,
+                            seed: None,
+                        },
+                    },
+                ))
+            })
+            .collect();
+        events.sort_by_key(|(guid, _)| *guid);
+        events.into_iter().map(|(_, event)| event).collect()
+    }
+
     fn apply(&mut self, event: Event) -> Option<EventHistory> {
         let history = match event {
-            Event::Add { guid, config } => {
+            Event::Add { guid, mut config } => {
                 let guid = if let Some(guid) = guid {
                     guid
                 } else {
-                    self.guid_generator.next()
+                    let guid = self.guid_generator.next();
+                    config.seed = shape::seed_for(guid);
+                    guid
                 };
                 log::info!("adding shape: {guid}");
                 self.shapes.insert(guid, config.clone());
@@ -71,26 +271,14 @@ impl Model {
             }
             Event::Modify { guid, config } => {
                 log::debug!("modifying shape: {guid}");
-                self.shapes.get_mut(&guid).and_then(|current_config| {
+                self.shapes.get_mut(&guid).map(|current_config| {
                     let old_config = current_config.clone();
                     current_config.update(config);
-
-                    if let Some(EventHistory::Modify {
-                        guid: shape_guid,
-                        to,
-                        ..
-                    }) = self.history.last_mut()
-                    {
-                        if *shape_guid == guid {
-                            *to = current_config.clone();
-                            return None;
-                        }
-                    }
-                    Some(EventHistory::Modify {
+                    EventHistory::Modify {
                         guid,
                         from: old_config,
                         to: current_config.clone(),
-                    })
+                    }
                 })
             }
             Event::Checkpoint => None,
@@ -104,7 +292,7 @@ impl Model {
                     log::warn!("Error updating view {:?}", e);
                 }
             }
-        } else if let Some(event) = self.history.last() {
+        } else if let Some(event) = self.history.last().and_then(|step| step.last()) {
             for view in self.views.iter_mut() {
                 if let Err(e) = view.process_event(crate::view::Event::Modify {
                     event: event.clone(),
@@ -119,28 +307,109 @@ impl Model {
 
     pub fn undo(&mut self) {
         log::info!("calling model undo");
+        self.modifying = false;
         if self.history_index > 0 {
             self.history_index -= 1;
-            if let Some(history) = self.history.get(self.history_index) {
-                log::info!("undoing event");
-                let event = Event::from(history.clone().revert());
-                self.apply(event);
+            if let Some(step) = self.history.get(self.history_index).cloned() {
+                log::info!("undoing {} events", step.len());
+                for history in step.iter().rev() {
+                    self.apply(Event::from(history.revert()));
+                }
+                self.notify_checkpoint();
             }
         }
     }
 
     pub fn redo(&mut self) {
         log::info!("calling model redo");
+        self.modifying = false;
         if self.history_index < self.history.len() {
-            if let Some(history) = self.history.get(self.history_index) {
-                log::info!("redoing event");
+            if let Some(step) = self.history.get(self.history_index).cloned() {
+                log::info!("redoing {} events", step.len());
                 self.history_index += 1;
-                let event = Event::from(history.clone());
-                self.apply(event);
+                for history in step {
+                    self.apply(Event::from(history));
+                }
+                self.notify_checkpoint();
             }
         }
     }
 
+    /// Captures all shapes and the GUID generator state in a [`Document`].
+    pub fn to_document(&self) -> Document {
+        let mut shapes: Vec<DocumentShape> = self
+            .shapes
+            .iter()
+            .map(|(guid, config)| DocumentShape {
+                guid: *guid,
+                config: config.clone(),
+            })
+            .collect();
+        shapes.sort_by_key(|shape| shape.guid);
+        Document {
+            version: Document::VERSION,
+            next_guid: self.guid_generator.peek(),
+            settings: self.settings,
+            shapes,
+        }
+    }
+
+    /// Creates a model holding the shapes of `document`, with an empty
+    /// history and no views.
+    pub fn from_document(document: Document) -> Result<Self, DocumentError> {
+        document.validate()?;
+        let mut model = Self::new();
+        model.guid_generator = guid::GuidGenerator::starting_at(document.next_guid);
+        model.settings = document.settings;
+        model.shapes = document
+            .shapes
+            .into_iter()
+            .map(|shape| (shape.guid, shape.config))
+            .collect();
+        Ok(model)
+    }
+
+    /// Replaces all shapes with the ones in `document`, clears the history
+    /// and reloads the views.
+    pub fn load_document(&mut self, document: Document) -> Result<(), DocumentError> {
+        let Model {
+            guid_generator,
+            shapes,
+            settings,
+            ..
+        } = Self::from_document(document)?;
+        self.guid_generator = guid_generator;
+        self.shapes = shapes;
+        self.settings = settings;
+        self.history.clear();
+        self.history_index = 0;
+        self.modifying = false;
+        self.reload_views();
+        Ok(())
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Changes the document settings and redraws the views with them.
+    pub fn set_settings(&mut self, settings: Settings) {
+        log::info!("updating settings: {:?}", settings);
+        self.settings = settings;
+        self.reload_views();
+        self.notify_checkpoint();
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = (&Guid, &shape::ShapeConfig)> {
+        self.shapes.iter()
+    }
+
+    /// The GUID the next shape added without one gets, the ones after it
+    /// follow in order.
+    pub fn next_guid(&self) -> Guid {
+        self.guid_generator.peek()
+    }
+
     pub fn get_shape(&self, guid: Guid) -> Option<&shape::ShapeConfig> {
         self.shapes.get(&guid)
     }
@@ -148,17 +417,18 @@ impl Model {
     pub fn add_view(&mut self, mut view: Box<dyn View>) {
         if let Err(e) = view.process_event(crate::view::Event::Reload {
             shapes: self.shapes.iter().collect(),
+            settings: &self.settings,
         }) {
             log::warn!("Error updating view {:?}", e);
         }
         self.views.push(view);
     }
 
-    #[allow(dead_code)]
     pub fn reload_views(&mut self) {
         for view in self.views.iter_mut() {
             if let Err(e) = view.process_event(crate::view::Event::Reload {
                 shapes: self.shapes.iter().collect(),
+                settings: &self.settings,
             }) {
                 log::warn!("Error updating view {:?}", e);
             }
@@ -174,10 +444,11 @@ mod tests {
     fn test_create_arrow() {
         let mut model = Model::new();
         let config = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Arrow(super::shape::ArrowDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event = Event::Add {
             guid: None,
@@ -195,10 +466,11 @@ mod tests {
     fn test_create_rect() {
         let mut model = Model::new();
         let config = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Rect(super::shape::RectDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event = Event::Add {
             guid: None,
@@ -216,10 +488,11 @@ mod tests {
     fn test_create_text() {
         let mut model = Model::new();
         let config = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Text(super::shape::TextDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event = Event::Add {
             config: config.clone(),
@@ -237,10 +510,11 @@ mod tests {
     fn test_undo() {
         let mut model = Model::new();
         let config1 = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Arrow(super::shape::ArrowDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event1 = Event::Add {
             guid: None,
@@ -248,10 +522,11 @@ mod tests {
         };
 
         let config2 = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 20.0, y: 20.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 20, y: 20 },
             details: super::shape::ShapeDetails::Arrow(super::shape::ArrowDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(1),
         };
         let event2 = Event::Add {
             guid: None,
@@ -282,10 +557,11 @@ mod tests {
     fn test_undo_commit() {
         let mut model = Model::new();
         let config1 = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Arrow(super::shape::ArrowDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event1 = Event::Add {
             guid: None,
@@ -299,22 +575,23 @@ mod tests {
 
         let mod1 = shape::PartialShapeConfig {
             start: None,
-            end: Some(crate::types::Point { x: 20.0, y: 20.0 }),
+            end: Some(crate::types::Point { x: 20, y: 20 }),
             details: None,
             options: None,
+            seed: None,
         };
 
         let mod2 = shape::PartialShapeConfig {
             start: None,
-            end: Some(crate::types::Point { x: 30.0, y: 30.0 }),
+            end: Some(crate::types::Point { x: 30, y: 30 }),
             details: None,
             options: None,
+            seed: None,
         };
 
         assert!(model.get_shape(guid1.unwrap()).is_some());
         assert!(
-            model.get_shape(guid1.unwrap()).unwrap().end
-                == crate::types::Point { x: 10.0, y: 10.0 }
+            model.get_shape(guid1.unwrap()).unwrap().end == crate::types::Point { x: 10, y: 10 }
         );
 
         model.process_event(Event::Modify {
@@ -330,27 +607,68 @@ mod tests {
 
         assert!(model.get_shape(guid1.unwrap()).is_some());
         assert!(
-            model.get_shape(guid1.unwrap()).unwrap().end
-                == crate::types::Point { x: 30.0, y: 30.0 }
+            model.get_shape(guid1.unwrap()).unwrap().end == crate::types::Point { x: 30, y: 30 }
         );
 
         model.undo();
 
         assert!(model.get_shape(guid1.unwrap()).is_some());
         assert!(
-            model.get_shape(guid1.unwrap()).unwrap().end
-                == crate::types::Point { x: 10.0, y: 10.0 }
+            model.get_shape(guid1.unwrap()).unwrap().end == crate::types::Point { x: 10, y: 10 }
         );
     }
 
+    #[test]
+    fn test_checkpoint_separates_modifications() {
+        let mut model = Model::new();
+        let guid = model
+            .process_event(Event::Add {
+                guid: None,
+                config: shape::ShapeConfig {
+                    start: crate::types::Point { x: 0, y: 0 },
+                    end: crate::types::Point { x: 10, y: 10 },
+                    details: super::shape::ShapeDetails::Rect(super::shape::RectDetails::default()),
+                    options: super::shape::Options::default(),
+                    seed: shape::seed_for(0),
+                },
+            })
+            .unwrap();
+        model.process_event(Event::Checkpoint);
+
+        for end in [20, 30] {
+            model.process_event(Event::Modify {
+                guid,
+                config: shape::PartialShapeConfig {
+                    start: None,
+                    end: Some(crate::types::Point { x: end, y: end }),
+                    details: None,
+                    options: None,
+                    seed: None,
+                },
+            });
+            model.process_event(Event::Checkpoint);
+        }
+
+        let end = |model: &Model| model.get_shape(guid).unwrap().end;
+        model.undo();
+        assert_eq!(end(&model), crate::types::Point { x: 20, y: 20 });
+        model.undo();
+        assert_eq!(end(&model), crate::types::Point { x: 10, y: 10 });
+        model.redo();
+        assert_eq!(end(&model), crate::types::Point { x: 20, y: 20 });
+        model.redo();
+        assert_eq!(end(&model), crate::types::Point { x: 30, y: 30 });
+    }
+
     #[test]
     fn test_create_modify_undo() {
         let mut model = Model::new();
         let config1 = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Arrow(super::shape::ArrowDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event1 = Event::Add {
             guid: None,
@@ -361,9 +679,10 @@ mod tests {
 
         let mod1 = shape::PartialShapeConfig {
             start: None,
-            end: Some(crate::types::Point { x: 20.0, y: 20.0 }),
+            end: Some(crate::types::Point { x: 20, y: 20 }),
             details: None,
             options: None,
+            seed: None,
         };
 
         model.process_event(Event::Modify {
@@ -375,8 +694,7 @@ mod tests {
 
         assert!(model.get_shape(guid1.unwrap()).is_some());
         assert!(
-            model.get_shape(guid1.unwrap()).unwrap().end
-                == crate::types::Point { x: 20.0, y: 20.0 }
+            model.get_shape(guid1.unwrap()).unwrap().end == crate::types::Point { x: 20, y: 20 }
         );
 
         model.undo();
@@ -384,14 +702,67 @@ mod tests {
         //assert!(model.get_shape(guid1.unwrap()).is_none());
     }
 
+    #[test]
+    fn test_load_document() {
+        struct CheckpointView {
+            documents: std::rc::Rc<std::cell::RefCell<Vec<Document>>>,
+        }
+
+        impl View for CheckpointView {
+            fn process_event(
+                &mut self,
+                event: crate::view::Event,
+            ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                if let crate::view::Event::Checkpoint { document } = event {
+                    self.documents.borrow_mut().push(document.clone());
+                }
+                Ok(())
+            }
+        }
+
+        let mut source = Model::new();
+        let config = shape::ShapeConfig {
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
+            details: super::shape::ShapeDetails::Rect(super::shape::RectDetails::default()),
+            options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
+        };
+        let guid = source.process_event(Event::Add {
+            guid: None,
+            config: config.clone(),
+        });
+
+        let documents = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let mut model = Model::new();
+        model.add_view(Box::new(CheckpointView {
+            documents: documents.clone(),
+        }));
+        model.load_document(source.to_document()).unwrap();
+        assert_eq!(*model.get_shape(guid.unwrap()).unwrap(), config);
+
+        model.process_event(Event::Remove {
+            guid: guid.unwrap(),
+        });
+        model.process_event(Event::Checkpoint);
+        model.undo();
+        assert_eq!(*model.get_shape(guid.unwrap()).unwrap(), config);
+
+        let documents = documents.borrow();
+        assert_eq!(documents.len(), 2);
+        assert!(documents[0].shapes.is_empty());
+        assert_eq!(documents[1], source.to_document());
+    }
+
     #[test]
     fn test_redo() {
         let mut model = Model::new();
         let config = shape::ShapeConfig {
-            start: crate::types::Point { x: 0.0, y: 0.0 },
-            end: crate::types::Point { x: 10.0, y: 10.0 },
+            start: crate::types::Point { x: 0, y: 0 },
+            end: crate::types::Point { x: 10, y: 10 },
             details: super::shape::ShapeDetails::Arrow(super::shape::ArrowDetails::default()),
             options: super::shape::Options::default(),
+            seed: shape::seed_for(0),
         };
         let event = Event::Add {
             guid: None,
@@ -413,4 +784,121 @@ mod tests {
         assert!(model.get_shape(guid.unwrap()).is_some());
         assert_eq!(*model.get_shape(guid.unwrap()).unwrap(), config);
     }
+
+    #[test]
+    fn test_bound_arrow_follows() {
+        let mut model = Model::new();
+        let shape = |start: (i32, i32), end: (i32, i32), details| shape::ShapeConfig {
+            start: crate::types::Point {
+                x: start.0,
+                y: start.1,
+            },
+            end: crate::types::Point { x: end.0, y: end.1 },
+            details,
+            options: super::shape::Options::default(),
+            seed: 0,
+        };
+        let rect = model
+            .process_event(Event::Add {
+                guid: None,
+                config: shape((0, 0), (4, 4), ShapeDetails::Rect(Default::default())),
+            })
+            .unwrap();
+        let binding = shape::Binding {
+            guid: rect,
+            anchor: shape::Anchor { x: 1.0, y: 0.5 },
+        };
+        let arrow = model
+            .process_event(Event::Add {
+                guid: None,
+                config: shape(
+                    (4, 2),
+                    (10, 2),
+                    ShapeDetails::Arrow(ArrowDetails {
+                        start_binding: Some(binding),
+                        ..Default::default()
+                    }),
+                ),
+            })
+            .unwrap();
+        let before = model.get_shape(arrow).unwrap().clone();
+
+        model.process_events(vec![Event::Modify {
+            guid: rect,
+            config: shape::PartialShapeConfig {
+                start: None,
+                end: Some(crate::types::Point { x: 6, y: 8 }),
+                details: None,
+                options: None,
+                seed: None,
+            },
+        }]);
+        let moved = model.get_shape(arrow).unwrap();
+        assert_eq!(moved.start, crate::types::Point { x: 6, y: 4 });
+        assert_eq!(moved.end, before.end);
+
+        model.process_events(vec![Event::Remove { guid: rect }]);
+        let ShapeDetails::Arrow(details) = &model.get_shape(arrow).unwrap().details else {
+            panic!("not an arrow");
+        };
+        assert_eq!(details.start_binding, None);
+
+        // Undo restores the arrow together with its shape.
+        model.undo();
+        model.undo();
+        assert_eq!(*model.get_shape(arrow).unwrap(), before);
+        model.redo();
+        assert_eq!(
+            model.get_shape(arrow).unwrap().start,
+            crate::types::Point { x: 6, y: 4 }
+        );
+    }
+
+    #[test]
+    fn test_elbow_arrow_reroutes() {
+        let mut model = Model::new();
+        let point = |x, y| crate::types::Point { x, y };
+        let arrow = |routing| shape::ShapeConfig {
+            start: point(0, 0),
+            end: point(10, 6),
+            details: ShapeDetails::Arrow(ArrowDetails {
+                routing,
+                ..Default::default()
+            }),
+            options: super::shape::Options::default(),
+            seed: 0,
+        };
+        let straight = model
+            .process_event(Event::Add {
+                guid: None,
+                config: arrow(Routing::Straight),
+            })
+            .unwrap();
+        let elbow = model
+            .process_event(Event::Add {
+                guid: None,
+                config: arrow(Routing::Elbow),
+            })
+            .unwrap();
+        let waypoints = |model: &Model, guid| match &model.get_shape(guid).unwrap().details {
+            ShapeDetails::Arrow(arrow) => arrow.waypoints.clone(),
+            _ => panic!("not an arrow"),
+        };
+        assert!(waypoints(&model, straight).is_empty());
+        assert_eq!(waypoints(&model, elbow).len(), 1);
+
+        model.process_events(vec![Event::Modify {
+            guid: elbow,
+            config: shape::PartialShapeConfig {
+                start: None,
+                end: Some(point(10, 0)),
+                details: None,
+                options: None,
+                seed: None,
+            },
+        }]);
+        assert!(waypoints(&model, elbow).is_empty());
+        model.undo();
+        assert_eq!(waypoints(&model, elbow).len(), 1);
+    }
 }
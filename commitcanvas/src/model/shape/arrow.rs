@@ -1,2 +1,164 @@
-#[derive(Clone, Default, Debug, PartialEq)]
-pub struct State {}
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Guid, PointGrid};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct State {
+    /// The points the arrow passes through on its way from `start` to `end`.
+    #[serde(default)]
+    pub waypoints: Vec<PointGrid>,
+    #[serde(default)]
+    pub routing: Routing,
+    /// The shape `start` is attached to.
+    #[serde(default)]
+    pub start_binding: Option<Binding>,
+    /// The shape `end` is attached to.
+    #[serde(default)]
+    pub end_binding: Option<Binding>,
+    /// The head drawn at `start`, an arrow pointing both ways has one at
+    /// either end.
+    #[serde(default)]
+    pub start_head: Head,
+    /// The head drawn at `end`.
+    #[serde(default = "open")]
+    pub end_head: Head,
+}
+
+/// An arrow points at its end unless told otherwise.
+impl Default for State {
+    fn default() -> Self {
+        State {
+            waypoints: Vec::new(),
+            routing: Routing::default(),
+            start_binding: None,
+            end_binding: None,
+            start_head: Head::None,
+            end_head: Head::Open,
+        }
+    }
+}
+
+fn open() -> Head {
+    Head::Open
+}
+
+/// What is drawn at an end of an arrow. Heads grow with the
+/// [`Thickness`](super::Thickness) of the arrow.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Head {
+    /// The line just ends.
+    #[default]
+    None,
+    /// Two strokes meeting in the tip.
+    Open,
+    /// A filled triangle.
+    Triangle,
+    Circle,
+    Diamond,
+    /// A stroke across the line.
+    Bar,
+}
+
+/// Attaches an end of an arrow to another shape, so the end follows the
+/// shape when it is moved or resized and lets go when it is removed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Binding {
+    pub guid: Guid,
+    pub anchor: Anchor,
+}
+
+/// A position relative to a shape, as the fraction of the way from its
+/// `start` to its `end` along each axis: `(0.5, 0.5)` is the center, `(1.0,
+/// 0.5)` the middle of the side at `end.x`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct Anchor {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// How an arrow runs through its points.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Routing {
+    /// A straight segment from each point to the next.
+    #[default]
+    Straight,
+    /// A smooth curve through all points.
+    Curved,
+    /// Horizontal and vertical segments around other shapes. The model keeps
+    /// the waypoints on the route of [`elbow`](crate::routing::elbow).
+    Elbow,
+}
+
+impl State {
+    /// All points of the arrow in order, `start` and `end` included.
+    pub fn points(&self, start: PointGrid, end: PointGrid) -> Vec<PointGrid> {
+        let mut points = Vec::with_capacity(self.waypoints.len() + 2);
+        points.push(start);
+        points.extend(&self.waypoints);
+        points.push(end);
+        points
+    }
+
+    /// Moves the bindings to the shapes `f` maps their shapes to, and drops
+    /// those it maps to `None`.
+    pub fn rebind(&mut self, f: impl Fn(Guid) -> Option<Guid>) {
+        for binding in [&mut self.start_binding, &mut self.end_binding] {
+            *binding = binding.and_then(|bound| {
+                Some(Binding {
+                    guid: f(bound.guid)?,
+                    ..bound
+                })
+            });
+        }
+    }
+
+    /// Drops the waypoints that lie on the straight segment between their
+    /// neighbours, which is how a waypoint dragged back into line goes away.
+    pub fn simplify(&mut self, start: PointGrid, end: PointGrid) {
+        let mut points = self.points(start, end);
+        let mut i = 1;
+        while i + 1 < points.len() {
+            if between(points[i - 1], points[i], points[i + 1]) {
+                points.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        self.waypoints = points[1..points.len() - 1].to_vec();
+    }
+}
+
+/// Whether `b` lies on the segment from `a` to `c`.
+fn between(a: PointGrid, b: PointGrid, c: PointGrid) -> bool {
+    let cross = (b.x - a.x) as i64 * (c.y - a.y) as i64 - (b.y - a.y) as i64 * (c.x - a.x) as i64;
+    cross == 0
+        && b.x >= a.x.min(c.x)
+        && b.x <= a.x.max(c.x)
+        && b.y >= a.y.min(c.y)
+        && b.y <= a.y.max(c.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: i32, y: i32) -> PointGrid {
+        PointGrid { x, y }
+    }
+
+    #[test]
+    fn test_simplify() {
+        let mut arrow = State {
+            waypoints: vec![point(2, 0), point(4, 0), point(4, 4), point(4, 2)],
+            routing: Routing::Curved,
+            ..Default::default()
+        };
+        arrow.simplify(point(0, 0), point(8, 2));
+        // (2, 0) lies on the way to (4, 0); (4, 4) turns back, so it stays.
+        assert_eq!(arrow.waypoints, vec![point(4, 0), point(4, 4), point(4, 2)]);
+
+        arrow.waypoints = vec![point(4, 0), point(4, 4)];
+        arrow.simplify(point(0, 0), point(4, 8));
+        assert_eq!(arrow.waypoints, vec![point(4, 0)]);
+    }
+}
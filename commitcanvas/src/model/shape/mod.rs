@@ -3,42 +3,168 @@ mod options;
 mod rect;
 mod text;
 
-use crate::types::PointPixel;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Guid, PointGrid};
 
 pub use arrow::State as ArrowDetails;
+/// An ellipse has the same details as a rectangle.
+pub use rect::State as EllipseDetails;
 pub use rect::State as RectDetails;
 #[allow(unused_imports)]
 pub use text::State as TextDetails;
 
-#[derive(Clone, Debug, PartialEq)]
+pub use arrow::{Anchor, Binding, Head, Routing};
+pub use options::{Color, Dash, FillStyle, Roughness, Thickness};
+pub use text::{FontSize, TextAlign, VerticalAlign};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ShapeDetails {
     Arrow(arrow::State),
     Rect(rect::State),
+    /// The ellipse inscribed in the rectangle spanned by `start` and `end`.
+    Ellipse(rect::State),
     #[allow(unused)]
     Text(text::State),
 }
 
-#[derive(Default, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
 pub struct Options {
     pub stroke_color: options::Color,
     pub roughness: options::Roughness,
     pub thickness: options::Thickness,
+    #[serde(default)]
+    pub dash: options::Dash,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ShapeConfig {
-    pub start: PointPixel, // TODO: This should be grid coordinates
-    pub end: PointPixel,   // TODO: This should be grid coordinates
+    pub start: PointGrid,
+    pub end: PointGrid,
     pub details: ShapeDetails,
     pub options: Options,
+    /// Seeds the wobble of the rough strokes, so a shape keeps its look while
+    /// it is moved or resized. Shapes added without a GUID get the seed of
+    /// their new GUID, see [`seed_for`].
+    pub seed: u32,
+}
+
+/// Spreads `value` over all seeds with the SplitMix64 mixer, so that close
+/// values give unrelated seeds.
+fn mix(value: u64) -> u32 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    ((z ^ (z >> 31)) >> 32) as u32
+}
+
+/// The seed of a shape added as `guid`.
+pub fn seed_for(guid: Guid) -> u32 {
+    mix(guid as u32 as u64)
+}
+
+/// A new seed for a shape drawn with `seed`, redrawing its strokes.
+pub fn reshuffle(seed: u32) -> u32 {
+    // Above all GUIDs, so a reshuffled shape doesn't look like another one.
+    mix(1 << 32 | seed as u64)
+}
+
+/// Changes to [`Options`]; `None` keeps the current value.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PartialOptions {
+    pub stroke_color: Option<options::Color>,
+    pub roughness: Option<options::Roughness>,
+    pub thickness: Option<options::Thickness>,
+    pub dash: Option<options::Dash>,
+}
+
+impl Options {
+    pub fn update(&mut self, update: PartialOptions) {
+        if let Some(stroke_color) = update.stroke_color {
+            self.stroke_color = stroke_color;
+        }
+
+        if let Some(roughness) = update.roughness {
+            self.roughness = roughness;
+        }
+
+        if let Some(thickness) = update.thickness {
+            self.thickness = thickness;
+        }
+
+        if let Some(dash) = update.dash {
+            self.dash = dash;
+        }
+    }
+}
+
+/// Changes to the style part of [`ShapeDetails`]. Fields that don't exist on
+/// a shape are ignored, e.g. `font_size` for a rectangle.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PartialDetails {
+    pub background: Option<options::Color>,
+    pub fill: Option<options::FillStyle>,
+    pub font_size: Option<text::FontSize>,
+    pub align: Option<text::TextAlign>,
+    pub vertical_align: Option<text::VerticalAlign>,
+    pub routing: Option<arrow::Routing>,
+    pub start_head: Option<arrow::Head>,
+    pub end_head: Option<arrow::Head>,
+}
+
+impl ShapeDetails {
+    pub fn update(&mut self, update: PartialDetails) {
+        match self {
+            ShapeDetails::Arrow(arrow) => {
+                if let Some(routing) = update.routing {
+                    arrow.routing = routing;
+                }
+                if let Some(start_head) = update.start_head {
+                    arrow.start_head = start_head;
+                }
+                if let Some(end_head) = update.end_head {
+                    arrow.end_head = end_head;
+                }
+            }
+            ShapeDetails::Rect(rect) => {
+                if let Some(background) = update.background {
+                    rect.background = background;
+                }
+                if let Some(fill) = update.fill {
+                    rect.fill = fill;
+                }
+            }
+            ShapeDetails::Ellipse(ellipse) => {
+                if let Some(background) = update.background {
+                    ellipse.background = background;
+                }
+                if let Some(fill) = update.fill {
+                    ellipse.fill = fill;
+                }
+            }
+            ShapeDetails::Text(text) => {
+                if let Some(font_size) = update.font_size {
+                    text.font_size = font_size;
+                }
+                if let Some(align) = update.align {
+                    text.align = align;
+                }
+                if let Some(vertical_align) = update.vertical_align {
+                    text.vertical_align = vertical_align;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct PartialShapeConfig {
-    pub start: Option<PointPixel>,
-    pub end: Option<PointPixel>,
+    pub start: Option<PointGrid>,
+    pub end: Option<PointGrid>,
     pub details: Option<ShapeDetails>,
     pub options: Option<Options>,
+    pub seed: Option<u32>,
 }
 
 impl ShapeConfig {
@@ -58,5 +184,303 @@ impl ShapeConfig {
         if let Some(options) = update.options {
             self.options = options;
         }
+
+        if let Some(seed) = update.seed {
+            self.seed = seed;
+        }
+    }
+
+    /// The points the shape is drawn through: `start`, the waypoints of an
+    /// arrow, then `end`.
+    pub fn points(&self) -> Vec<PointGrid> {
+        match &self.details {
+            ShapeDetails::Arrow(arrow) => arrow.points(self.start, self.end),
+            _ => vec![self.start, self.end],
+        }
+    }
+
+    /// The shape with all of its points moved by `f`.
+    pub fn map_points(&self, f: impl Fn(PointGrid) -> PointGrid) -> ShapeConfig {
+        let mut config = self.clone();
+        config.start = f(self.start);
+        config.end = f(self.end);
+        if let ShapeDetails::Arrow(arrow) = &mut config.details {
+            for waypoint in &mut arrow.waypoints {
+                *waypoint = f(*waypoint);
+            }
+        }
+        config
+    }
+
+    /// The anchor at `p` if it lies within the bounds of the shape. Arrows
+    /// have no anchors, nothing is attached to them.
+    pub fn anchor(&self, p: PointGrid) -> Option<Anchor> {
+        if matches!(self.details, ShapeDetails::Arrow(_)) {
+            return None;
+        }
+        let fraction = |q: i32, from: i32, to: i32| {
+            if q < from.min(to) || q > from.max(to) {
+                None
+            } else if from == to {
+                Some(0.5)
+            } else {
+                Some((q - from) as f32 / (to - from) as f32)
+            }
+        };
+        Some(Anchor {
+            x: fraction(p.x, self.start.x, self.end.x)?,
+            y: fraction(p.y, self.start.y, self.end.y)?,
+        })
+    }
+
+    /// The grid point at `anchor` on the shape.
+    pub fn anchor_point(&self, anchor: Anchor) -> PointGrid {
+        let axis = |from: i32, to: i32, fraction: f32| {
+            from + ((to - from) as f32 * fraction).round() as i32
+        };
+        PointGrid {
+            x: axis(self.start.x, self.end.x, anchor.x),
+            y: axis(self.start.y, self.end.y, anchor.y),
+        }
+    }
+
+    /// Whether the shape overlaps or lies inside the rectangle spanned by `a`
+    /// and `b`.
+    pub fn intersects(&self, a: PointGrid, b: PointGrid) -> bool {
+        let min = PointGrid {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+        };
+        let max = PointGrid {
+            x: a.x.max(b.x),
+            y: a.y.max(b.y),
+        };
+        match self.details {
+            ShapeDetails::Arrow(_) => {
+                let inside =
+                    |p: PointGrid| min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y;
+                let corners = [
+                    min,
+                    PointGrid { x: max.x, y: min.y },
+                    max,
+                    PointGrid { x: min.x, y: max.y },
+                ];
+                let points = self.points();
+                points.iter().any(|p| inside(*p))
+                    || points.windows(2).any(|segment| {
+                        (0..4).any(|i| {
+                            segments_intersect(
+                                (segment[0], segment[1]),
+                                (corners[i], corners[(i + 1) % 4]),
+                            )
+                        })
+                    })
+            }
+            ShapeDetails::Rect(_) | ShapeDetails::Text(_) => {
+                self.start.x.min(self.end.x) <= max.x
+                    && self.start.x.max(self.end.x) >= min.x
+                    && self.start.y.min(self.end.y) <= max.y
+                    && self.start.y.max(self.end.y) >= min.y
+            }
+            ShapeDetails::Ellipse(_) => {
+                let center = (
+                    (self.start.x + self.end.x) as f32 / 2.0,
+                    (self.start.y + self.end.y) as f32 / 2.0,
+                );
+                let radii = (
+                    (self.end.x - self.start.x).abs() as f32 / 2.0,
+                    (self.end.y - self.start.y).abs() as f32 / 2.0,
+                );
+                // The point of the rectangle closest to the center.
+                let closest = (
+                    center.0.clamp(min.x as f32, max.x as f32),
+                    center.1.clamp(min.y as f32, max.y as f32),
+                );
+                // A flat ellipse is a line and only reaches along it.
+                let distance = |d: f32, r: f32| {
+                    if r > 0.0 {
+                        (d / r).powi(2)
+                    } else if d == 0.0 {
+                        0.0
+                    } else {
+                        f32::INFINITY
+                    }
+                };
+                distance(closest.0 - center.0, radii.0) + distance(closest.1 - center.1, radii.1)
+                    <= 1.0
+            }
+        }
+    }
+}
+
+/// Whether two closed line segments share at least one point.
+fn segments_intersect((p, q): (PointGrid, PointGrid), (r, s): (PointGrid, PointGrid)) -> bool {
+    fn orientation(a: PointGrid, b: PointGrid, c: PointGrid) -> i64 {
+        let cross =
+            (b.x - a.x) as i64 * (c.y - a.y) as i64 - (b.y - a.y) as i64 * (c.x - a.x) as i64;
+        cross.signum()
+    }
+    fn on_segment(a: PointGrid, b: PointGrid, c: PointGrid) -> bool {
+        c.x >= a.x.min(b.x) && c.x <= a.x.max(b.x) && c.y >= a.y.min(b.y) && c.y <= a.y.max(b.y)
+    }
+
+    let (o1, o2) = (orientation(p, q, r), orientation(p, q, s));
+    let (o3, o4) = (orientation(r, s, p), orientation(r, s, q));
+    (o1 != o2 && o3 != o4)
+        || (o1 == 0 && on_segment(p, q, r))
+        || (o2 == 0 && on_segment(p, q, s))
+        || (o3 == 0 && on_segment(r, s, p))
+        || (o4 == 0 && on_segment(r, s, q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(details: ShapeDetails, start: (i32, i32), end: (i32, i32)) -> ShapeConfig {
+        ShapeConfig {
+            start: PointGrid {
+                x: start.0,
+                y: start.1,
+            },
+            end: PointGrid { x: end.0, y: end.1 },
+            details,
+            options: Options::default(),
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn test_seeds() {
+        assert_eq!(seed_for(3), seed_for(3));
+        let seeds: Vec<u32> = (-2..100).map(seed_for).collect();
+        for (i, seed) in seeds.iter().enumerate() {
+            assert!(!seeds[i + 1..].contains(seed));
+            assert_ne!(reshuffle(*seed), *seed);
+            assert!(!seeds.contains(&reshuffle(*seed)));
+        }
+    }
+
+    #[test]
+    fn test_partial_updates() {
+        let mut options = Options::default();
+        options.update(PartialOptions {
+            thickness: Some(Thickness::Thick),
+            ..Default::default()
+        });
+        assert_eq!(
+            options,
+            Options {
+                thickness: Thickness::Thick,
+                ..Default::default()
+            }
+        );
+
+        let update = PartialDetails {
+            background: Some(Color::Teal),
+            fill: Some(FillStyle::Dots),
+            font_size: Some(FontSize::Small),
+            align: Some(TextAlign::Center),
+            ..Default::default()
+        };
+        let mut rect = ShapeDetails::Rect(RectDetails::default());
+        rect.update(update);
+        assert_eq!(
+            rect,
+            ShapeDetails::Rect(RectDetails {
+                background: Color::Teal,
+                fill: FillStyle::Dots,
+            })
+        );
+        let mut text = ShapeDetails::Text(TextDetails {
+            content: "main".to_string(),
+            font_size: FontSize::Large,
+            ..Default::default()
+        });
+        text.update(update);
+        assert_eq!(
+            text,
+            ShapeDetails::Text(TextDetails {
+                content: "main".to_string(),
+                font_size: FontSize::Small,
+                align: TextAlign::Center,
+                vertical_align: VerticalAlign::Top,
+            })
+        );
+    }
+
+    #[test]
+    fn test_anchors() {
+        let rect = shape(ShapeDetails::Rect(RectDetails::default()), (8, 4), (0, 0));
+        let anchor = rect.anchor(PointGrid { x: 2, y: 4 }).unwrap();
+        assert_eq!(anchor, Anchor { x: 0.75, y: 0.0 });
+        assert_eq!(rect.anchor_point(anchor), PointGrid { x: 2, y: 4 });
+        assert_eq!(rect.anchor(PointGrid { x: 9, y: 2 }), None);
+
+        // The anchor keeps its place on the side as the shape grows.
+        let grown = shape(ShapeDetails::Rect(RectDetails::default()), (16, 6), (0, 2));
+        assert_eq!(grown.anchor_point(anchor), PointGrid { x: 4, y: 6 });
+
+        let arrow = shape(ShapeDetails::Arrow(ArrowDetails::default()), (0, 0), (8, 4));
+        assert_eq!(arrow.anchor(PointGrid { x: 0, y: 0 }), None);
+    }
+
+    #[test]
+    fn test_rect_intersects() {
+        let rect = shape(ShapeDetails::Rect(RectDetails::default()), (4, 4), (0, 0));
+        let a = PointGrid { x: 2, y: 2 };
+        assert!(rect.intersects(a, PointGrid { x: 10, y: 10 }));
+        assert!(rect.intersects(PointGrid { x: -1, y: -1 }, PointGrid { x: 5, y: 5 }));
+        assert!(rect.intersects(PointGrid { x: 4, y: 4 }, PointGrid { x: 8, y: 8 }));
+        assert!(!rect.intersects(PointGrid { x: 5, y: 0 }, PointGrid { x: 8, y: 8 }));
+    }
+
+    #[test]
+    fn test_ellipse_intersects() {
+        let ellipse = shape(
+            ShapeDetails::Ellipse(EllipseDetails::default()),
+            (0, 0),
+            (8, 4),
+        );
+        assert!(ellipse.intersects(PointGrid { x: 3, y: 1 }, PointGrid { x: 5, y: 3 }));
+        assert!(ellipse.intersects(PointGrid { x: 8, y: 2 }, PointGrid { x: 10, y: 10 }));
+        // Inside the bounding box, but in the corner outside the ellipse.
+        assert!(!ellipse.intersects(PointGrid { x: 0, y: 0 }, PointGrid { x: 1, y: 0 }));
+        assert!(!ellipse.intersects(PointGrid { x: 9, y: 0 }, PointGrid { x: 10, y: 4 }));
+
+        let line = shape(
+            ShapeDetails::Ellipse(EllipseDetails::default()),
+            (0, 2),
+            (8, 2),
+        );
+        assert!(line.intersects(PointGrid { x: 4, y: 0 }, PointGrid { x: 5, y: 4 }));
+        assert!(!line.intersects(PointGrid { x: 4, y: 3 }, PointGrid { x: 5, y: 4 }));
+    }
+
+    #[test]
+    fn test_arrow_intersects() {
+        let arrow = shape(
+            ShapeDetails::Arrow(ArrowDetails::default()),
+            (0, 0),
+            (10, 10),
+        );
+        // Crosses the rectangle without an end inside.
+        assert!(arrow.intersects(PointGrid { x: 4, y: 3 }, PointGrid { x: 6, y: 7 }));
+        // Inside the bounding box, but off the line.
+        assert!(!arrow.intersects(PointGrid { x: 6, y: 1 }, PointGrid { x: 9, y: 4 }));
+        assert!(arrow.intersects(PointGrid { x: 9, y: 9 }, PointGrid { x: 20, y: 20 }));
+        assert!(arrow.intersects(PointGrid { x: -5, y: -5 }, PointGrid { x: 15, y: 15 }));
+
+        let bent = shape(
+            ShapeDetails::Arrow(ArrowDetails {
+                waypoints: vec![PointGrid { x: 10, y: 0 }],
+                ..Default::default()
+            }),
+            (0, 0),
+            (10, 10),
+        );
+        // Off the diagonal, but on the way through the waypoint.
+        assert!(bent.intersects(PointGrid { x: 6, y: -1 }, PointGrid { x: 9, y: 4 }));
+        assert!(!bent.intersects(PointGrid { x: 4, y: 3 }, PointGrid { x: 6, y: 7 }));
     }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum Color {
     #[default]
     None,
@@ -22,6 +22,81 @@ pub enum Color {
     Rose,
 }
 
+impl Color {
+    /// Every color, in palette order.
+    pub const ALL: [Color; 17] = [
+        Color::None,
+        Color::Red,
+        Color::Organge,
+        Color::Amber,
+        Color::Yellow,
+        Color::Lime,
+        Color::Green,
+        Color::Emerald,
+        Color::Teal,
+        Color::Cyan,
+        Color::Sky,
+        Color::Blue,
+        Color::Indigo,
+        Color::Purple,
+        Color::Fuchsia,
+        Color::Pink,
+        Color::Rose,
+    ];
+
+    /// Lowercase name, as used in text formats.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Color::None => "none",
+            Color::Red => "red",
+            Color::Organge => "orange",
+            Color::Amber => "amber",
+            Color::Yellow => "yellow",
+            Color::Lime => "lime",
+            Color::Green => "green",
+            Color::Emerald => "emerald",
+            Color::Teal => "teal",
+            Color::Cyan => "cyan",
+            Color::Sky => "sky",
+            Color::Blue => "blue",
+            Color::Indigo => "indigo",
+            Color::Purple => "purple",
+            Color::Fuchsia => "fuchsia",
+            Color::Pink => "pink",
+            Color::Rose => "rose",
+        }
+    }
+
+    /// The color called `name`, see [`Color::name`].
+    pub fn from_name(name: &str) -> Option<Color> {
+        Color::ALL.into_iter().find(|color| color.name() == name)
+    }
+
+    /// CSS class coloring the outline of a shape, see the `From` conversion
+    /// for the fill.
+    pub fn stroke_class(&self) -> &'static str {
+        match self {
+            Color::None => "cc_stroke_none",
+            Color::Red => "cc_stroke_red",
+            Color::Organge => "cc_stroke_orange",
+            Color::Amber => "cc_stroke_amber",
+            Color::Yellow => "cc_stroke_yellow",
+            Color::Lime => "cc_stroke_lime",
+            Color::Green => "cc_stroke_green",
+            Color::Emerald => "cc_stroke_emerald",
+            Color::Teal => "cc_stroke_teal",
+            Color::Cyan => "cc_stroke_cyan",
+            Color::Sky => "cc_stroke_sky",
+            Color::Blue => "cc_stroke_blue",
+            Color::Indigo => "cc_stroke_indigo",
+            Color::Purple => "cc_stroke_purple",
+            Color::Fuchsia => "cc_stroke_fuchsia",
+            Color::Pink => "cc_stroke_pink",
+            Color::Rose => "cc_stroke_rose",
+        }
+    }
+}
+
 impl From<&Color> for &'static str {
     fn from(color: &Color) -> &'static str {
         match color {
@@ -46,6 +121,43 @@ impl From<&Color> for &'static str {
     }
 }
 
+/// How the background of a rectangle or ellipse is drawn.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FillStyle {
+    /// A flat, translucent area.
+    #[default]
+    Solid,
+    /// Parallel lines.
+    Hachure,
+    /// Lines in two directions.
+    CrossHatch,
+    /// Lines joined into a single zigzag stroke.
+    ZigZag,
+    Dots,
+}
+
+impl FillStyle {
+    pub const ALL: [FillStyle; 5] = [
+        FillStyle::Solid,
+        FillStyle::Hachure,
+        FillStyle::CrossHatch,
+        FillStyle::ZigZag,
+        FillStyle::Dots,
+    ];
+
+    /// CSS class drawing the fill in its style, next to the class of the
+    /// [`Color`].
+    pub fn class(&self) -> &'static str {
+        match self {
+            FillStyle::Solid => "cc_fill_style_solid",
+            FillStyle::Hachure => "cc_fill_style_hachure",
+            FillStyle::CrossHatch => "cc_fill_style_cross_hatch",
+            FillStyle::ZigZag => "cc_fill_style_zigzag",
+            FillStyle::Dots => "cc_fill_style_dots",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
 pub enum Roughness {
     Low,
@@ -81,3 +193,24 @@ impl From<&Thickness> for &'static str {
         }
     }
 }
+
+/// The width of the stroke in pixels, the same as the `&str` conversion.
+impl From<&Thickness> for f32 {
+    fn from(thickness: &Thickness) -> f32 {
+        match thickness {
+            Thickness::Thin => 1.0,
+            Thickness::Medium => 2.0,
+            Thickness::Thick => 3.0,
+        }
+    }
+}
+
+/// How the outline of a shape is stroked. Dashes and dots grow with the
+/// [`Thickness`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
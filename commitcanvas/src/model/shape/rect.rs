@@ -1,6 +1,11 @@
-use super::options::Color;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Default, Debug, PartialEq)]
+use super::options::{Color, FillStyle};
+
+/// How the inside of a rectangle or an ellipse is filled.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
 pub struct State {
     pub background: Color,
+    #[serde(default)]
+    pub fill: FillStyle,
 }
@@ -1,10 +1,16 @@
-#[derive(Clone, Default, Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
 pub struct State {
     pub content: String,
     pub font_size: FontSize,
+    #[serde(default)]
+    pub align: TextAlign,
+    #[serde(default)]
+    pub vertical_align: VerticalAlign,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
 pub enum FontSize {
     #[default]
     Medium,
@@ -12,6 +18,16 @@ pub enum FontSize {
     Large,
 }
 
+impl FontSize {
+    pub fn pixels(&self) -> f32 {
+        match self {
+            FontSize::Small => 12.0,
+            FontSize::Medium => 16.0,
+            FontSize::Large => 24.0,
+        }
+    }
+}
+
 impl From<&FontSize> for &'static str {
     fn from(size: &FontSize) -> &'static str {
         match size {
@@ -21,3 +37,21 @@ impl From<&FontSize> for &'static str {
         }
     }
 }
+
+/// Horizontal placement of the lines within the shape.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Vertical placement of the lines within the shape.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
@@ -0,0 +1,272 @@
+//! Orthogonal routes for elbow arrows.
+//!
+//! A route runs along the grid in horizontal and vertical segments from one
+//! point to another. It keeps a step away from the boxes of other shapes and
+//! takes as few bends as it can, a bend costing as much as a few steps.
+//!
+//! The same input always gives the same route.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::types::PointGrid;
+
+/// Steps a bend is worth, so a route with fewer bends wins over a slightly
+/// shorter one.
+const BEND: i32 = 4;
+/// Grid steps obstacles grow by. A route may run along the outline of the
+/// grown box, never into it.
+const MARGIN: i32 = 1;
+/// Grid steps the search may leave the box around all points.
+const SLACK: i32 = 2;
+
+/// Right, down, left, up.
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// The points of an orthogonal route from `start` to `end`, both included,
+/// with the bends in between. It avoids the boxes spanned by the pairs of
+/// `obstacles`, given by any two opposite corners.
+///
+/// The ends may lie on an obstacle, like the side of the shape an arrow is
+/// attached to; the route then leaves it straight away. An obstacle an end
+/// lies inside is ignored. Without a way around the obstacles the route
+/// bends once, ignoring them.
+pub fn elbow(
+    start: PointGrid,
+    end: PointGrid,
+    obstacles: &[(PointGrid, PointGrid)],
+) -> Vec<PointGrid> {
+    if start == end {
+        return vec![start, end];
+    }
+    let boxes: Vec<(PointGrid, PointGrid)> = obstacles
+        .iter()
+        .map(|(a, b)| {
+            (
+                PointGrid {
+                    x: a.x.min(b.x),
+                    y: a.y.min(b.y),
+                },
+                PointGrid {
+                    x: a.x.max(b.x),
+                    y: a.y.max(b.y),
+                },
+            )
+        })
+        .filter(|(min, max)| !inside(start, *min, *max, 0) && !inside(end, *min, *max, 0))
+        .collect();
+    let blocked = |p: PointGrid| boxes.iter().any(|(min, max)| inside(p, *min, *max, MARGIN));
+
+    let (mut low, mut high) = (
+        PointGrid {
+            x: start.x.min(end.x),
+            y: start.y.min(end.y),
+        },
+        PointGrid {
+            x: start.x.max(end.x),
+            y: start.y.max(end.y),
+        },
+    );
+    for (min, max) in &boxes {
+        low = PointGrid {
+            x: low.x.min(min.x - MARGIN),
+            y: low.y.min(min.y - MARGIN),
+        };
+        high = PointGrid {
+            x: high.x.max(max.x + MARGIN),
+            y: high.y.max(max.y + MARGIN),
+        };
+    }
+    let within = |p: PointGrid| {
+        low.x - SLACK <= p.x
+            && p.x <= high.x + SLACK
+            && low.y - SLACK <= p.y
+            && p.y <= high.y + SLACK
+    };
+
+    search(start, end, |p| within(p) && !blocked(p)).unwrap_or_else(|| {
+        if start.x == end.x || start.y == end.y {
+            vec![start, end]
+        } else {
+            vec![
+                start,
+                PointGrid {
+                    x: end.x,
+                    y: start.y,
+                },
+                end,
+            ]
+        }
+    })
+}
+
+/// Whether `p` lies strictly inside the box from `min` to `max` grown by
+/// `margin`.
+fn inside(p: PointGrid, min: PointGrid, max: PointGrid, margin: i32) -> bool {
+    min.x - margin < p.x && p.x < max.x + margin && min.y - margin < p.y && p.y < max.y + margin
+}
+
+/// The cheapest route over the points `free` allows, as its bends. The state
+/// of the search is a point and the direction it was reached in, the start
+/// has none.
+fn search(
+    start: PointGrid,
+    end: PointGrid,
+    free: impl Fn(PointGrid) -> bool,
+) -> Option<Vec<PointGrid>> {
+    const NONE: usize = DIRECTIONS.len();
+    type State = (i32, i32, usize);
+
+    let mut costs: HashMap<State, i32> = HashMap::new();
+    let mut previous: HashMap<State, State> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    costs.insert((start.x, start.y, NONE), 0);
+    queue.push(Reverse((0, start.x, start.y, NONE)));
+
+    while let Some(Reverse((cost, x, y, direction))) = queue.pop() {
+        let state = (x, y, direction);
+        if costs.get(&state).is_some_and(|best| *best < cost) {
+            continue;
+        }
+        if (PointGrid { x, y }) == end {
+            return Some(corners(unwind(&previous, state)));
+        }
+        for (turn, (dx, dy)) in DIRECTIONS.iter().enumerate() {
+            // Never straight back.
+            if direction != NONE && (turn + 2) % 4 == direction {
+                continue;
+            }
+            let next = PointGrid {
+                x: x + dx,
+                y: y + dy,
+            };
+            if next != end && !free(next) {
+                continue;
+            }
+            let bend = if direction == NONE || direction == turn {
+                0
+            } else {
+                BEND
+            };
+            let next_state = (next.x, next.y, turn);
+            let next_cost = cost + 1 + bend;
+            if costs.get(&next_state).is_none_or(|best| next_cost < *best) {
+                costs.insert(next_state, next_cost);
+                previous.insert(next_state, state);
+                queue.push(Reverse((next_cost, next.x, next.y, turn)));
+            }
+        }
+    }
+    None
+}
+
+/// The points from the start of the search to `state`.
+fn unwind(
+    previous: &HashMap<(i32, i32, usize), (i32, i32, usize)>,
+    state: (i32, i32, usize),
+) -> Vec<PointGrid> {
+    let mut points = vec![PointGrid {
+        x: state.0,
+        y: state.1,
+    }];
+    let mut state = state;
+    while let Some(before) = previous.get(&state) {
+        points.push(PointGrid {
+            x: before.0,
+            y: before.1,
+        });
+        state = *before;
+    }
+    points.reverse();
+    points
+}
+
+/// The ends of a path of neighbouring points and the points it bends at.
+fn corners(points: Vec<PointGrid>) -> Vec<PointGrid> {
+    let mut result = vec![points[0]];
+    for window in points.windows(3) {
+        let (a, b, c) = (window[0], window[1], window[2]);
+        if (b.x - a.x, b.y - a.y) != (c.x - b.x, c.y - b.y) {
+            result.push(b);
+        }
+    }
+    result.push(points[points.len() - 1]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: i32, y: i32) -> PointGrid {
+        PointGrid { x, y }
+    }
+
+    fn points(points: &[(i32, i32)]) -> Vec<PointGrid> {
+        points.iter().map(|&(x, y)| point(x, y)).collect()
+    }
+
+    /// Whether every segment of `route` is horizontal or vertical.
+    fn orthogonal(route: &[PointGrid]) -> bool {
+        route
+            .windows(2)
+            .all(|segment| segment[0].x == segment[1].x || segment[0].y == segment[1].y)
+    }
+
+    #[test]
+    fn test_straight_and_single_bend() {
+        assert_eq!(
+            elbow(point(0, 0), point(10, 0), &[]),
+            points(&[(0, 0), (10, 0)])
+        );
+        assert_eq!(elbow(point(3, 3), point(3, 3), &[]).len(), 2);
+        let route = elbow(point(0, 0), point(10, 6), &[]);
+        assert_eq!(route.len(), 3);
+        assert!(orthogonal(&route));
+    }
+
+    #[test]
+    fn test_avoids_obstacles() {
+        // A box right across the straight way.
+        let obstacle = (point(4, -3), point(6, 3));
+        let route = elbow(point(0, 0), point(10, 0), &[obstacle]);
+        assert!(orthogonal(&route));
+        assert_eq!(route.len(), 4);
+        // It goes around with a step to spare.
+        assert_eq!(route[1].y.abs(), 4, "{:?}", route);
+        for segment in route.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            assert!(a.x.max(b.x) < 3 || a.x.min(b.x) > 7 || a.y.min(b.y).abs() > 3);
+        }
+    }
+
+    #[test]
+    fn test_leaves_attached_shapes() {
+        // From the right side of one box to the left side of another.
+        let (from, to) = ((point(0, 0), point(4, 4)), (point(10, 6), point(14, 10)));
+        let route = elbow(point(4, 2), point(10, 8), &[from, to]);
+        assert!(orthogonal(&route));
+        assert_eq!(route[1].y, 2);
+        assert!(route[1].x > 4);
+        assert_eq!(route[route.len() - 2].y, 8);
+
+        // An end inside a box ignores it.
+        let route = elbow(point(2, 2), point(10, 2), &[from]);
+        assert_eq!(route, points(&[(2, 2), (10, 2)]));
+    }
+
+    #[test]
+    fn test_enclosed_end() {
+        // The end is walled in by boxes on all sides.
+        let walls = [
+            (point(8, -4), point(12, -2)),
+            (point(8, 2), point(12, 4)),
+            (point(6, -4), point(7, 4)),
+            (point(13, -4), point(14, 4)),
+        ];
+        assert_eq!(
+            elbow(point(0, 0), point(10, 0), &walls),
+            points(&[(0, 0), (10, 0)])
+        );
+    }
+}
@@ -1 +1,55 @@
-pub const PIXEL_STEP: f32 = 6.;
+use serde::{Deserialize, Serialize};
+
+use crate::types::PointPixel;
+
+/// Per document settings, saved together with the shapes.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub grid: GridConfig,
+}
+
+/// Layout of the grid shapes are placed on.
+///
+/// Shapes are stored in grid coordinates, where one unit is one grid step of
+/// `spacing` pixels. Dots are drawn every `subdivision` grid steps.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct GridConfig {
+    /// Distance between two grid steps in pixels.
+    pub spacing: f32,
+    /// Pixel position of the grid point `(0, 0)`.
+    pub origin: PointPixel,
+    /// Whether the pointer snaps to the dots. Otherwise it moves in single
+    /// grid steps.
+    pub snapping: bool,
+    /// Number of grid steps between two neighbouring dots.
+    pub subdivision: u32,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            spacing: 12.,
+            origin: PointPixel { x: 6., y: 6. },
+            snapping: true,
+            subdivision: 1,
+        }
+    }
+}
+
+impl GridConfig {
+    /// Number of grid steps the pointer moves at once.
+    pub fn snap_steps(&self) -> i32 {
+        if self.snapping {
+            self.subdivision.max(1) as i32
+        } else {
+            1
+        }
+    }
+
+    /// Distance between two neighbouring dots in pixels.
+    pub fn dot_distance(&self) -> f32 {
+        self.spacing * self.subdivision.max(1) as f32
+    }
+}
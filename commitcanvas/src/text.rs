@@ -0,0 +1,211 @@
+//! Line breaking and placement of the content of text shapes.
+//!
+//! Views measure text differently, a browser asks its font engine while
+//! headless renderers estimate. The layout takes a [`TextMeasure`] so every
+//! view breaks lines the same way given the same measurements.
+
+use crate::model::{TextAlign, TextDetails, VerticalAlign};
+use crate::types::PointPixel;
+
+/// Height of a line relative to the font size.
+pub const LINE_HEIGHT: f32 = 1.25;
+
+/// Measures the rendered width of a string.
+pub trait TextMeasure {
+    /// Width in pixels of `text` set in the text font at `font_size` pixels.
+    fn width(&self, text: &str, font_size: f32) -> f32;
+}
+
+/// Estimates widths from the number of characters, for views without access
+/// to font metrics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApproximateMeasure {
+    /// Average character width relative to the font size.
+    pub char_width: f32,
+}
+
+impl Default for ApproximateMeasure {
+    fn default() -> Self {
+        // Patrick Hand averages about half an em per character.
+        Self { char_width: 0.5 }
+    }
+}
+
+impl TextMeasure for ApproximateMeasure {
+    fn width(&self, text: &str, font_size: f32) -> f32 {
+        text.chars().count() as f32 * self.char_width * font_size
+    }
+}
+
+/// Breaks `content` into lines no wider than `max_width`.
+///
+/// Explicit newlines always start a new line. Lines break at spaces; a word
+/// that doesn't fit on a line of its own is split between characters.
+pub fn wrap(
+    content: &str,
+    max_width: f32,
+    font_size: f32,
+    measure: &dyn TextMeasure,
+) -> Vec<String> {
+    let fits = |line: &str| measure.width(line, font_size) <= max_width;
+    let mut lines = Vec::new();
+    for paragraph in content.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if !fits(&line) && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A line of text and the position of its anchor, see [`TextLayout`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub text: String,
+    pub position: PointPixel,
+}
+
+/// The content of a text shape broken into lines and placed within the shape.
+///
+/// Line positions are the top of the line and, depending on the alignment,
+/// its left end, center or right end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    pub lines: Vec<Line>,
+    /// Height of all lines together.
+    pub height: f32,
+}
+
+impl TextLayout {
+    /// Lays out `details` within the box spanned by `start` and `end`.
+    pub fn new(
+        details: &TextDetails,
+        start: PointPixel,
+        end: PointPixel,
+        measure: &dyn TextMeasure,
+    ) -> Self {
+        let (min_x, max_x) = (start.x.min(end.x), start.x.max(end.x));
+        let (min_y, max_y) = (start.y.min(end.y), start.y.max(end.y));
+        let font_size = details.font_size.pixels();
+        let line_height = LINE_HEIGHT * font_size;
+
+        let lines = wrap(&details.content, max_x - min_x, font_size, measure);
+        let height = lines.len() as f32 * line_height;
+        let x = match details.align {
+            TextAlign::Left => min_x,
+            TextAlign::Center => (min_x + max_x) / 2.0,
+            TextAlign::Right => max_x,
+        };
+        let top = match details.vertical_align {
+            VerticalAlign::Top => min_y,
+            VerticalAlign::Middle => (min_y + max_y - height) / 2.0,
+            VerticalAlign::Bottom => max_y - height,
+        };
+
+        TextLayout {
+            lines: lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| Line {
+                    text,
+                    position: PointPixel {
+                        x,
+                        y: top + i as f32 * line_height,
+                    },
+                })
+                .collect(),
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::FontSize;
+
+    /// Every character is 10 pixels wide at font size 20.
+    fn measure() -> ApproximateMeasure {
+        ApproximateMeasure { char_width: 0.5 }
+    }
+
+    fn wrap_at(content: &str, chars: usize) -> Vec<String> {
+        wrap(content, chars as f32 * 10.0, 20.0, &measure())
+    }
+
+    #[test]
+    fn test_wrap_words() {
+        assert_eq!(
+            wrap_at("merge main into feature", 10),
+            ["merge main", "into", "feature"]
+        );
+        assert_eq!(wrap_at("merge main", 100), ["merge main"]);
+        assert_eq!(wrap_at("", 10), [""]);
+    }
+
+    #[test]
+    fn test_wrap_newlines() {
+        assert_eq!(wrap_at("main\n\nfeature", 10), ["main", "", "feature"]);
+        assert_eq!(wrap_at("a b\nc", 1), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_wrap_long_words() {
+        assert_eq!(
+            wrap_at("1234567890abc x", 4),
+            ["1234", "5678", "90ab", "c x"]
+        );
+        // A single character that doesn't fit still gets a line.
+        assert_eq!(wrap_at("ab", 0), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_layout_alignment() {
+        let mut details = TextDetails {
+            content: "a\nb".to_string(),
+            font_size: FontSize::Small,
+            ..Default::default()
+        };
+        let start = PointPixel { x: 100.0, y: 50.0 };
+        let end = PointPixel { x: 0.0, y: 0.0 };
+        let positions = |details: &TextDetails| -> Vec<(f32, f32)> {
+            TextLayout::new(details, start, end, &measure())
+                .lines
+                .iter()
+                .map(|line| (line.position.x, line.position.y))
+                .collect()
+        };
+
+        assert_eq!(positions(&details), [(0.0, 0.0), (0.0, 15.0)]);
+
+        details.align = TextAlign::Center;
+        details.vertical_align = VerticalAlign::Middle;
+        assert_eq!(positions(&details), [(50.0, 10.0), (50.0, 25.0)]);
+
+        details.align = TextAlign::Right;
+        details.vertical_align = VerticalAlign::Bottom;
+        assert_eq!(positions(&details), [(100.0, 20.0), (100.0, 35.0)]);
+
+        let layout = TextLayout::new(&details, start, end, &measure());
+        assert_eq!(layout.height, 30.0);
+    }
+}
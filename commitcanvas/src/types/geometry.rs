@@ -1,15 +1,17 @@
-use std::ops::Add;
+use std::ops::{Add, Sub};
+
+use serde::{Deserialize, Serialize};
 
 pub type Float = f32;
 pub type Int = i32;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct Point<T> {
     pub x: T,
     pub y: T,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct Vec<T> {
     pub x: T,
     pub y: T,
@@ -33,10 +35,10 @@ impl<T> From<Point<T>> for (T, T) {
     }
 }
 
-impl Add<Vec<Float>> for Point<Float> {
-    type Output = Point<Float>;
+impl<T: Add<Output = T>> Add<Vec<T>> for Point<T> {
+    type Output = Point<T>;
 
-    fn add(self, rhs: Vec<Float>) -> Point<Float> {
+    fn add(self, rhs: Vec<T>) -> Point<T> {
         Point {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
@@ -44,6 +46,17 @@ impl Add<Vec<Float>> for Point<Float> {
     }
 }
 
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Vec<T>;
+
+    fn sub(self, rhs: Point<T>) -> Vec<T> {
+        Vec {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,6 +89,14 @@ mod tests {
         assert_eq!(point1, point2);
     }
 
+    #[test]
+    fn test_point_vec_arithmetic() {
+        let start: Point<i32> = Point { x: 1, y: 2 };
+        let end: Point<i32> = Point { x: 4, y: -2 };
+        assert_eq!(end - start, Vec { x: 3, y: -4 });
+        assert_eq!(start + (end - start), end);
+    }
+
     #[test]
     fn test_point_inequality() {
         let point1: Point<f32> = Point { x: 1.0, y: 2.0 };
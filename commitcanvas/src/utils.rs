@@ -1,20 +1,26 @@
 use crate::types::{PointGrid, PointPixel};
 
-use crate::settings::PIXEL_STEP;
+use crate::settings::GridConfig;
 
 /// Converts a point in pixel coordinates to grid coordinates.
 ///
 /// # Arguments
 ///
 /// * `PointPixel { x, y }` - A point in pixel coordinates.
+/// * `grid` - The grid to snap to.
 ///
 /// # Returns
 ///
-/// * `PointGrid` - The corresponding point in grid coordinates.
-pub fn pixels_to_coords(PointPixel { x, y }: PointPixel) -> PointGrid {
+/// * `PointGrid` - The nearest point the pointer can snap to, in grid
+///   coordinates.
+pub fn pixels_to_coords(PointPixel { x, y }: PointPixel, grid: &GridConfig) -> PointGrid {
+    let steps = grid.snap_steps();
+    let snap = |pixels: f32, origin: f32| {
+        ((pixels - origin) / (grid.spacing * steps as f32)).round() as i32 * steps
+    };
     PointGrid {
-        x: ((x - PIXEL_STEP) / (2. * PIXEL_STEP)).round() as i32,
-        y: ((y - PIXEL_STEP) / (2. * PIXEL_STEP)).round() as i32,
+        x: snap(x, grid.origin.x),
+        y: snap(y, grid.origin.y),
     }
 }
 
@@ -23,42 +29,78 @@ pub fn pixels_to_coords(PointPixel { x, y }: PointPixel) -> PointGrid {
 /// # Arguments
 ///
 /// * `PointGrid { x, y }` - A point in grid coordinates.
+/// * `grid` - The grid the point lies on.
 ///
 /// # Returns
 ///
 /// * `PointPixel` - The corresponding point in pixel coordinates.
-pub fn coords_to_pixels(PointGrid { x, y }: PointGrid) -> PointPixel {
+pub fn coords_to_pixels(PointGrid { x, y }: PointGrid, grid: &GridConfig) -> PointPixel {
     PointPixel {
-        x: x as f32 * 2. * PIXEL_STEP + PIXEL_STEP,
-        y: y as f32 * 2. * PIXEL_STEP + PIXEL_STEP,
+        x: x as f32 * grid.spacing + grid.origin.x,
+        y: y as f32 * grid.spacing + grid.origin.y,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::settings::PIXEL_STEP;
     use crate::types::{PointGrid, PointPixel};
 
     #[test]
     fn test_pixels_to_coords() {
+        let grid = GridConfig::default();
         let pixel_point = PointPixel { x: 10.0, y: 20.0 };
         let expected_grid_point = PointGrid {
-            x: ((10.0 - PIXEL_STEP) / (2. * PIXEL_STEP)).round() as i32,
-            y: ((20.0 - PIXEL_STEP) / (2. * PIXEL_STEP)).round() as i32,
+            x: ((10.0 - grid.origin.x) / grid.spacing).round() as i32,
+            y: ((20.0 - grid.origin.y) / grid.spacing).round() as i32,
         };
-        let grid_point = pixels_to_coords(pixel_point);
+        let grid_point = pixels_to_coords(pixel_point, &grid);
         assert_eq!(grid_point, expected_grid_point);
     }
 
     #[test]
     fn test_coords_to_pixels() {
+        let grid = GridConfig::default();
         let grid_point = PointGrid { x: 3, y: 4 };
         let expected_pixel_point = PointPixel {
-            x: 3_f32 * 2. * PIXEL_STEP + PIXEL_STEP,
-            y: 4_f32 * 2. * PIXEL_STEP + PIXEL_STEP,
+            x: 3_f32 * grid.spacing + grid.origin.x,
+            y: 4_f32 * grid.spacing + grid.origin.y,
         };
-        let pixel_point = coords_to_pixels(grid_point);
+        let pixel_point = coords_to_pixels(grid_point, &grid);
         assert_eq!(pixel_point, expected_pixel_point);
     }
+
+    #[test]
+    fn test_snapping() {
+        let mut grid = GridConfig {
+            spacing: 10.0,
+            origin: PointPixel { x: 0.0, y: 0.0 },
+            snapping: true,
+            subdivision: 4,
+        };
+        let pixel_point = PointPixel { x: 31.0, y: -14.0 };
+        assert_eq!(
+            pixels_to_coords(pixel_point, &grid),
+            PointGrid { x: 4, y: 0 }
+        );
+
+        grid.snapping = false;
+        assert_eq!(
+            pixels_to_coords(pixel_point, &grid),
+            PointGrid { x: 3, y: -1 }
+        );
+    }
+
+    #[test]
+    fn test_origin_offset() {
+        let grid = GridConfig {
+            origin: PointPixel { x: -20.0, y: 100.0 },
+            ..Default::default()
+        };
+        let grid_point = PointGrid { x: -2, y: 5 };
+        assert_eq!(
+            pixels_to_coords(coords_to_pixels(grid_point, &grid), &grid),
+            grid_point
+        );
+    }
 }
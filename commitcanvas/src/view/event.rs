@@ -1,10 +1,16 @@
-use crate::model::{EventHistory, Guid, ShapeConfig};
+use crate::model::{Document, EventHistory, Guid, ShapeConfig};
+use crate::settings::Settings;
 
 pub enum Event<'a> {
     Reload {
         shapes: Vec<(&'a Guid, &'a ShapeConfig)>,
+        settings: &'a Settings,
     },
     Modify {
         event: EventHistory,
     },
+    /// Sent after each completed user action, including undo and redo.
+    Checkpoint {
+        document: &'a Document,
+    },
 }
@@ -0,0 +1,171 @@
+//! The control harness shared by the integration tests: stand-ins for the
+//! browser parts of a [`Control`] and a view that keeps every shape.
+
+// Every test binary compiles this module, and none of them uses all of it.
+#![allow(dead_code)]
+
+use commitcanvas::control::editor::Editor;
+use commitcanvas::control::marker::Marker;
+use commitcanvas::control::marquee::Marquee;
+use commitcanvas::control::menu::MainMenuButton;
+use commitcanvas::control::selection::Selection;
+use commitcanvas::control::Control;
+use commitcanvas::control::MouseButton;
+use commitcanvas::model::{EventHistory, ShapeConfig, ShapeDetails};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::types::{Guid, PointGrid, PointPixel};
+use commitcanvas::view::{Event, View};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+pub struct TestMarker;
+
+impl Marker for TestMarker {
+    fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    #[allow(unused_variables)]
+    fn update(&self, p: PointPixel) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+pub struct TestSelection;
+
+impl Selection for TestSelection {
+    #[allow(unused_variables)]
+    fn new(
+        guids: &[Guid],
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    #[allow(unused_variables)]
+    fn update(
+        &mut self,
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+pub struct TestMarquee;
+
+impl Marquee for TestMarquee {
+    fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    #[allow(unused_variables)]
+    fn update(
+        &self,
+        start: PointPixel,
+        end: PointPixel,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+pub struct TestEditor {
+    content: String,
+}
+
+impl Editor for TestEditor {
+    #[allow(unused_variables)]
+    fn new(
+        guid: Guid,
+        config: &ShapeConfig,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let content = match &config.details {
+            ShapeDetails::Text(text) => text.content.clone(),
+            _ => String::new(),
+        };
+        Ok(Self { content })
+    }
+
+    fn content(&self) -> String {
+        self.content.clone()
+    }
+}
+
+/// Keeps the current state of every shape.
+pub struct TestView {
+    shapes: Shapes,
+}
+
+impl View for TestView {
+    fn process_event(&mut self, event: Event) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Event::Modify { event } = event {
+            let mut shapes = self.shapes.lock().unwrap();
+            match event {
+                EventHistory::Add { guid, config } => {
+                    shapes.insert(guid, config);
+                }
+                EventHistory::Modify { guid, to, .. } => {
+                    shapes.insert(guid, to);
+                }
+                EventHistory::Remove { guid, .. } => {
+                    shapes.remove(&guid);
+                }
+                EventHistory::Checkpoint => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+pub type TestControl = Control<TestMarker, TestSelection, TestMarquee, TestEditor>;
+
+/// The current state of every shape, by GUID.
+pub type Shapes = Arc<Mutex<HashMap<Guid, ShapeConfig>>>;
+
+/// Adds a [`TestView`] to `control` and returns the shapes it keeps.
+pub fn add_view(control: &mut TestControl) -> Shapes {
+    let shapes = Arc::new(Mutex::new(HashMap::new()));
+    control.add_view(Box::new(TestView {
+        shapes: shapes.clone(),
+    }));
+    shapes
+}
+
+pub fn setup() -> (TestControl, Shapes) {
+    let mut control = TestControl::new(Box::new(|_| Ok(())), Box::new(|_| Ok(())));
+    let shapes = add_view(&mut control);
+    (control, shapes)
+}
+
+/// Pixel position of the grid point `(x, y)`.
+pub fn at(x: i32, y: i32) -> (f32, f32) {
+    let grid = GridConfig::default();
+    (
+        grid.origin.x + x as f32 * grid.spacing,
+        grid.origin.y + y as f32 * grid.spacing,
+    )
+}
+
+/// Drags out a new shape with the tool `button` and returns its GUID.
+pub fn draw(
+    control: &mut TestControl,
+    button: MainMenuButton,
+    start: (i32, i32),
+    end: (i32, i32),
+) -> Guid {
+    control.set_button_state(button);
+    control.mouse_update(at(start.0, start.1));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_update(at(end.0, end.1));
+    control.mouse_up();
+    control.get_selection().unwrap()
+}
+
+pub fn draw_rect(control: &mut TestControl, start: (i32, i32), end: (i32, i32)) -> Guid {
+    draw(control, MainMenuButton::Rect, start, end)
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use commitcanvas::control::menu::MainMenuButton;
+use commitcanvas::control::ModificationType;
+use commitcanvas::model::{
+    Anchor, ArrowDetails, Binding, PartialDetails, Routing, ShapeConfig, ShapeDetails,
+};
+use commitcanvas::types::{Guid, PointGrid};
+use common::{at, draw, setup, TestControl};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+fn ends(shapes: &Mutex<HashMap<Guid, ShapeConfig>>, guid: Guid) -> (i32, i32, i32, i32) {
+    let config = &shapes.lock().unwrap()[&guid];
+    (config.start.x, config.start.y, config.end.x, config.end.y)
+}
+
+fn bindings(
+    shapes: &Mutex<HashMap<Guid, ShapeConfig>>,
+    guid: Guid,
+) -> (Option<Binding>, Option<Binding>) {
+    match &shapes.lock().unwrap()[&guid].details {
+        ShapeDetails::Arrow(ArrowDetails {
+            start_binding,
+            end_binding,
+            ..
+        }) => (*start_binding, *end_binding),
+        details => panic!("not an arrow: {:?}", details),
+    }
+}
+
+/// Two rectangles side by side with an arrow drawn from one to the other.
+fn connected(control: &mut TestControl) -> (Guid, Guid, Guid) {
+    let a = draw(control, MainMenuButton::Rect, (0, 0), (4, 4));
+    let b = draw(control, MainMenuButton::Rect, (10, 0), (14, 4));
+    let arrow = draw(control, MainMenuButton::Arrow, (4, 2), (10, 2));
+    (a, b, arrow)
+}
+
+#[test]
+fn test_attach_on_drop() {
+    let (mut control, shapes) = setup();
+    let (a, b, arrow) = connected(&mut control);
+    assert_eq!(
+        bindings(&shapes, arrow),
+        (
+            Some(Binding {
+                guid: a,
+                anchor: Anchor { x: 1.0, y: 0.5 },
+            }),
+            Some(Binding {
+                guid: b,
+                anchor: Anchor { x: 0.0, y: 0.5 },
+            }),
+        )
+    );
+
+    // Dragged off the shape, the end lets go; dropped on another, it attaches.
+    control.mouse_update(at(10, 2));
+    control.modify(ModificationType::BR);
+    control.mouse_update(at(8, 8));
+    control.mouse_up();
+    assert_eq!(bindings(&shapes, arrow).1, None);
+    control.modify(ModificationType::BR);
+    control.mouse_update(at(2, 4));
+    control.mouse_up();
+    assert_eq!(bindings(&shapes, arrow).1.map(|bound| bound.guid), Some(a));
+
+    let free = draw(&mut control, MainMenuButton::Arrow, (20, 20), (24, 20));
+    assert_eq!(bindings(&shapes, free), (None, None));
+}
+
+#[test]
+fn test_arrow_follows_moved_shape() {
+    let (mut control, shapes) = setup();
+    let (a, b, arrow) = connected(&mut control);
+
+    control.mouse_update(at(12, 2));
+    assert!(control.start_move(b));
+    control.mouse_update(at(14, 8));
+    control.mouse_up();
+    assert_eq!(ends(&shapes, arrow), (4, 2, 12, 8));
+
+    // Resizing moves the end with its anchor.
+    control.select(a);
+    control.mouse_update(at(4, 4));
+    control.modify(ModificationType::BR);
+    control.mouse_update(at(6, 8));
+    control.mouse_up();
+    assert_eq!(ends(&shapes, arrow), (6, 4, 12, 8));
+
+    // One undo step each brings the shape and the arrow back.
+    control.undo();
+    assert_eq!(ends(&shapes, arrow), (4, 2, 12, 8));
+    control.undo();
+    assert_eq!(ends(&shapes, arrow), (4, 2, 10, 2));
+    assert_eq!(bindings(&shapes, arrow).1.map(|bound| bound.guid), Some(b));
+}
+
+#[test]
+fn test_arrow_lets_go_of_removed_shape() {
+    let (mut control, shapes) = setup();
+    let (a, b, arrow) = connected(&mut control);
+
+    control.select(b);
+    control.delete();
+    assert_eq!(bindings(&shapes, arrow).1, None);
+    assert_eq!(ends(&shapes, arrow), (4, 2, 10, 2));
+
+    control.undo();
+    assert_eq!(bindings(&shapes, arrow).1.map(|bound| bound.guid), Some(b));
+    control.select(b);
+    control.mouse_update(at(12, 2));
+    assert!(control.start_move(b));
+    control.mouse_update(at(12, 4));
+    control.mouse_up();
+    assert_eq!(ends(&shapes, arrow), (4, 2, 10, 4));
+    assert_eq!(bindings(&shapes, arrow).0.map(|bound| bound.guid), Some(a));
+}
+
+#[test]
+fn test_paste_keeps_bindings_among_pasted_shapes() {
+    let (mut control, shapes) = setup();
+    let (a, _, arrow) = connected(&mut control);
+
+    control.select(a);
+    control.toggle_selection(arrow);
+    control.copy();
+    control.mouse_update(at(0, 20));
+    control.paste();
+    let pasted = control.selected().to_vec();
+    let (start, end) = bindings(&shapes, pasted[1]);
+    assert_eq!(start.map(|bound| bound.guid), Some(pasted[0]));
+    assert_eq!(end, None);
+    assert_eq!(ends(&shapes, pasted[1]), (4, 22, 10, 22));
+}
+
+#[test]
+fn test_elbow_connector_reroutes() {
+    let (mut control, shapes) = setup();
+    let (_, b, arrow) = connected(&mut control);
+    control.set_details(PartialDetails {
+        routing: Some(Routing::Elbow),
+        ..Default::default()
+    });
+    let route =
+        |shapes: &Mutex<HashMap<Guid, ShapeConfig>>| shapes.lock().unwrap()[&arrow].points();
+    assert_eq!(
+        route(&shapes),
+        vec![PointGrid { x: 4, y: 2 }, PointGrid { x: 10, y: 2 }]
+    );
+
+    // Moving the target bends the connector at right angles.
+    control.mouse_update(at(12, 2));
+    assert!(control.start_move(b));
+    control.mouse_update(at(12, 10));
+    control.mouse_up();
+    let bent = route(&shapes);
+    assert_eq!(bent.first(), Some(&PointGrid { x: 4, y: 2 }));
+    assert_eq!(bent.last(), Some(&PointGrid { x: 10, y: 10 }));
+    assert_eq!(bent.len(), 4);
+    assert!(bent
+        .windows(2)
+        .all(|segment| segment[0].x == segment[1].x || segment[0].y == segment[1].y));
+
+    control.undo();
+    assert_eq!(route(&shapes).len(), 2);
+}
@@ -0,0 +1,168 @@
+use commitcanvas::model::migrate::MigrationError;
+use commitcanvas::model::{
+    seed_for, ArrowDetails, Color, Dash, Document, DocumentError, DocumentFormat, Event, FontSize,
+    Model, Options, RectDetails, Roughness, ShapeConfig, ShapeDetails, TextDetails, Thickness,
+};
+use commitcanvas::settings::{GridConfig, Settings};
+use commitcanvas::types::Point;
+
+// Every fixture describes the same drawing, saved by the build that wrote the
+// corresponding document version.
+fn assert_fixture_drawing(model: &Model) {
+    assert_eq!(model.settings(), &Settings::default());
+    assert_eq!(
+        model.get_shape(0),
+        Some(&ShapeConfig {
+            start: Point { x: 1, y: 1 },
+            end: Point { x: 11, y: 6 },
+            details: ShapeDetails::Rect(RectDetails {
+                background: Color::Sky,
+                ..Default::default()
+            }),
+            options: Options {
+                stroke_color: Color::None,
+                roughness: Roughness::High,
+                thickness: Thickness::Medium,
+                dash: Dash::Solid,
+            },
+            seed: seed_for(0),
+        })
+    );
+    assert_eq!(
+        model.get_shape(1),
+        Some(&ShapeConfig {
+            start: Point { x: 11, y: 3 },
+            end: Point { x: 20, y: 3 },
+            details: ShapeDetails::Arrow(ArrowDetails::default()),
+            options: Options::default(),
+            seed: seed_for(1),
+        })
+    );
+    assert_eq!(model.get_shape(2), None);
+    assert_eq!(
+        model.get_shape(3),
+        Some(&ShapeConfig {
+            start: Point { x: 21, y: 2 },
+            end: Point { x: 34, y: 6 },
+            details: ShapeDetails::Text(TextDetails {
+                content: "main".to_string(),
+                font_size: FontSize::Large,
+                ..Default::default()
+            }),
+            options: Options {
+                stroke_color: Color::Rose,
+                roughness: Roughness::Low,
+                thickness: Thickness::Thick,
+                dash: Dash::Solid,
+            },
+            seed: seed_for(3),
+        })
+    );
+}
+
+fn load(input: &str, format: DocumentFormat) -> Model {
+    let document = Document::parse(input, format).expect("failed to parse fixture");
+    assert_eq!(document.version, Document::VERSION);
+    Model::from_document(document).expect("failed to load fixture")
+}
+
+macro_rules! test_load_fixture {
+    ($($name:ident: $file:expr, $format:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let mut model = load(include_str!($file), $format);
+                assert_fixture_drawing(&model);
+
+                let guid = model.process_event(Event::Add {
+                    guid: None,
+                    config: model.get_shape(1).unwrap().clone(),
+                });
+                assert_eq!(guid, Some(4));
+            }
+        )*
+    };
+}
+
+test_load_fixture! {
+    test_load_v1_yaml: "fixtures/document_v1.yaml", DocumentFormat::Yaml,
+    test_load_v1_json: "fixtures/document_v1.json", DocumentFormat::Json,
+    test_load_v2_yaml: "fixtures/document_v2.yaml", DocumentFormat::Yaml,
+    test_load_v2_json: "fixtures/document_v2.json", DocumentFormat::Json,
+    test_load_v3_yaml: "fixtures/document_v3.yaml", DocumentFormat::Yaml,
+    test_load_v3_json: "fixtures/document_v3.json", DocumentFormat::Json,
+    test_load_v4_yaml: "fixtures/document_v4.yaml", DocumentFormat::Yaml,
+    test_load_v4_json: "fixtures/document_v4.json", DocumentFormat::Json,
+    test_load_v5_yaml: "fixtures/document_v5.yaml", DocumentFormat::Yaml,
+    test_load_v5_json: "fixtures/document_v5.json", DocumentFormat::Json,
+    test_load_v6_yaml: "fixtures/document_v6.yaml", DocumentFormat::Yaml,
+    test_load_v6_json: "fixtures/document_v6.json", DocumentFormat::Json,
+    test_load_v7_yaml: "fixtures/document_v7.yaml", DocumentFormat::Yaml,
+    test_load_v7_json: "fixtures/document_v7.json", DocumentFormat::Json,
+    test_load_v8_yaml: "fixtures/document_v8.yaml", DocumentFormat::Yaml,
+    test_load_v8_json: "fixtures/document_v8.json", DocumentFormat::Json,
+    test_load_v9_yaml: "fixtures/document_v9.yaml", DocumentFormat::Yaml,
+    test_load_v9_json: "fixtures/document_v9.json", DocumentFormat::Json,
+    test_load_v10_yaml: "fixtures/document_v10.yaml", DocumentFormat::Yaml,
+    test_load_v10_json: "fixtures/document_v10.json", DocumentFormat::Json,
+}
+
+#[test]
+fn test_resave_fixture() {
+    let model = load(
+        include_str!("fixtures/document_v10.yaml"),
+        DocumentFormat::Yaml,
+    );
+    let saved = model
+        .to_document()
+        .serialize(DocumentFormat::Yaml)
+        .expect("failed to save document");
+    assert_fixture_drawing(&load(&saved, DocumentFormat::Yaml));
+}
+
+#[test]
+fn test_settings_are_saved() {
+    let mut model = Model::new();
+    let settings = Settings {
+        grid: GridConfig {
+            spacing: 20.0,
+            subdivision: 4,
+            snapping: false,
+            ..Default::default()
+        },
+    };
+    model.set_settings(settings);
+    let saved = model
+        .to_document()
+        .serialize(DocumentFormat::Json)
+        .expect("failed to save document");
+    let loaded = Model::from_document(
+        Document::parse(&saved, DocumentFormat::Json).expect("failed to parse document"),
+    )
+    .expect("failed to load document");
+    assert_eq!(loaded.settings(), &settings);
+}
+
+#[test]
+fn test_reject_newer_version() {
+    let input = format!(
+        "version: {}\nnext_guid: 0\nshapes: []\n",
+        Document::VERSION + 1
+    );
+    match Document::parse(&input, DocumentFormat::Yaml) {
+        Err(DocumentError::Migration(MigrationError::TooNew { version, supported })) => {
+            assert_eq!(version, Document::VERSION + 1);
+            assert_eq!(supported, Document::VERSION);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_reject_missing_version() {
+    let result = Document::parse(r#"{ "next_guid": 0, "shapes": [] }"#, DocumentFormat::Json);
+    assert!(matches!(
+        result,
+        Err(DocumentError::Migration(MigrationError::MissingVersion))
+    ));
+}
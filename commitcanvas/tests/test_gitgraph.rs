@@ -0,0 +1,97 @@
+use commitcanvas::gitgraph::{self, Script};
+use commitcanvas::model::{Color, Model, ShapeDetails};
+use commitcanvas::types::PointGrid;
+
+const SCRIPT: &str = "\
+gitGraph
+commit msg: \"Initial commit\"
+branch develop
+commit id: feature
+checkout main
+merge develop msg: Release
+tag v1.0
+";
+
+fn count(model: &Model, matches: impl Fn(&ShapeDetails) -> bool) -> usize {
+    model
+        .shapes()
+        .filter(|(_, config)| matches(&config.details))
+        .count()
+}
+
+#[test]
+fn test_draw() {
+    let mut model = Model::new();
+    let origin = PointGrid { x: 4, y: 10 };
+    let guids = gitgraph::draw(SCRIPT, &mut model, origin).unwrap();
+    assert_eq!(model.shapes().count(), guids.len());
+
+    // Three commits, the branches main and develop and the tag.
+    let nodes = count(&model, |d| matches!(d, ShapeDetails::Ellipse(_)));
+    assert_eq!(nodes, 3);
+    let rects = count(&model, |d| matches!(d, ShapeDetails::Rect(_)));
+    assert_eq!(rects, 3);
+    let texts = count(&model, |d| matches!(d, ShapeDetails::Text(_)));
+    assert_eq!(texts, 3 + 3);
+    // The merge commit has two parents.
+    let arrows = count(&model, |d| matches!(d, ShapeDetails::Arrow(_)));
+    assert_eq!(arrows, 3);
+    // The fork and the merge follow the lanes instead of cutting across.
+    let bent = count(
+        &model,
+        |d| matches!(d, ShapeDetails::Arrow(arrow) if arrow.waypoints.len() == 2),
+    );
+    assert_eq!(bent, 2);
+    for (_, config) in model.shapes() {
+        if let ShapeDetails::Arrow(_) = config.details {
+            assert!(config
+                .points()
+                .windows(2)
+                .all(|segment| segment[0].x == segment[1].x || segment[0].y == segment[1].y));
+        }
+    }
+
+    // The first commit is centered at the origin in the color of main.
+    let first = model
+        .shapes()
+        .find(|(_, config)| config.start == PointGrid { x: 3, y: 9 })
+        .unwrap();
+    assert_eq!(first.1.end, PointGrid { x: 5, y: 11 });
+    assert!(matches!(
+        &first.1.details,
+        ShapeDetails::Ellipse(node) if node.background == Color::Sky
+    ));
+
+    model.undo();
+    assert_eq!(model.shapes().count(), 0);
+}
+
+#[test]
+fn test_regenerate() {
+    let script: Script = SCRIPT.parse().unwrap();
+    let regenerated: Script = script.to_string().parse().unwrap();
+    assert_eq!(regenerated, script);
+
+    let origin = PointGrid { x: 0, y: 0 };
+    let mut drawn = Model::new();
+    drawn.process_events(script.events(origin).unwrap());
+    let mut redrawn = Model::new();
+    redrawn.process_events(regenerated.events(origin).unwrap());
+    assert_eq!(redrawn.to_document(), drawn.to_document());
+}
+
+#[test]
+fn test_errors_are_located() {
+    let mut model = Model::new();
+    let error = gitgraph::draw(
+        "commit\ncheckout develop",
+        &mut model,
+        PointGrid { x: 0, y: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 1: no branch named develop"
+    );
+    assert_eq!(model.shapes().count(), 0);
+}
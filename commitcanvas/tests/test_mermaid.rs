@@ -0,0 +1,153 @@
+use commitcanvas::gitgraph::mermaid::{export, import};
+use commitcanvas::model::{
+    ArrowDetails, Event, Model, Options, RectDetails, ShapeConfig, ShapeDetails, Thickness,
+};
+use commitcanvas::types::PointGrid;
+
+const ORIGIN: PointGrid = PointGrid { x: 0, y: 0 };
+
+const DIAGRAM: &str = "\
+gitGraph LR:
+    commit id: \"Alpha\"
+    commit id: \"Beta\" type: HIGHLIGHT tag: \"v1.0\"
+    branch develop
+    commit id: \"Gamma\" type: REVERSE
+    checkout main
+    commit id: \"Delta\"
+    cherry-pick id: \"Gamma\"
+    merge develop id: \"Merge\" tag: \"v2.0\"
+";
+
+fn imported(source: &str) -> Model {
+    let mut model = Model::new();
+    import(source, &mut model, ORIGIN).unwrap();
+    model
+}
+
+#[test]
+fn test_import() {
+    let mut model = Model::new();
+    let guids = import(DIAGRAM, &mut model, ORIGIN).unwrap();
+    assert_eq!(model.shapes().count(), guids.len());
+
+    let nodes: Vec<&ShapeConfig> = model
+        .shapes()
+        .map(|(_, config)| config)
+        .filter(|config| {
+            matches!(config.details, ShapeDetails::Ellipse(_))
+                && config.end.x - config.start.x == 2
+                && config.end.y - config.start.y == 2
+        })
+        .collect();
+    assert_eq!(nodes.len(), 6);
+    // Beta is highlighted.
+    let thick = nodes
+        .iter()
+        .filter(|config| config.options.thickness == Thickness::Thick)
+        .count();
+    assert_eq!(thick, 1);
+
+    let texts: Vec<String> = model
+        .shapes()
+        .filter_map(|(_, config)| match &config.details {
+            ShapeDetails::Text(text) => Some(text.content.clone()),
+            _ => None,
+        })
+        .collect();
+    for text in [
+        "Alpha",
+        "Beta",
+        "Gamma",
+        "cherry-pick: Gamma",
+        "v1.0",
+        "v2.0",
+        "main",
+        "develop",
+    ] {
+        assert!(texts.iter().any(|t| t == text), "missing {}", text);
+    }
+
+    model.undo();
+    assert_eq!(model.shapes().count(), 0);
+}
+
+#[test]
+fn test_round_trip() {
+    let exported = export(&imported(DIAGRAM));
+    assert_eq!(exported.mermaid, DIAGRAM);
+    assert!(exported.skipped.is_empty());
+}
+
+#[test]
+fn test_main_branch_name() {
+    let diagram = "\
+%%{init: {'gitGraph': {'mainBranchName': 'master'}}}%%
+gitGraph TB:
+    commit id: \"a\"
+    branch feature
+    commit id: \"b\"
+    checkout master
+    merge feature id: \"c\"
+";
+    let model = imported(&format!("```mermaid\n{}```\n", diagram));
+    let texts: Vec<_> = model
+        .shapes()
+        .filter_map(|(_, config)| match &config.details {
+            ShapeDetails::Text(text) => Some(text.content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(texts.contains(&"master"));
+    assert!(!texts.contains(&"main"));
+    assert_eq!(export(&model).mermaid, diagram);
+}
+
+#[test]
+fn test_hand_drawn_shapes_are_skipped() {
+    let mut model = imported(DIAGRAM);
+    let add = |start, end, details| Event::Add {
+        guid: None,
+        config: ShapeConfig {
+            start,
+            end,
+            details,
+            options: Options::default(),
+            seed: 0,
+        },
+    };
+    let sketch = model.process_events([
+        // A box around the whole diagram.
+        add(
+            PointGrid { x: -10, y: -10 },
+            PointGrid { x: 70, y: 20 },
+            ShapeDetails::Rect(RectDetails::default()),
+        ),
+        // An arrow pointing at nothing.
+        add(
+            PointGrid { x: 80, y: 0 },
+            PointGrid { x: 90, y: 0 },
+            ShapeDetails::Arrow(ArrowDetails::default()),
+        ),
+        // A second root can't be replayed after the first commit.
+        add(
+            PointGrid { x: 99, y: -1 },
+            PointGrid { x: 101, y: 1 },
+            ShapeDetails::Rect(RectDetails::default()),
+        ),
+    ]);
+
+    let exported = export(&model);
+    assert_eq!(exported.mermaid, DIAGRAM);
+    assert_eq!(exported.skipped, sketch);
+}
+
+#[test]
+fn test_import_errors() {
+    let mut model = Model::new();
+    let error = import("gitGraph\n  commit\n  merge develop", &mut model, ORIGIN).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 3, column 3: no branch named develop"
+    );
+    assert_eq!(model.shapes().count(), 0);
+}
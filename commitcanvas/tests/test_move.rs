@@ -0,0 +1,61 @@
+mod common;
+
+use commitcanvas::control::menu::MainMenuButton;
+use commitcanvas::control::MouseButton;
+use commitcanvas::types::PointGrid;
+use common::setup;
+
+macro_rules! test_move {
+    ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (mut control, shapes) = setup();
+
+                control.set_button_state($value);
+                control.mouse_update((0.0, 0.0));
+                control.mouse_down(MouseButton::Left);
+                control.mouse_update((30.0, 30.0));
+                control.mouse_up();
+
+                let guid = control.get_selection().unwrap();
+
+                assert!(control.start_move(guid));
+                control.mouse_update((54.0, 54.0));
+                control.mouse_update((78.0, 90.0));
+                control.mouse_up();
+
+                assert_eq!(control.get_selection(), Some(guid));
+                let moved = shapes.lock().unwrap()[&guid].clone();
+                assert_eq!(moved.start, PointGrid { x: 3, y: 4 });
+                assert_eq!(moved.end, PointGrid { x: 6, y: 7 });
+
+                control.undo();
+                let reverted = shapes.lock().unwrap()[&guid].clone();
+                assert_eq!(reverted.start, PointGrid { x: -1, y: -1 });
+                assert_eq!(reverted.end, PointGrid { x: 2, y: 2 });
+            }
+        )*
+    };
+}
+
+test_move! {
+    test_move_arrow: MainMenuButton::Arrow,
+    test_move_rect: MainMenuButton::Rect,
+    test_move_ellipse: MainMenuButton::Ellipse,
+}
+
+#[test]
+fn test_no_move_while_drawing() {
+    let (mut control, _) = setup();
+
+    control.set_button_state(MainMenuButton::Rect);
+    control.mouse_update((0.0, 0.0));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_update((30.0, 30.0));
+    control.mouse_up();
+    let guid = control.get_selection().unwrap();
+
+    control.set_button_state(MainMenuButton::Rect);
+    assert!(!control.start_move(guid));
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use commitcanvas::control::menu::MainMenuButton;
+use commitcanvas::control::{ModificationType, MouseButton};
+use commitcanvas::model::ShapeConfig;
+use commitcanvas::types::Guid;
+use common::{at, draw_rect, setup, TestControl};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+fn corners(shapes: &Mutex<HashMap<Guid, ShapeConfig>>, guid: Guid) -> Option<(i32, i32, i32, i32)> {
+    shapes
+        .lock()
+        .unwrap()
+        .get(&guid)
+        .map(|config| (config.start.x, config.start.y, config.end.x, config.end.y))
+}
+
+fn marquee(control: &mut TestControl, start: (i32, i32), end: (i32, i32)) {
+    control.mouse_update(at(start.0, start.1));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_update(at(end.0, end.1));
+    control.mouse_up();
+}
+
+#[test]
+fn test_shift_click_toggles() {
+    let (mut control, _) = setup();
+    let a = draw_rect(&mut control, (0, 0), (2, 2));
+    let b = draw_rect(&mut control, (4, 4), (6, 6));
+
+    assert!(control.toggle_selection(a));
+    assert_eq!(control.selected(), &[b, a]);
+    assert_eq!(control.get_selection(), None);
+
+    assert!(control.toggle_selection(b));
+    assert_eq!(control.selected(), &[a]);
+
+    control.set_button_state(MainMenuButton::Arrow);
+    assert!(!control.toggle_selection(b));
+    assert!(control.selected().is_empty());
+}
+
+#[test]
+fn test_marquee_selects_intersecting_shapes() {
+    let (mut control, _) = setup();
+    let a = draw_rect(&mut control, (0, 0), (2, 2));
+    let b = draw_rect(&mut control, (4, 4), (6, 6));
+    draw_rect(&mut control, (10, 10), (12, 12));
+
+    marquee(&mut control, (-1, -1), (5, 5));
+    assert_eq!(control.selected(), &[a, b]);
+
+    // A click without dragging clears the selection.
+    marquee(&mut control, (1, 1), (1, 1));
+    assert!(control.selected().is_empty());
+}
+
+#[test]
+fn test_resize_selection() {
+    let (mut control, shapes) = setup();
+    let a = draw_rect(&mut control, (0, 0), (2, 2));
+    let b = draw_rect(&mut control, (4, 4), (6, 6));
+    marquee(&mut control, (-1, -1), (5, 5));
+
+    control.modify(ModificationType::BR);
+    control.mouse_update(at(9, 9));
+    control.mouse_update(at(12, 12));
+    control.mouse_up();
+
+    assert_eq!(corners(&shapes, a), Some((0, 0, 4, 4)));
+    assert_eq!(corners(&shapes, b), Some((8, 8, 12, 12)));
+    assert_eq!(control.selected(), &[a, b]);
+
+    control.undo();
+    assert_eq!(corners(&shapes, a), Some((0, 0, 2, 2)));
+    assert_eq!(corners(&shapes, b), Some((4, 4, 6, 6)));
+}
+
+#[test]
+fn test_move_selection() {
+    let (mut control, shapes) = setup();
+    let a = draw_rect(&mut control, (0, 0), (2, 2));
+    let b = draw_rect(&mut control, (4, 4), (6, 6));
+    marquee(&mut control, (-1, -1), (5, 5));
+
+    control.mouse_update(at(5, 5));
+    assert!(control.start_move(b));
+    control.mouse_update(at(8, 6));
+    control.mouse_up();
+
+    assert_eq!(corners(&shapes, a), Some((3, 1, 5, 3)));
+    assert_eq!(corners(&shapes, b), Some((7, 5, 9, 7)));
+
+    control.undo();
+    assert_eq!(corners(&shapes, a), Some((0, 0, 2, 2)));
+    assert_eq!(corners(&shapes, b), Some((4, 4, 6, 6)));
+}
+
+#[test]
+fn test_copy_paste_delete_selection() {
+    let (mut control, shapes) = setup();
+    draw_rect(&mut control, (0, 0), (2, 2));
+    draw_rect(&mut control, (4, 4), (6, 6));
+    marquee(&mut control, (-1, -1), (5, 5));
+
+    control.copy();
+    control.mouse_update(at(20, 20));
+    control.paste();
+    let pasted = control.selected().to_vec();
+    assert_eq!(pasted.len(), 2);
+    assert_eq!(corners(&shapes, pasted[0]), Some((20, 20, 22, 22)));
+    assert_eq!(corners(&shapes, pasted[1]), Some((24, 24, 26, 26)));
+
+    control.delete();
+    assert!(control.selected().is_empty());
+    assert_eq!(shapes.lock().unwrap().len(), 2);
+
+    control.undo();
+    assert_eq!(shapes.lock().unwrap().len(), 4);
+    control.undo();
+    assert_eq!(shapes.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn test_cut_selection() {
+    let (mut control, shapes) = setup();
+    draw_rect(&mut control, (0, 0), (2, 2));
+    draw_rect(&mut control, (4, 4), (6, 6));
+    marquee(&mut control, (-1, -1), (5, 5));
+
+    control.cut();
+    assert!(shapes.lock().unwrap().is_empty());
+
+    control.mouse_update(at(10, 10));
+    control.paste();
+    assert_eq!(shapes.lock().unwrap().len(), 2);
+    assert_eq!(control.selected().len(), 2);
+}
@@ -1,11 +1,13 @@
+use commitcanvas::control::editor::Editor;
 use commitcanvas::control::marker::Marker;
+use commitcanvas::control::marquee::Marquee;
 use commitcanvas::control::menu::MainMenuButton;
 use commitcanvas::control::selection::Selection;
 use commitcanvas::control::Control;
 use commitcanvas::control::MouseButton;
-use commitcanvas::model::{EventHistory, ShapeConfig};
-use commitcanvas::settings::PIXEL_STEP;
-use commitcanvas::types::{Guid, PointPixel};
+use commitcanvas::model::{EventHistory, ShapeConfig, ShapeDetails};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::types::{Guid, PointGrid, PointPixel};
 use commitcanvas::view::{Event, View};
 use std::error::Error;
 use std::sync::{Arc, Mutex};
@@ -27,16 +29,66 @@ struct TestSelection;
 
 impl Selection for TestSelection {
     #[allow(unused_variables)]
-    fn new(guid: Guid, config: &ShapeConfig) -> Result<Self, Box<dyn Error + Send + Sync>> {
+    fn new(
+        guids: &[Guid],
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         Ok(Self)
     }
 
     #[allow(unused_variables)]
-    fn update(&mut self, config: &ShapeConfig) -> Result<(), Box<dyn Error + Send + Sync>> {
+    fn update(
+        &mut self,
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
         Ok(())
     }
 }
 
+struct TestMarquee;
+
+impl Marquee for TestMarquee {
+    fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    #[allow(unused_variables)]
+    fn update(
+        &self,
+        start: PointPixel,
+        end: PointPixel,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+struct TestEditor {
+    content: String,
+}
+
+impl Editor for TestEditor {
+    #[allow(unused_variables)]
+    fn new(
+        guid: Guid,
+        config: &ShapeConfig,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let content = match &config.details {
+            ShapeDetails::Text(text) => text.content.clone(),
+            _ => String::new(),
+        };
+        Ok(Self { content })
+    }
+
+    fn content(&self) -> String {
+        self.content.clone()
+    }
+}
+
 struct TestView {
     events: Arc<Mutex<Vec<EventHistory>>>,
 }
@@ -56,17 +108,18 @@ macro_rules! test_selection_remains_after_resize {
         $(
             #[test]
             fn $name() {
-                let mut control = Control::<TestMarker, TestSelection>::new(Box::new(|_| Ok({})));
+                let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(Box::new(|_| Ok({})), Box::new(|_| Ok(())));
                 let events = Arc::new(Mutex::new(vec![]));
                 let view = TestView {
                     events: events.clone(),
                 };
                 control.add_view(Box::new(view));
+                let step = GridConfig::default().spacing / 2.0;
 
                 control.set_button_state($value);
                 control.mouse_update((0.0, 0.0));
                 control.mouse_down(MouseButton::Left);
-                control.mouse_update((PIXEL_STEP, PIXEL_STEP));
+                control.mouse_update((step, step));
                 control.mouse_up();
 
                 assert_eq!(events.lock().unwrap().len(), 3);
@@ -74,14 +127,23 @@ macro_rules! test_selection_remains_after_resize {
                 let guid = events.lock().unwrap()[0].guid().unwrap();
 
                 control.select(guid);
-                control.modify(guid, commitcanvas::control::ModificationType::T);
-                control.mouse_update((2.0 * PIXEL_STEP, 2.0 * PIXEL_STEP));
+                control.modify(commitcanvas::control::ModificationType::T);
+                control.mouse_update((2.0 * step, 2.0 * step));
                 control.mouse_up();
 
                 let selected = control.get_selection();
 
                 assert!(selected.is_some());
                 assert_eq!(selected.unwrap(), guid);
+
+                let last = events.lock().unwrap().last().cloned();
+                match last {
+                    Some(EventHistory::Modify { to, .. }) => {
+                        assert_eq!(to.start, PointGrid { x: -1, y: 1 });
+                        assert_eq!(to.end, PointGrid { x: 0, y: 0 });
+                    }
+                    _ => panic!("last event is not a modification"),
+                }
             }
         )*
 
@@ -91,6 +153,7 @@ macro_rules! test_selection_remains_after_resize {
 test_selection_remains_after_resize! {
     test_selection_remains_after_resize_arrow: MainMenuButton::Arrow,
     test_selection_remains_after_resize_rect: MainMenuButton::Rect,
+    test_selection_remains_after_resize_ellipse: MainMenuButton::Ellipse,
 }
 
 macro_rules! test_selected_after_creation {
@@ -98,17 +161,18 @@ macro_rules! test_selected_after_creation {
         $(
             #[test]
             fn $name() {
-                let mut control = Control::<TestMarker, TestSelection>::new(Box::new(|_| Ok({})));
+                let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(Box::new(|_| Ok({})), Box::new(|_| Ok(())));
                 let events = Arc::new(Mutex::new(vec![]));
                 let view = TestView {
                     events: events.clone(),
                 };
                 control.add_view(Box::new(view));
+                let step = GridConfig::default().spacing / 2.0;
 
                 control.set_button_state($value);
                 control.mouse_update((0.0, 0.0));
                 control.mouse_down(MouseButton::Left);
-                control.mouse_update((PIXEL_STEP, PIXEL_STEP));
+                control.mouse_update((step, step));
                 control.mouse_up();
 
                 assert_eq!(events.lock().unwrap().len(), 3);
@@ -128,6 +192,7 @@ macro_rules! test_selected_after_creation {
 test_selected_after_creation! {
     test_selected_after_creation_arrow: MainMenuButton::Arrow,
     test_selected_after_creation_rect: MainMenuButton::Rect,
+    test_selected_after_creation_ellipse: MainMenuButton::Ellipse,
 }
 
 macro_rules! test_selection_removed_on_random_click {
@@ -135,17 +200,18 @@ macro_rules! test_selection_removed_on_random_click {
         $(
             #[test]
             fn $name() {
-                let mut control = Control::<TestMarker, TestSelection>::new(Box::new(|_| Ok({})));
+                let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(Box::new(|_| Ok({})), Box::new(|_| Ok(())));
                 let events = Arc::new(Mutex::new(vec![]));
                 let view = TestView {
                     events: events.clone(),
                 };
                 control.add_view(Box::new(view));
+                let step = GridConfig::default().spacing / 2.0;
 
                 control.set_button_state($value);
                 control.mouse_update((0.0, 0.0));
                 control.mouse_down(MouseButton::Left);
-                control.mouse_update((PIXEL_STEP, PIXEL_STEP));
+                control.mouse_update((step, step));
                 control.mouse_up();
 
                 assert_eq!(events.lock().unwrap().len(), 3);
@@ -156,7 +222,7 @@ macro_rules! test_selection_removed_on_random_click {
                 assert!(selected.is_some());
                 assert_eq!(selected.unwrap(), guid);
 
-                control.mouse_update((2.0 * PIXEL_STEP, 2.0 * PIXEL_STEP));
+                control.mouse_update((2.0 * step, 2.0 * step));
                 control.mouse_down(MouseButton::Left);
                 control.mouse_up();
 
@@ -171,4 +237,5 @@ macro_rules! test_selection_removed_on_random_click {
 test_selection_removed_on_random_click! {
     test_selection_removed_on_random_click_arrow: MainMenuButton::Arrow,
     test_selection_removed_on_random_click_rect: MainMenuButton::Rect,
+    test_selection_removed_on_random_click_ellipse: MainMenuButton::Ellipse,
 }
@@ -0,0 +1,200 @@
+mod common;
+
+use commitcanvas::control::menu::{MainMenuButton, Style};
+use commitcanvas::control::MouseButton;
+use commitcanvas::model::{
+    Color, FillStyle, FontSize, PartialDetails, PartialOptions, ShapeConfig, ShapeDetails,
+    Thickness,
+};
+use commitcanvas::types::{Guid, PointGrid};
+use common::{add_view, at, draw, draw_rect, Shapes, TestControl};
+use std::sync::{Arc, Mutex};
+
+fn setup() -> (TestControl, Shapes, Arc<Mutex<Vec<Style>>>) {
+    let styles = Arc::new(Mutex::new(Vec::new()));
+    let menu_styles = styles.clone();
+    let mut control = TestControl::new(
+        Box::new(|_| Ok(())),
+        Box::new(move |style| {
+            menu_styles.lock().unwrap().push(style.clone());
+            Ok(())
+        }),
+    );
+    let shapes = add_view(&mut control);
+    (control, shapes, styles)
+}
+
+fn draw_text(control: &mut TestControl, start: (i32, i32), end: (i32, i32)) -> Guid {
+    draw(control, MainMenuButton::Text, start, end)
+}
+
+fn shape(shapes: &Shapes, guid: Guid) -> ShapeConfig {
+    shapes.lock().unwrap()[&guid].clone()
+}
+
+#[test]
+fn test_set_options_on_selection() {
+    let (mut control, shapes, _) = setup();
+    let rect = draw_rect(&mut control, (0, 0), (4, 4));
+    let text = draw_text(&mut control, (10, 0), (14, 2));
+    control.toggle_selection(rect);
+    assert_eq!(control.selected(), &[text, rect]);
+
+    control.set_options(PartialOptions {
+        stroke_color: Some(Color::Blue),
+        thickness: Some(Thickness::Thick),
+        ..Default::default()
+    });
+    for guid in [rect, text] {
+        let options = shape(&shapes, guid).options;
+        assert_eq!(options.stroke_color, Color::Blue);
+        assert_eq!(options.thickness, Thickness::Thick);
+    }
+
+    // Both shapes change in a single undo step.
+    control.undo();
+    for guid in [rect, text] {
+        assert_eq!(shape(&shapes, guid).options, Default::default());
+    }
+    control.redo();
+    for guid in [rect, text] {
+        assert_eq!(shape(&shapes, guid).options.stroke_color, Color::Blue);
+    }
+}
+
+#[test]
+fn test_set_details_on_selection() {
+    let (mut control, shapes, _) = setup();
+    let rect = draw_rect(&mut control, (0, 0), (4, 4));
+    let text = draw_text(&mut control, (10, 0), (14, 2));
+    control.toggle_selection(rect);
+
+    control.set_details(PartialDetails {
+        background: Some(Color::Teal),
+        ..Default::default()
+    });
+    assert!(matches!(
+        shape(&shapes, rect).details,
+        ShapeDetails::Rect(ref d) if d.background == Color::Teal
+    ));
+    assert!(matches!(
+        shape(&shapes, text).details,
+        ShapeDetails::Text(ref d) if d.font_size == FontSize::default()
+    ));
+
+    control.set_details(PartialDetails {
+        font_size: Some(FontSize::Large),
+        ..Default::default()
+    });
+    assert!(matches!(
+        shape(&shapes, text).details,
+        ShapeDetails::Text(ref d) if d.font_size == FontSize::Large
+    ));
+
+    control.undo();
+    assert!(matches!(
+        shape(&shapes, text).details,
+        ShapeDetails::Text(ref d) if d.font_size == FontSize::default()
+    ));
+    assert!(matches!(
+        shape(&shapes, rect).details,
+        ShapeDetails::Rect(ref d) if d.background == Color::Teal
+    ));
+}
+
+#[test]
+fn test_reshuffle() {
+    let (mut control, shapes, _) = setup();
+    let first = draw_rect(&mut control, (0, 0), (4, 4));
+    let second = draw_rect(&mut control, (0, 0), (4, 4));
+    let seed = shape(&shapes, first).seed;
+    assert_ne!(seed, shape(&shapes, second).seed);
+
+    control.select(first);
+    control.reshuffle();
+    let reshuffled = shape(&shapes, first);
+    assert_ne!(reshuffled.seed, seed);
+    assert_eq!(reshuffled.start, PointGrid { x: 0, y: 0 });
+    assert_eq!(reshuffled.end, PointGrid { x: 4, y: 4 });
+
+    // Moving keeps the seed, undo brings back the old one.
+    control.start_move(first);
+    control.mouse_update(at(6, 6));
+    control.mouse_up();
+    assert_ne!(shape(&shapes, first).start, reshuffled.start);
+    assert_eq!(shape(&shapes, first).seed, reshuffled.seed);
+    control.undo();
+    control.undo();
+    assert_eq!(shape(&shapes, first).seed, seed);
+}
+
+#[test]
+fn test_style_applies_to_new_shapes() {
+    let (mut control, shapes, _) = setup();
+    control.set_options(PartialOptions {
+        stroke_color: Some(Color::Red),
+        ..Default::default()
+    });
+    control.set_details(PartialDetails {
+        background: Some(Color::Amber),
+        fill: Some(FillStyle::CrossHatch),
+        font_size: Some(FontSize::Small),
+        ..Default::default()
+    });
+    assert_eq!(control.style().fill, FillStyle::CrossHatch);
+
+    let rect = draw_rect(&mut control, (0, 0), (4, 4));
+    let config = shape(&shapes, rect);
+    assert_eq!(config.options.stroke_color, Color::Red);
+    assert!(matches!(
+        config.details,
+        ShapeDetails::Rect(ref d) if d.background == Color::Amber && d.fill == FillStyle::CrossHatch
+    ));
+
+    let text = draw_text(&mut control, (10, 0), (14, 2));
+    assert!(matches!(
+        shape(&shapes, text).details,
+        ShapeDetails::Text(ref d) if d.font_size == FontSize::Small
+    ));
+}
+
+#[test]
+fn test_style_menu_shows_selection() {
+    let (mut control, _, styles) = setup();
+    let rect = draw_rect(&mut control, (0, 0), (4, 4));
+    control.set_details(PartialDetails {
+        background: Some(Color::Rose),
+        ..Default::default()
+    });
+    assert_eq!(
+        styles.lock().unwrap().last().unwrap().background,
+        Color::Rose
+    );
+
+    // Without a selection the menu shows the style of the next shape, which
+    // kept the background.
+    control.set_button_state(MainMenuButton::Select);
+    control.mouse_update(at(20, 20));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_up();
+    assert!(control.selected().is_empty());
+    assert_eq!(
+        styles.lock().unwrap().last().unwrap().background,
+        Color::Rose
+    );
+
+    control.set_details(PartialDetails {
+        background: Some(Color::Sky),
+        ..Default::default()
+    });
+    assert_eq!(
+        styles.lock().unwrap().last().unwrap().background,
+        Color::Sky
+    );
+
+    control.select(rect);
+    assert_eq!(
+        styles.lock().unwrap().last().unwrap().background,
+        Color::Rose
+    );
+}
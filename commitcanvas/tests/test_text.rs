@@ -1,11 +1,15 @@
+use commitcanvas::control::editor::Editor;
 use commitcanvas::control::marker::Marker;
+use commitcanvas::control::marquee::Marquee;
 use commitcanvas::control::menu::MainMenuButton;
 use commitcanvas::control::selection::Selection;
 use commitcanvas::control::Control;
 use commitcanvas::control::MouseButton;
 use commitcanvas::model::{EventHistory, ShapeConfig, ShapeDetails};
-use commitcanvas::types::{Guid, PointPixel};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::types::{Guid, PointGrid, PointPixel};
 use commitcanvas::view::{Event, View};
+use std::cell::RefCell;
 use std::error::Error;
 use std::sync::{Arc, Mutex};
 
@@ -26,16 +30,75 @@ struct TestSelection;
 
 impl Selection for TestSelection {
     #[allow(unused_variables)]
-    fn new(guid: Guid, config: &ShapeConfig) -> Result<Self, Box<dyn Error + Send + Sync>> {
+    fn new(
+        guids: &[Guid],
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         Ok(Self)
     }
 
     #[allow(unused_variables)]
-    fn update(&mut self, config: &ShapeConfig) -> Result<(), Box<dyn Error + Send + Sync>> {
+    fn update(
+        &mut self,
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
         Ok(())
     }
 }
 
+struct TestMarquee;
+
+impl Marquee for TestMarquee {
+    fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    #[allow(unused_variables)]
+    fn update(
+        &self,
+        start: PointPixel,
+        end: PointPixel,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// What the user typed into the open editor, if anything.
+    static TYPED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn type_text(text: &str) {
+    TYPED.with(|t| *t.borrow_mut() = Some(text.to_string()));
+}
+
+struct TestEditor {
+    content: String,
+}
+
+impl Editor for TestEditor {
+    #[allow(unused_variables)]
+    fn new(
+        guid: Guid,
+        config: &ShapeConfig,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let content = match &config.details {
+            ShapeDetails::Text(text) => text.content.clone(),
+            _ => String::new(),
+        };
+        Ok(Self { content })
+    }
+
+    fn content(&self) -> String {
+        TYPED.with(|t| t.borrow().clone().unwrap_or_else(|| self.content.clone()))
+    }
+}
+
 struct TestView {
     events: Arc<Mutex<Vec<EventHistory>>>,
 }
@@ -52,7 +115,10 @@ impl View for TestView {
 
 #[test]
 fn test_text_creation() {
-    let mut control = Control::<TestMarker, TestSelection>::new(Box::new(|_| Ok({})));
+    let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(
+        Box::new(|_| Ok(())),
+        Box::new(|_| Ok(())),
+    );
     let events = Arc::new(Mutex::new(vec![]));
     let view = TestView {
         events: events.clone(),
@@ -67,7 +133,7 @@ fn test_text_creation() {
     control.mouse_up();
 
     // Verify events were created
-    assert!(events.lock().unwrap().len() >= 1, "No events were created");
+    assert!(!events.lock().unwrap().is_empty(), "No events were created");
 
     // Get the first event and verify it is a text creation event
     let event = &events.lock().unwrap()[0];
@@ -82,6 +148,8 @@ fn test_text_creation() {
             // Verify positions
             assert!(config.start.x <= config.end.x, "Start x should be <= end x");
             assert!(config.start.y <= config.end.y, "Start y should be <= end y");
+            assert_eq!(config.start, PointGrid { x: 4, y: 4 });
+            assert_eq!(config.end, PointGrid { x: 16, y: 8 });
         } else {
             panic!("Created shape is not a text box");
         }
@@ -92,7 +160,10 @@ fn test_text_creation() {
 
 #[test]
 fn test_text_resize() {
-    let mut control = Control::<TestMarker, TestSelection>::new(Box::new(|_| Ok({})));
+    let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(
+        Box::new(|_| Ok(())),
+        Box::new(|_| Ok(())),
+    );
     let events = Arc::new(Mutex::new(vec![]));
     let view = TestView {
         events: events.clone(),
@@ -111,7 +182,7 @@ fn test_text_resize() {
 
     // Resize the text box
     control.select(guid);
-    control.modify(guid, commitcanvas::control::ModificationType::BR);
+    control.modify(commitcanvas::control::ModificationType::BR);
     control.mouse_update((200.0, 150.0));
     control.mouse_up();
 
@@ -126,4 +197,207 @@ fn test_text_resize() {
         !modification_events.is_empty(),
         "No modification events found after resize"
     );
+
+    // Verify the final grid position
+    if let Some(EventHistory::Modify { to, .. }) = modification_events.last() {
+        assert_eq!(to.start, PointGrid { x: 4, y: 4 });
+        assert_eq!(to.end, PointGrid { x: 16, y: 12 });
+    }
+}
+
+fn create_text(control: &mut Control<TestMarker, TestSelection, TestMarquee, TestEditor>) -> Guid {
+    control.set_button_state(MainMenuButton::Text);
+    control.mouse_update((50.0, 50.0));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_update((150.0, 100.0));
+    control.mouse_up();
+    control.get_selection().unwrap()
+}
+
+fn content(events: &Mutex<Vec<EventHistory>>, guid: Guid) -> Option<String> {
+    events
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find_map(|event| match event {
+            EventHistory::Add { guid: g, config }
+            | EventHistory::Modify {
+                guid: g,
+                to: config,
+                ..
+            } if *g == guid => match &config.details {
+                ShapeDetails::Text(text) => Some(text.content.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+#[test]
+fn test_text_editing() {
+    let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(
+        Box::new(|_| Ok(())),
+        Box::new(|_| Ok(())),
+    );
+    let events = Arc::new(Mutex::new(vec![]));
+    control.add_view(Box::new(TestView {
+        events: events.clone(),
+    }));
+
+    // The editor opens right after creating a text box.
+    let guid = create_text(&mut control);
+    assert!(control.is_editing());
+    type_text("main\nfeature");
+    control.commit_edit();
+    assert!(!control.is_editing());
+    assert_eq!(control.get_selection(), Some(guid));
+    assert_eq!(content(&events, guid).as_deref(), Some("main\nfeature"));
+
+    // Double click reopens it, a click on the canvas commits.
+    type_text("release");
+    assert!(control.edit_text(guid));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_up();
+    assert!(!control.is_editing());
+    assert_eq!(content(&events, guid).as_deref(), Some("release"));
+
+    // Each edit session is one undo step.
+    control.undo();
+    assert_eq!(content(&events, guid).as_deref(), Some("main\nfeature"));
+    control.undo();
+    assert_eq!(content(&events, guid).as_deref(), Some(""));
+    control.redo();
+    assert_eq!(content(&events, guid).as_deref(), Some("main\nfeature"));
+}
+
+#[test]
+fn test_text_edit_cancel() {
+    let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(
+        Box::new(|_| Ok(())),
+        Box::new(|_| Ok(())),
+    );
+    let events = Arc::new(Mutex::new(vec![]));
+    control.add_view(Box::new(TestView {
+        events: events.clone(),
+    }));
+
+    let guid = create_text(&mut control);
+    let count = events.lock().unwrap().len();
+    type_text("discarded");
+    control.cancel_edit();
+    assert!(!control.is_editing());
+    assert_eq!(control.get_selection(), Some(guid));
+    assert_eq!(events.lock().unwrap().len(), count);
+
+    // Unchanged content doesn't create an undo step.
+    type_text("");
+    assert!(control.edit_text(guid));
+    control.commit_edit();
+    assert_eq!(events.lock().unwrap().len(), count);
+}
+
+#[test]
+fn test_edit_only_text_with_select_tool() {
+    let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(
+        Box::new(|_| Ok(())),
+        Box::new(|_| Ok(())),
+    );
+    let events = Arc::new(Mutex::new(vec![]));
+    control.add_view(Box::new(TestView {
+        events: events.clone(),
+    }));
+
+    control.set_button_state(MainMenuButton::Rect);
+    control.mouse_update((50.0, 50.0));
+    control.mouse_down(MouseButton::Left);
+    control.mouse_update((150.0, 100.0));
+    control.mouse_up();
+    let rect = control.get_selection().unwrap();
+    assert!(!control.is_editing());
+    assert!(!control.edit_text(rect));
+
+    let text = create_text(&mut control);
+    control.commit_edit();
+    control.set_button_state(MainMenuButton::Arrow);
+    assert!(!control.edit_text(text));
+    assert!(!control.is_editing());
+}
+
+fn bounds(events: &Mutex<Vec<EventHistory>>, guid: Guid) -> Option<(PointGrid, PointGrid)> {
+    events
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find_map(|event| match event {
+            EventHistory::Add { guid: g, config }
+            | EventHistory::Modify {
+                guid: g,
+                to: config,
+                ..
+            } if *g == guid => Some((config.start, config.end)),
+            _ => None,
+        })
+}
+
+#[test]
+fn test_text_grows_to_content() {
+    let mut control = Control::<TestMarker, TestSelection, TestMarquee, TestEditor>::new(
+        Box::new(|_| Ok(())),
+        Box::new(|_| Ok(())),
+    );
+    let events = Arc::new(Mutex::new(vec![]));
+    control.add_view(Box::new(TestView {
+        events: events.clone(),
+    }));
+
+    // The box is 8 cells (96 pixels) wide and 4 cells high, a line of
+    // medium text is 20 pixels high and fits 12 characters.
+    let guid = create_text(&mut control);
+    assert_eq!(
+        bounds(&events, guid),
+        Some((PointGrid { x: 4, y: 4 }, PointGrid { x: 12, y: 8 }))
+    );
+    type_text("one two three");
+    control.commit_edit();
+    assert_eq!(
+        bounds(&events, guid),
+        Some((PointGrid { x: 4, y: 4 }, PointGrid { x: 12, y: 8 }))
+    );
+
+    type_text("one\ntwo\nthree\nfour\nfive");
+    assert!(control.edit_text(guid));
+    control.commit_edit();
+    assert_eq!(
+        bounds(&events, guid),
+        Some((PointGrid { x: 4, y: 4 }, PointGrid { x: 12, y: 13 }))
+    );
+    assert_eq!(
+        content(&events, guid).as_deref(),
+        Some("one\ntwo\nthree\nfour\nfive")
+    );
+
+    // Growing is part of the edit's undo step.
+    control.undo();
+    assert_eq!(
+        bounds(&events, guid),
+        Some((PointGrid { x: 4, y: 4 }, PointGrid { x: 12, y: 8 }))
+    );
+    control.redo();
+
+    // Narrowing the box wraps the lines, which grows it further.
+    control.modify(commitcanvas::control::ModificationType::R);
+    control.mouse_update((90.0, 100.0));
+    control.mouse_up();
+    let (start, end) = bounds(&events, guid).unwrap();
+    assert_eq!((start, end.x), (PointGrid { x: 4, y: 4 }, 7));
+    assert!(end.y > 13);
+
+    // A larger font needs more room as well.
+    control.set_details(commitcanvas::model::PartialDetails {
+        font_size: Some(commitcanvas::model::FontSize::Large),
+        ..Default::default()
+    });
+    assert!(bounds(&events, guid).unwrap().1.y > end.y);
 }
@@ -0,0 +1,147 @@
+mod common;
+
+use commitcanvas::control::menu::MainMenuButton;
+use commitcanvas::control::ModificationType;
+use commitcanvas::model::{ArrowDetails, Head, PartialDetails, Routing, ShapeConfig, ShapeDetails};
+use commitcanvas::types::{Guid, PointGrid};
+use common::{at, draw, setup, TestControl};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+fn draw_arrow(control: &mut TestControl, start: (i32, i32), end: (i32, i32)) -> Guid {
+    draw(control, MainMenuButton::Arrow, start, end)
+}
+
+/// Drags the handle `modification_type` of the selected arrow from the grid
+/// point `from` to `to`.
+fn drag(
+    control: &mut TestControl,
+    modification_type: ModificationType,
+    from: (i32, i32),
+    to: (i32, i32),
+) {
+    control.mouse_update(at(from.0, from.1));
+    control.modify(modification_type);
+    control.mouse_update(at(to.0, to.1));
+    control.mouse_up();
+}
+
+fn arrow(shapes: &Mutex<HashMap<Guid, ShapeConfig>>, guid: Guid) -> ArrowDetails {
+    match &shapes.lock().unwrap()[&guid].details {
+        ShapeDetails::Arrow(arrow) => arrow.clone(),
+        details => panic!("not an arrow: {:?}", details),
+    }
+}
+
+fn points(points: &[(i32, i32)]) -> Vec<PointGrid> {
+    points.iter().map(|&(x, y)| PointGrid { x, y }).collect()
+}
+
+#[test]
+fn test_midpoint_inserts_waypoint() {
+    let (mut control, shapes) = setup();
+    let guid = draw_arrow(&mut control, (0, 0), (10, 0));
+
+    // A click on the handle without moving leaves the arrow straight.
+    control.mouse_update(at(5, 0));
+    control.modify(ModificationType::Midpoint(0));
+    control.mouse_up();
+    assert!(arrow(&shapes, guid).waypoints.is_empty());
+
+    drag(&mut control, ModificationType::Midpoint(0), (5, 0), (5, 4));
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(5, 4)]));
+    drag(&mut control, ModificationType::Midpoint(1), (8, 2), (8, 6));
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(5, 4), (8, 6)]));
+    assert_eq!(control.get_selection(), Some(guid));
+
+    control.undo();
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(5, 4)]));
+    control.undo();
+    assert!(arrow(&shapes, guid).waypoints.is_empty());
+    control.redo();
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(5, 4)]));
+}
+
+#[test]
+fn test_waypoint_dragged_into_line_goes_away() {
+    let (mut control, shapes) = setup();
+    let guid = draw_arrow(&mut control, (0, 0), (10, 0));
+    drag(&mut control, ModificationType::Midpoint(0), (5, 0), (5, 4));
+
+    drag(&mut control, ModificationType::Waypoint(0), (5, 4), (2, 6));
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(2, 6)]));
+    drag(&mut control, ModificationType::Waypoint(0), (2, 6), (3, 0));
+    assert!(arrow(&shapes, guid).waypoints.is_empty());
+
+    // Dragging and straightening are one step.
+    control.undo();
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(2, 6)]));
+}
+
+#[test]
+fn test_waypoints_follow_the_arrow() {
+    let (mut control, shapes) = setup();
+    let guid = draw_arrow(&mut control, (0, 0), (10, 0));
+    drag(&mut control, ModificationType::Midpoint(0), (5, 0), (5, 4));
+
+    // Moving the end keeps the waypoint, moving the arrow takes it along.
+    drag(&mut control, ModificationType::BR, (10, 0), (10, 2));
+    assert_eq!(arrow(&shapes, guid).waypoints, points(&[(5, 4)]));
+    control.mouse_update(at(0, 0));
+    assert!(control.start_move(guid));
+    control.mouse_update(at(2, 1));
+    control.mouse_up();
+    assert_eq!(
+        shapes.lock().unwrap()[&guid].points(),
+        points(&[(2, 1), (7, 5), (12, 3)])
+    );
+
+    control.copy();
+    control.mouse_update(at(20, 20));
+    control.paste();
+    let pasted = control.get_selection().unwrap();
+    assert_eq!(
+        shapes.lock().unwrap()[&pasted].points(),
+        points(&[(20, 20), (25, 24), (30, 22)])
+    );
+}
+
+#[test]
+fn test_routing() {
+    let (mut control, shapes) = setup();
+    let guid = draw_arrow(&mut control, (0, 0), (10, 0));
+    control.set_details(PartialDetails {
+        routing: Some(Routing::Curved),
+        ..Default::default()
+    });
+    assert_eq!(arrow(&shapes, guid).routing, Routing::Curved);
+    assert_eq!(control.style().routing, Routing::Curved);
+    control.undo();
+    assert_eq!(arrow(&shapes, guid).routing, Routing::Straight);
+
+    // New arrows take the routing over.
+    let other = draw_arrow(&mut control, (0, 4), (10, 4));
+    assert_eq!(arrow(&shapes, other).routing, Routing::Curved);
+}
+
+#[test]
+fn test_heads() {
+    let (mut control, shapes) = setup();
+    let guid = draw_arrow(&mut control, (0, 0), (10, 0));
+    assert_eq!(arrow(&shapes, guid).start_head, Head::None);
+    assert_eq!(arrow(&shapes, guid).end_head, Head::Open);
+
+    // Pointing both ways.
+    control.set_details(PartialDetails {
+        start_head: Some(Head::Triangle),
+        ..Default::default()
+    });
+    assert_eq!(arrow(&shapes, guid).start_head, Head::Triangle);
+    assert_eq!(arrow(&shapes, guid).end_head, Head::Open);
+    assert_eq!(control.style().start_head, Head::Triangle);
+    control.undo();
+    assert_eq!(arrow(&shapes, guid).start_head, Head::None);
+
+    let other = draw_arrow(&mut control, (0, 4), (10, 4));
+    assert_eq!(arrow(&shapes, other).start_head, Head::Triangle);
+}
@@ -0,0 +1,137 @@
+use std::error::Error;
+
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use crate::globals::{CONTROL, DOCUMENT, SVG_CONTROL_GROUP};
+use crate::utils::to_error;
+
+use commitcanvas::control::editor::Editor as EditorInterface;
+use commitcanvas::model::{Guid, ShapeConfig, ShapeDetails, TextAlign};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::utils::coords_to_pixels;
+
+/// A textarea laid over the edited text shape.
+pub struct Editor {
+    object: web_sys::Element,
+    textarea: web_sys::HtmlTextAreaElement,
+    #[allow(dead_code)]
+    keydown_closure: Closure<dyn Fn(web_sys::KeyboardEvent)>,
+    #[allow(dead_code)]
+    blur_closure: Closure<dyn Fn(web_sys::FocusEvent)>,
+    #[allow(dead_code)]
+    mousedown_closure: Closure<dyn Fn(web_sys::MouseEvent)>,
+}
+
+impl Drop for Editor {
+    fn drop(&mut self) {
+        // Removing the focused textarea blurs it, which must not commit again.
+        self.textarea.set_onblur(None);
+        self.object.remove();
+    }
+}
+
+impl EditorInterface for Editor {
+    #[allow(unused_variables)]
+    fn new(
+        guid: Guid,
+        config: &ShapeConfig,
+        grid: &GridConfig,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let ShapeDetails::Text(text) = &config.details else {
+            return Err("called editor with non-text config".into());
+        };
+        let start = coords_to_pixels(config.start, grid);
+        let end = coords_to_pixels(config.end, grid);
+
+        let object = DOCUMENT
+            .with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "foreignObject"))
+            .map_err(to_error)?;
+        let attributes = [
+            ("x", start.x.min(end.x)),
+            ("y", start.y.min(end.y)),
+            ("width", (end.x - start.x).abs()),
+            ("height", (end.y - start.y).abs()),
+        ];
+        for (name, value) in attributes {
+            object
+                .set_attribute(name, &value.to_string())
+                .map_err(to_error)?;
+        }
+
+        let textarea = DOCUMENT
+            .with(|d| d.create_element("textarea"))
+            .map_err(to_error)?
+            .dyn_into::<web_sys::HtmlTextAreaElement>()
+            .map_err(|e| to_error(e.into()))?;
+        textarea.set_class_name("cc_textarea cc_text_editor");
+        textarea
+            .style()
+            .set_property("font-size", (&text.font_size).into())
+            .map_err(to_error)?;
+        let align = match text.align {
+            TextAlign::Left => "left",
+            TextAlign::Center => "center",
+            TextAlign::Right => "right",
+        };
+        textarea
+            .style()
+            .set_property("text-align", align)
+            .map_err(to_error)?;
+        textarea.set_value(&text.content);
+
+        let keydown_closure =
+            Closure::<dyn Fn(web_sys::KeyboardEvent)>::new(|event: web_sys::KeyboardEvent| {
+                // Keys belong to the text, not to the canvas shortcuts.
+                event.stop_propagation();
+                let commit = event.key() == "Enter" && (event.ctrl_key() || event.meta_key());
+                if event.key() == "Escape" || commit {
+                    event.prevent_default();
+                    CONTROL.with(|c| {
+                        if let Ok(mut control) = c.try_borrow_mut() {
+                            if commit {
+                                control.commit_edit();
+                            } else {
+                                control.cancel_edit();
+                            }
+                        }
+                    });
+                }
+            });
+        textarea.set_onkeydown(Some(keydown_closure.as_ref().unchecked_ref()));
+
+        let blur_closure = Closure::<dyn Fn(web_sys::FocusEvent)>::new(|_| {
+            CONTROL.with(|c| {
+                if let Ok(mut control) = c.try_borrow_mut() {
+                    control.commit_edit();
+                }
+            });
+        });
+        textarea.set_onblur(Some(blur_closure.as_ref().unchecked_ref()));
+
+        // Clicks into the text must not reach the canvas.
+        let mousedown_closure =
+            Closure::<dyn Fn(web_sys::MouseEvent)>::new(|event: web_sys::MouseEvent| {
+                event.stop_propagation();
+            });
+        textarea.set_onmousedown(Some(mousedown_closure.as_ref().unchecked_ref()));
+
+        object.append_child(&textarea).map_err(to_error)?;
+        SVG_CONTROL_GROUP
+            .with(|svg| svg.append_child(&object))
+            .map_err(to_error)?;
+        textarea.focus().map_err(to_error)?;
+        textarea.select();
+
+        Ok(Self {
+            object,
+            textarea,
+            keydown_closure,
+            blur_closure,
+            mousedown_closure,
+        })
+    }
+
+    fn content(&self) -> String {
+        self.textarea.value()
+    }
+}
@@ -0,0 +1,50 @@
+use std::error::Error;
+
+use crate::globals::{DOCUMENT, SVG_CONTROL_GROUP};
+use crate::utils::to_error;
+
+use commitcanvas::control::marquee::Marquee as MarqueeInterface;
+use commitcanvas::types::PointPixel;
+
+pub struct Marquee {
+    rect: web_sys::Element,
+}
+
+impl Drop for Marquee {
+    fn drop(&mut self) {
+        self.rect.remove();
+    }
+}
+
+impl MarqueeInterface for Marquee {
+    fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let rect = DOCUMENT
+            .with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "rect"))
+            .map_err(to_error)?;
+        rect.set_attribute("class", "cc_marquee")
+            .map_err(to_error)?;
+        SVG_CONTROL_GROUP
+            .with(|svg| svg.append_child(&rect))
+            .map_err(to_error)?;
+        Ok(Self { rect })
+    }
+
+    fn update(
+        &self,
+        start: PointPixel,
+        end: PointPixel,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let attributes = [
+            ("x", start.x.min(end.x)),
+            ("y", start.y.min(end.y)),
+            ("width", (end.x - start.x).abs()),
+            ("height", (end.y - start.y).abs()),
+        ];
+        for (name, value) in attributes {
+            self.rect
+                .set_attribute(name, &value.to_string())
+                .map_err(to_error)?;
+        }
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@ fn button_to_title(button: &MainMenuButton) -> &'static str {
     match button {
         MainMenuButton::Arrow => "Arrow",
         MainMenuButton::Rect => "Rectangle",
+        MainMenuButton::Ellipse => "Ellipse",
         MainMenuButton::Select => "Select",
         MainMenuButton::Text => "Text",
     }
@@ -21,6 +22,7 @@ fn button_to_icon(button: &MainMenuButton) -> &'static str {
     match button {
         MainMenuButton::Arrow => "north_west",
         MainMenuButton::Rect => "check_box_outline_blank",
+        MainMenuButton::Ellipse => "circle",
         MainMenuButton::Select => "arrow_selector_tool",
         MainMenuButton::Text => "match_case",
     }
@@ -30,6 +32,7 @@ fn button_to_id(button: &MainMenuButton) -> &'static str {
     match button {
         MainMenuButton::Arrow => "cc_button_arrow",
         MainMenuButton::Rect => "cc_button_rect",
+        MainMenuButton::Ellipse => "cc_button_ellipse",
         MainMenuButton::Select => "cc_button_select",
         MainMenuButton::Text => "cc_button_text",
     }
@@ -41,6 +44,7 @@ pub fn setup() -> Result<(), JsValue> {
         MainMenuButton::Select,
         MainMenuButton::Arrow,
         MainMenuButton::Rect,
+        MainMenuButton::Ellipse,
         MainMenuButton::Text,
     ];
     DOCUMENT.with(|d| {
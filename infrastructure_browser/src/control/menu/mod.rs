@@ -1,11 +1,14 @@
 mod main;
+mod style;
 use wasm_bindgen::JsValue;
 
 pub use main::update;
+pub use style::update as update_style;
 
 pub fn setup() -> Result<(), JsValue> {
     log::info!("setting up menus");
     main::setup()?;
+    style::setup()?;
 
     Ok(())
 }
@@ -0,0 +1,413 @@
+use std::error::Error;
+
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+use crate::{
+    globals::{CONTROL, DOCUMENT},
+    utils::to_error,
+};
+use commitcanvas::control::menu::Style;
+use commitcanvas::model::{
+    Color, Dash, FillStyle, FontSize, Head, PartialDetails, PartialOptions, Roughness, Routing,
+    TextAlign, Thickness, VerticalAlign,
+};
+
+const FILL_STYLES: [(FillStyle, &str); 5] = [
+    (FillStyle::Solid, "Solid"),
+    (FillStyle::Hachure, "Hachure"),
+    (FillStyle::CrossHatch, "Cross-hatch"),
+    (FillStyle::ZigZag, "Zigzag"),
+    (FillStyle::Dots, "Dots"),
+];
+
+const ROUGHNESSES: [(Roughness, &str); 3] = [
+    (Roughness::Low, "Low"),
+    (Roughness::Medium, "Medium"),
+    (Roughness::High, "High"),
+];
+
+const THICKNESSES: [(Thickness, &str); 3] = [
+    (Thickness::Thin, "Thin"),
+    (Thickness::Medium, "Medium"),
+    (Thickness::Thick, "Thick"),
+];
+
+const DASHES: [(Dash, &str); 3] = [
+    (Dash::Solid, "Solid"),
+    (Dash::Dashed, "Dashed"),
+    (Dash::Dotted, "Dotted"),
+];
+
+const FONT_SIZES: [(FontSize, &str); 3] = [
+    (FontSize::Small, "Small"),
+    (FontSize::Medium, "Medium"),
+    (FontSize::Large, "Large"),
+];
+
+const ALIGNS: [(TextAlign, &str); 3] = [
+    (TextAlign::Left, "Left"),
+    (TextAlign::Center, "Center"),
+    (TextAlign::Right, "Right"),
+];
+
+const VERTICAL_ALIGNS: [(VerticalAlign, &str); 3] = [
+    (VerticalAlign::Top, "Top"),
+    (VerticalAlign::Middle, "Middle"),
+    (VerticalAlign::Bottom, "Bottom"),
+];
+
+const ROUTINGS: [(Routing, &str); 3] = [
+    (Routing::Straight, "Straight"),
+    (Routing::Curved, "Curved"),
+    (Routing::Elbow, "Elbow"),
+];
+
+const HEADS: [(Head, &str); 6] = [
+    (Head::None, "None"),
+    (Head::Open, "Open"),
+    (Head::Triangle, "Triangle"),
+    (Head::Circle, "Circle"),
+    (Head::Diamond, "Diamond"),
+    (Head::Bar, "Bar"),
+];
+
+fn color_to_title(color: &Color) -> &'static str {
+    match color {
+        Color::None => "None",
+        Color::Red => "Red",
+        Color::Organge => "Orange",
+        Color::Amber => "Amber",
+        Color::Yellow => "Yellow",
+        Color::Lime => "Lime",
+        Color::Green => "Green",
+        Color::Emerald => "Emerald",
+        Color::Teal => "Teal",
+        Color::Cyan => "Cyan",
+        Color::Sky => "Sky",
+        Color::Blue => "Blue",
+        Color::Indigo => "Indigo",
+        Color::Purple => "Purple",
+        Color::Fuchsia => "Fuchsia",
+        Color::Pink => "Pink",
+        Color::Rose => "Rose",
+    }
+}
+
+fn swatch_class(color: &Color) -> String {
+    color.stroke_class().replace("cc_stroke_", "cc_swatch_")
+}
+
+fn button_id(row: &str, idx: usize) -> String {
+    format!("cc_style_{}_{}", row, idx)
+}
+
+/// Appends a titled row of buttons to the style menu. Clicking the button at
+/// position `i` calls `on_click(i)`.
+fn add_row(
+    d: &web_sys::Document,
+    menu: &web_sys::Element,
+    row: &'static str,
+    title: &str,
+    buttons: &[(String, Option<String>)],
+    on_click: fn(usize),
+) -> Result<(), JsValue> {
+    let label = d.create_element("div")?;
+    label.set_class_name("cc_style_label");
+    label.set_inner_html(title);
+    menu.append_child(&label)?;
+
+    let wrapper = d.create_element("div")?;
+    wrapper.set_attribute("role", "group")?;
+    wrapper.set_class_name("cc_style_row");
+    for (idx, (title, class)) in buttons.iter().enumerate() {
+        let button = d
+            .create_element("button")?
+            .dyn_into::<web_sys::HtmlButtonElement>()?;
+        button.set_attribute("id", &button_id(row, idx))?;
+        button.set_attribute("type", "button")?;
+        button.set_attribute("title", title)?;
+        match class {
+            Some(class) => button.set_attribute("class", &format!("cc_swatch {}", class))?,
+            None => {
+                button.set_attribute("class", "cc_style_button")?;
+                button.set_inner_html(title);
+            }
+        }
+        let closure = Closure::<dyn Fn()>::new(move || on_click(idx));
+        button.set_onclick(Some(closure.as_ref().unchecked_ref()));
+        closure.forget();
+        wrapper.append_child(&button)?;
+    }
+    menu.append_child(&wrapper)?;
+    Ok(())
+}
+
+fn set_options(options: PartialOptions) {
+    CONTROL.with(|c| c.borrow_mut().set_options(options));
+}
+
+fn set_details(details: PartialDetails) {
+    CONTROL.with(|c| c.borrow_mut().set_details(details));
+}
+
+pub fn setup() -> Result<(), JsValue> {
+    log::info!("setting up style menu");
+    let colors: Vec<(String, Option<String>)> = Color::ALL
+        .iter()
+        .map(|color| (color_to_title(color).to_string(), Some(swatch_class(color))))
+        .collect();
+    let labels = |entries: &[&str]| -> Vec<(String, Option<String>)> {
+        entries
+            .iter()
+            .map(|label| (label.to_string(), None))
+            .collect()
+    };
+
+    DOCUMENT.with(|d| {
+        let div = d
+            .get_element_by_id("cc_menu_style")
+            .expect("cc_menu_style div not found");
+        let menu = d.create_element("div")?;
+        menu.set_attribute("id", "cc_style_panel")?;
+        menu.set_class_name("cc_menu_style");
+
+        add_row(d, &menu, "stroke", "Stroke", &colors, |idx| {
+            set_options(PartialOptions {
+                stroke_color: Some(Color::ALL[idx]),
+                ..Default::default()
+            })
+        })?;
+        add_row(d, &menu, "background", "Background", &colors, |idx| {
+            set_details(PartialDetails {
+                background: Some(Color::ALL[idx]),
+                ..Default::default()
+            })
+        })?;
+        add_row(
+            d,
+            &menu,
+            "fill",
+            "Fill",
+            &labels(&FILL_STYLES.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    fill: Some(FILL_STYLES[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "roughness",
+            "Roughness",
+            &labels(&ROUGHNESSES.map(|(_, label)| label)),
+            |idx| {
+                set_options(PartialOptions {
+                    roughness: Some(ROUGHNESSES[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "thickness",
+            "Thickness",
+            &labels(&THICKNESSES.map(|(_, label)| label)),
+            |idx| {
+                set_options(PartialOptions {
+                    thickness: Some(THICKNESSES[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "dash",
+            "Dash",
+            &labels(&DASHES.map(|(_, label)| label)),
+            |idx| {
+                set_options(PartialOptions {
+                    dash: Some(DASHES[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        // A command rather than a setting, so never shown as active.
+        add_row(
+            d,
+            &menu,
+            "strokes",
+            "Strokes",
+            &labels(&["Reshuffle"]),
+            |_| CONTROL.with(|c| c.borrow_mut().reshuffle()),
+        )?;
+        add_row(
+            d,
+            &menu,
+            "font_size",
+            "Font size",
+            &labels(&FONT_SIZES.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    font_size: Some(FONT_SIZES[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "align",
+            "Text align",
+            &labels(&ALIGNS.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    align: Some(ALIGNS[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "vertical_align",
+            "Vertical align",
+            &labels(&VERTICAL_ALIGNS.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    vertical_align: Some(VERTICAL_ALIGNS[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "routing",
+            "Arrow",
+            &labels(&ROUTINGS.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    routing: Some(ROUTINGS[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "start_head",
+            "Start head",
+            &labels(&HEADS.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    start_head: Some(HEADS[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+        add_row(
+            d,
+            &menu,
+            "end_head",
+            "End head",
+            &labels(&HEADS.map(|(_, label)| label)),
+            |idx| {
+                set_details(PartialDetails {
+                    end_head: Some(HEADS[idx].0),
+                    ..Default::default()
+                })
+            },
+        )?;
+
+        div.append_child(&menu)?;
+        Ok::<(), JsValue>(())
+    })?;
+
+    let style = CONTROL.with(|c| c.borrow().style());
+    update(&style).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+pub fn update(style: &Style) -> Result<(), Box<dyn Error + Send + Sync>> {
+    // Every value is in its list, so the fallback never applies.
+    let active = [
+        (
+            "stroke",
+            Color::ALL
+                .iter()
+                .position(|c| *c == style.options.stroke_color),
+        ),
+        (
+            "background",
+            Color::ALL.iter().position(|c| *c == style.background),
+        ),
+        (
+            "fill",
+            FILL_STYLES.iter().position(|(f, _)| *f == style.fill),
+        ),
+        (
+            "roughness",
+            ROUGHNESSES
+                .iter()
+                .position(|(r, _)| *r == style.options.roughness),
+        ),
+        (
+            "thickness",
+            THICKNESSES
+                .iter()
+                .position(|(t, _)| *t == style.options.thickness),
+        ),
+        (
+            "dash",
+            DASHES.iter().position(|(d, _)| *d == style.options.dash),
+        ),
+        (
+            "font_size",
+            FONT_SIZES.iter().position(|(f, _)| *f == style.font_size),
+        ),
+        ("align", ALIGNS.iter().position(|(a, _)| *a == style.align)),
+        (
+            "vertical_align",
+            VERTICAL_ALIGNS
+                .iter()
+                .position(|(a, _)| *a == style.vertical_align),
+        ),
+        (
+            "routing",
+            ROUTINGS.iter().position(|(r, _)| *r == style.routing),
+        ),
+        (
+            "start_head",
+            HEADS.iter().position(|(h, _)| *h == style.start_head),
+        ),
+        (
+            "end_head",
+            HEADS.iter().position(|(h, _)| *h == style.end_head),
+        ),
+    ];
+
+    DOCUMENT.with(|d| {
+        let active_buttons = d.get_elements_by_class_name("cc_style_active");
+        // The collection is live, so removing the class shrinks it.
+        while let Some(button) = active_buttons.item(0) {
+            button
+                .class_list()
+                .remove_1("cc_style_active")
+                .map_err(to_error)?;
+        }
+        for (row, idx) in active {
+            let idx = idx.unwrap_or_default();
+            let button = d
+                .get_element_by_id(&button_id(row, idx))
+                .expect("Failed to get style button");
+            button
+                .class_list()
+                .add_1("cc_style_active")
+                .map_err(to_error)?;
+        }
+        Ok(())
+    })
+}
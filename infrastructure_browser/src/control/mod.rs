@@ -1,4 +1,6 @@
+pub mod editor;
 pub mod marker;
+pub mod marquee;
 pub mod menu;
 pub mod selection;
 pub mod setup;
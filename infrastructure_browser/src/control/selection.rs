@@ -1,6 +1,9 @@
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 
-use commitcanvas::model::{Guid, ShapeConfig};
+use commitcanvas::model::Guid;
+use commitcanvas::settings::GridConfig;
+use commitcanvas::types::PointGrid;
+use commitcanvas::utils::coords_to_pixels;
 
 use crate::globals::{CONTROL, DOCUMENT, SVG_CONTROL_GROUP};
 use crate::utils::to_error;
@@ -17,6 +20,7 @@ pub struct Nodes {
     pub node6: web_sys::SvgElement,
     pub node7: web_sys::SvgElement,
     pub node8: web_sys::SvgElement,
+    pub node9: web_sys::SvgElement,
     #[allow(dead_code)]
     closure1: Closure<dyn Fn(web_sys::MouseEvent)>,
     #[allow(dead_code)]
@@ -33,11 +37,12 @@ pub struct Nodes {
     closure7: Closure<dyn Fn(web_sys::MouseEvent)>,
     #[allow(dead_code)]
     closure8: Closure<dyn Fn(web_sys::MouseEvent)>,
-    // TODO add node to center
+    #[allow(dead_code)]
+    closure9: Closure<dyn Fn(web_sys::MouseEvent)>,
 }
 
 impl Nodes {
-    pub fn new(guid: Guid, config: &ShapeConfig) -> Result<Self, JsValue> {
+    pub fn new(start: PointGrid, end: PointGrid, grid: &GridConfig) -> Result<Self, JsValue> {
         let node1 = DOCUMENT
             .with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "circle"))?
             .dyn_into::<web_sys::SvgElement>()?;
@@ -49,7 +54,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::TL);
+                    c.modify(ModificationType::TL);
                 });
             });
         node1.set_onmousedown(Some(closure1.as_ref().unchecked_ref()));
@@ -65,7 +70,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::TR);
+                    c.modify(ModificationType::TR);
                 });
             });
         node2.set_onmousedown(Some(closure2.as_ref().unchecked_ref()));
@@ -81,7 +86,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::BR);
+                    c.modify(ModificationType::BR);
                 });
             });
         node3.set_onmousedown(Some(closure3.as_ref().unchecked_ref()));
@@ -97,7 +102,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::BL);
+                    c.modify(ModificationType::BL);
                 });
             });
         node4.set_onmousedown(Some(closure4.as_ref().unchecked_ref()));
@@ -113,7 +118,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::T);
+                    c.modify(ModificationType::T);
                 });
             });
         node5.set_onmousedown(Some(closure5.as_ref().unchecked_ref()));
@@ -129,7 +134,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::R);
+                    c.modify(ModificationType::R);
                 });
             });
         node6.set_onmousedown(Some(closure6.as_ref().unchecked_ref()));
@@ -145,7 +150,7 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::B);
+                    c.modify(ModificationType::B);
                 });
             });
         node7.set_onmousedown(Some(closure7.as_ref().unchecked_ref()));
@@ -161,11 +166,27 @@ impl Nodes {
                 event.stop_propagation();
                 CONTROL.with(|control| {
                     let mut c = control.borrow_mut();
-                    c.modify(guid, ModificationType::L);
+                    c.modify(ModificationType::L);
                 });
             });
         node8.set_onmousedown(Some(closure8.as_ref().unchecked_ref()));
 
+        let node9 = DOCUMENT
+            .with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "circle"))?
+            .dyn_into::<web_sys::SvgElement>()?;
+        node9.set_attribute("class", "cc_selection_node cc_selection_node_c")?;
+        node9.set_attribute("r", "5")?;
+        let closure9 =
+            Closure::<dyn Fn(web_sys::MouseEvent)>::new(move |event: web_sys::MouseEvent| {
+                event.prevent_default();
+                event.stop_propagation();
+                CONTROL.with(|control| {
+                    let mut c = control.borrow_mut();
+                    c.modify(ModificationType::Move);
+                });
+            });
+        node9.set_onmousedown(Some(closure9.as_ref().unchecked_ref()));
+
         SVG_CONTROL_GROUP.with(|g| {
             g.append_child(&node1)?;
             g.append_child(&node2)?;
@@ -174,7 +195,8 @@ impl Nodes {
             g.append_child(&node5)?;
             g.append_child(&node6)?;
             g.append_child(&node7)?;
-            g.append_child(&node8)
+            g.append_child(&node8)?;
+            g.append_child(&node9)
         })?;
 
         let mut result = Self {
@@ -186,6 +208,7 @@ impl Nodes {
             node6,
             node7,
             node8,
+            node9,
             closure1,
             closure2,
             closure3,
@@ -194,52 +217,50 @@ impl Nodes {
             closure6,
             closure7,
             closure8,
+            closure9,
         };
-        result.update(config)?;
+        result.update(start, end, grid)?;
         Ok(result)
     }
 
-    pub fn update(&mut self, config: &ShapeConfig) -> Result<(), JsValue> {
+    pub fn update(
+        &mut self,
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
+    ) -> Result<(), JsValue> {
+        let start = coords_to_pixels(start, grid);
+        let end = coords_to_pixels(end, grid);
         self.node1
-            .set_attribute("cx", config.start.x.to_string().as_str())?;
+            .set_attribute("cx", start.x.to_string().as_str())?;
         self.node1
-            .set_attribute("cy", config.start.y.to_string().as_str())?;
-        self.node2
-            .set_attribute("cx", config.end.x.to_string().as_str())?;
+            .set_attribute("cy", start.y.to_string().as_str())?;
+        self.node2.set_attribute("cx", end.x.to_string().as_str())?;
         self.node2
-            .set_attribute("cy", config.start.y.to_string().as_str())?;
-        self.node3
-            .set_attribute("cx", config.end.x.to_string().as_str())?;
-        self.node3
-            .set_attribute("cy", config.end.y.to_string().as_str())?;
-        self.node4
-            .set_attribute("cx", config.start.x.to_string().as_str())?;
+            .set_attribute("cy", start.y.to_string().as_str())?;
+        self.node3.set_attribute("cx", end.x.to_string().as_str())?;
+        self.node3.set_attribute("cy", end.y.to_string().as_str())?;
         self.node4
-            .set_attribute("cy", config.end.y.to_string().as_str())?;
-        self.node5.set_attribute(
-            "cx",
-            ((config.start.x + config.end.x) / 2.0).to_string().as_str(),
-        )?;
+            .set_attribute("cx", start.x.to_string().as_str())?;
+        self.node4.set_attribute("cy", end.y.to_string().as_str())?;
         self.node5
-            .set_attribute("cy", config.start.y.to_string().as_str())?;
+            .set_attribute("cx", ((start.x + end.x) / 2.0).to_string().as_str())?;
+        self.node5
+            .set_attribute("cy", start.y.to_string().as_str())?;
+        self.node6.set_attribute("cx", end.x.to_string().as_str())?;
         self.node6
-            .set_attribute("cx", config.end.x.to_string().as_str())?;
-        self.node6.set_attribute(
-            "cy",
-            ((config.start.y + config.end.y) / 2.0).to_string().as_str(),
-        )?;
-        self.node7.set_attribute(
-            "cx",
-            ((config.start.x + config.end.x) / 2.0).to_string().as_str(),
-        )?;
+            .set_attribute("cy", ((start.y + end.y) / 2.0).to_string().as_str())?;
         self.node7
-            .set_attribute("cy", config.end.y.to_string().as_str())?;
+            .set_attribute("cx", ((start.x + end.x) / 2.0).to_string().as_str())?;
+        self.node7.set_attribute("cy", end.y.to_string().as_str())?;
+        self.node8
+            .set_attribute("cx", start.x.to_string().as_str())?;
         self.node8
-            .set_attribute("cx", config.start.x.to_string().as_str())?;
-        self.node8.set_attribute(
-            "cy",
-            ((config.start.y + config.end.y) / 2.0).to_string().as_str(),
-        )?;
+            .set_attribute("cy", ((start.y + end.y) / 2.0).to_string().as_str())?;
+        self.node9
+            .set_attribute("cx", ((start.x + end.x) / 2.0).to_string().as_str())?;
+        self.node9
+            .set_attribute("cy", ((start.y + end.y) / 2.0).to_string().as_str())?;
         Ok(())
     }
 }
@@ -254,14 +275,88 @@ impl Drop for Nodes {
         self.node6.remove();
         self.node7.remove();
         self.node8.remove();
+        self.node9.remove();
+    }
+}
+
+/// A handle dragging a point of the selected arrow.
+pub struct PointNode {
+    pub node: web_sys::SvgElement,
+    #[allow(dead_code)]
+    closure: Closure<dyn Fn(web_sys::MouseEvent)>,
+}
+
+impl PointNode {
+    fn new(class: &str, modification_type: ModificationType) -> Result<Self, JsValue> {
+        let node = DOCUMENT
+            .with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "circle"))?
+            .dyn_into::<web_sys::SvgElement>()?;
+        node.set_attribute("class", &format!("cc_selection_node {}", class))?;
+        node.set_attribute("r", "4")?;
+        let closure =
+            Closure::<dyn Fn(web_sys::MouseEvent)>::new(move |event: web_sys::MouseEvent| {
+                event.prevent_default();
+                event.stop_propagation();
+                CONTROL.with(|control| {
+                    let mut c = control.borrow_mut();
+                    c.modify(modification_type);
+                });
+            });
+        node.set_onmousedown(Some(closure.as_ref().unchecked_ref()));
+        SVG_CONTROL_GROUP.with(|g| g.append_child(&node))?;
+        Ok(PointNode { node, closure })
+    }
+
+    fn update(&self, (x, y): (f32, f32)) -> Result<(), JsValue> {
+        self.node.set_attribute("cx", x.to_string().as_str())?;
+        self.node.set_attribute("cy", y.to_string().as_str())
     }
 }
 
+impl Drop for PointNode {
+    fn drop(&mut self) {
+        self.node.remove();
+    }
+}
+
+/// The handles on an arrow's points: one on each waypoint, then one halfway
+/// along each segment, with what dragging them does.
+fn point_handles(
+    points: &[PointGrid],
+    grid: &GridConfig,
+) -> Vec<(&'static str, ModificationType, (f32, f32))> {
+    let pixels: Vec<_> = points.iter().map(|p| coords_to_pixels(*p, grid)).collect();
+    let waypoints = pixels
+        .iter()
+        .skip(1)
+        .take(pixels.len().saturating_sub(2))
+        .enumerate()
+        .map(|(i, p)| {
+            (
+                "cc_selection_node_waypoint",
+                ModificationType::Waypoint(i),
+                (p.x, p.y),
+            )
+        });
+    let midpoints = pixels.windows(2).enumerate().map(|(i, segment)| {
+        (
+            "cc_selection_node_midpoint",
+            ModificationType::Midpoint(i),
+            (
+                (segment[0].x + segment[1].x) / 2.0,
+                (segment[0].y + segment[1].y) / 2.0,
+            ),
+        )
+    });
+    waypoints.chain(midpoints).collect()
+}
+
 pub struct Selection {
     #[allow(dead_code)]
-    pub selected: Guid,
+    pub selected: Vec<Guid>,
     pub path: web_sys::SvgElement,
     pub nodes: Nodes,
+    pub points: Vec<PointNode>,
 }
 
 impl Drop for Selection {
@@ -272,8 +367,10 @@ impl Drop for Selection {
 
 impl SelectionInterface for Selection {
     fn new(
-        guid: Guid,
-        config: &ShapeConfig,
+        guids: &[Guid],
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let path = DOCUMENT
             .with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "path"))
@@ -285,31 +382,36 @@ impl SelectionInterface for Selection {
         SVG_CONTROL_GROUP
             .with(|g| g.append_child(&path))
             .map_err(to_error)?;
-        let nodes = Nodes::new(guid, config).map_err(to_error)?;
+        let nodes = Nodes::new(start, end, grid).map_err(to_error)?;
 
         let mut result = Self {
-            selected: guid,
+            selected: guids.to_vec(),
             path: path
                 .dyn_into::<web_sys::SvgElement>()
                 .map_err(|e| to_error(e.into()))?,
             nodes,
+            points: Vec::new(),
         };
 
-        result.update(config)?;
+        result.update(start, end, grid)?;
 
         Ok(result)
     }
 
     fn update(
         &mut self,
-        config: &ShapeConfig,
+        start: PointGrid,
+        end: PointGrid,
+        grid: &GridConfig,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         const EXTRA: f32 = 4096.0;
 
-        let min_x = config.start.x.min(config.end.x);
-        let min_y = config.start.y.min(config.end.y);
-        let max_x = config.start.x.max(config.end.x);
-        let max_y = config.start.y.max(config.end.y);
+        let start_pixels = coords_to_pixels(start, grid);
+        let end_pixels = coords_to_pixels(end, grid);
+        let min_x = start_pixels.x.min(end_pixels.x);
+        let min_y = start_pixels.y.min(end_pixels.y);
+        let max_x = start_pixels.x.max(end_pixels.x);
+        let max_y = start_pixels.y.max(end_pixels.y);
 
         let d = format!(
             "M {} {} L {} {} M {} {} L {} {} M {} {} L {} {} M {} {} L {} {}",
@@ -331,8 +433,29 @@ impl SelectionInterface for Selection {
             max_y,
         );
         self.path.set_attribute("d", &d).map_err(to_error)?;
-        self.nodes.update(config).map_err(to_error)?;
+        self.nodes.update(start, end, grid).map_err(to_error)?;
 
         Ok(())
     }
+
+    fn set_points(
+        &mut self,
+        points: &[PointGrid],
+        grid: &GridConfig,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let handles = point_handles(points, grid);
+        // Keep the nodes while the number of points stays, a new node would
+        // lose the pointer in the middle of a drag.
+        if handles.len() != self.points.len() {
+            self.points = handles
+                .iter()
+                .map(|(class, modification_type, _)| PointNode::new(class, *modification_type))
+                .collect::<Result<_, _>>()
+                .map_err(to_error)?;
+        }
+        for (node, (_, _, position)) in self.points.iter().zip(handles) {
+            node.update(position).map_err(to_error)?;
+        }
+        Ok(())
+    }
 }
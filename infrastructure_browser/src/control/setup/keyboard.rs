@@ -5,6 +5,11 @@ use crate::globals::{CONTROL, DOCUMENT};
 pub fn setup() -> Result<(), JsValue> {
     let closure = Closure::<dyn FnMut(web_sys::KeyboardEvent) -> Result<(), JsValue>>::new(
         |event: web_sys::KeyboardEvent| {
+            // The text editor handles its own keys.
+            if CONTROL.with(|c| c.borrow().is_editing()) {
+                return Ok(());
+            }
+
             let key = event.key();
 
             // Ctrl + z for undo
@@ -0,0 +1,21 @@
+use infrastructure_png::{PngOptions, BASE_DPI};
+use wasm_bindgen::prelude::*;
+
+use crate::globals::CONTROL;
+
+/// Draws the current drawing as a PNG image for the page to download.
+///
+/// `scale` multiplies the pixels of the drawing. Text is set in the
+/// hand-drawn font compiled into the module, the page has no font files to
+/// give the image renderer.
+#[wasm_bindgen]
+pub fn export_png(scale: f32, transparent: bool) -> Result<Vec<u8>, JsValue> {
+    let document = CONTROL.with(|c| c.borrow().to_document());
+    let options = PngOptions {
+        scale,
+        dpi: (BASE_DPI as f32 * scale).round() as u32,
+        transparent,
+        ..Default::default()
+    };
+    infrastructure_png::render(&document, &options).map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -1,4 +1,5 @@
 mod control;
+mod export;
 mod globals;
 mod view;
 use globals::CONTROL;
@@ -33,8 +34,18 @@ fn run() -> Result<(), JsValue> {
     let urlview = view::url::UrlView::new();
     CONTROL.with(|c| {
         let mut control = c.borrow_mut();
+        control.set_text_measure(view::ui::text_measure());
         control.add_view(Box::new(uiview));
         control.add_view(Box::new(urlview));
+        match view::url::UrlView::load() {
+            Some(Ok(document)) => {
+                if let Err(e) = control.load_document(document) {
+                    log::warn!("failed to load drawing from url: {}", e);
+                }
+            }
+            Some(Err(e)) => log::warn!("failed to read drawing from url: {}", e),
+            None => {}
+        }
     });
 
     Ok(())
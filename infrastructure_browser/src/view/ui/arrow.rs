@@ -1,69 +1,72 @@
-use super::utils::to_identifier;
+use super::utils::{part, set_attributes, to_dom};
 use crate::globals::{CONTROL, DOCUMENT, SVG_VIEW_GROUP};
-use commitcanvas::model::{Guid, ShapeConfig};
-use rough::to_svg_path;
+use commitcanvas::model::Guid;
+use infrastructure_svg::{Element, NAMESPACE};
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 
 use super::Item;
 
-pub fn create_arrow(guid: Guid, config: &ShapeConfig) -> Result<Item, JsValue> {
-    let path = DOCUMENT
-        .with(|document| document.create_element_ns(Some("http://www.w3.org/2000/svg"), "path"))?
-        .dyn_into::<web_sys::SvgPathElement>()?;
-    let svg_path = to_svg_path(
-        config.start.into(),
-        config.end.into(),
-        (&config.options.roughness).into(),
-        2,
-        2.0,
-    );
-    path.set_attribute("d", &svg_path)?;
-    path.set_attribute("class", "cc_arrow")?;
-    path.set_attribute("filter", "url(#cc_pencil_texture_4)")?;
-    path.set_attribute("marker-end", "url(#cc_arrow_head)")?;
-    path.set_attribute("stroke-width", (&config.options.thickness).into())?;
-    let group = DOCUMENT
-        .with(|document| document.create_element_ns(Some("http://www.w3.org/2000/svg"), "g"))?
-        .dyn_into::<web_sys::SvgElement>()?;
-    group.set_id(&to_identifier(guid));
-    group.append_child(&path)?;
+/// Adds the arrow drawn by `element` to the page.
+pub fn create_arrow(guid: Guid, element: &Element) -> Result<Item, JsValue> {
+    let group = to_dom(element)?;
+    let children = group.children();
+    let (Some(path), Some(head), Some(head_fill)) =
+        (children.item(0), children.item(1), children.item(2))
+    else {
+        return Err(JsValue::from_str("arrow has no path or heads"));
+    };
+    let path = path.dyn_into::<web_sys::SvgPathElement>()?;
+    let head = head.dyn_into::<web_sys::SvgElement>()?;
+    let head_fill = head_fill.dyn_into::<web_sys::SvgElement>()?;
     let selector = DOCUMENT
-        .with(|document| document.create_element_ns(Some("http://www.w3.org/2000/svg"), "path"))?
+        .with(|document| document.create_element_ns(Some(NAMESPACE), "path"))?
         .dyn_into::<web_sys::SvgPathElement>()?;
-    selector.set_attribute("d", &svg_path)?;
+    selector.set_attribute("d", part(element, 0)?.get("d").unwrap_or_default())?;
     selector.set_attribute("class", "cc_selector")?;
     let selector_closure =
         Closure::<dyn Fn(web_sys::MouseEvent)>::new(move |event: web_sys::MouseEvent| {
-            event.prevent_default();
-            event.stop_propagation();
             CONTROL.with(|control| {
                 let mut c = control.borrow_mut();
-                c.select(guid)
+                let handled = if event.shift_key() {
+                    c.toggle_selection(guid)
+                } else {
+                    c.start_move(guid)
+                };
+                if handled {
+                    event.prevent_default();
+                    event.stop_propagation();
+                }
             });
         });
-    selector.set_onclick(Some(selector_closure.as_ref().unchecked_ref()));
+    selector.set_onmousedown(Some(selector_closure.as_ref().unchecked_ref()));
     group.append_child(&selector)?;
     SVG_VIEW_GROUP.with(|svg| svg.append_child(&group))?;
     Ok(Item::Arrow {
         path,
+        head,
+        head_fill,
         selector,
         group,
         selector_closure,
     })
 }
 
-pub fn update_arrow(config: &ShapeConfig, item: &Item) -> Result<(), JsValue> {
-    if let Item::Arrow { path, selector, .. } = item {
-        let svg_path = to_svg_path(
-            config.start.into(),
-            config.end.into(),
-            (&config.options.roughness).into(),
-            2,
-            2.0,
-        );
-        path.set_attribute("d", &svg_path)?;
-        path.set_attribute("stroke-width", (&config.options.thickness).into())?;
-        selector.set_attribute("d", &svg_path)?;
+pub fn update_arrow(element: &Element, item: &Item) -> Result<(), JsValue> {
+    if let Item::Arrow {
+        path,
+        head,
+        head_fill,
+        selector,
+        group,
+        ..
+    } = item
+    {
+        let drawn = part(element, 0)?;
+        set_attributes(group, element)?;
+        set_attributes(path, drawn)?;
+        set_attributes(head, part(element, 1)?)?;
+        set_attributes(head_fill, part(element, 2)?)?;
+        selector.set_attribute("d", drawn.get("d").unwrap_or_default())?;
         Ok(())
     } else {
         Err(JsValue::from_str("called update_arrow with non-arrow item"))
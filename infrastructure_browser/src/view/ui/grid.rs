@@ -0,0 +1,44 @@
+use crate::globals::DOCUMENT;
+use commitcanvas::settings::GridConfig;
+use wasm_bindgen::JsValue;
+
+/// Redraws the background pattern, so the dots lie exactly on the points the
+/// pointer snaps to.
+pub fn render_grid(grid: &GridConfig) -> Result<(), JsValue> {
+    let pattern = DOCUMENT
+        .with(|d| d.get_element_by_id("pattern_circles"))
+        .ok_or_else(|| JsValue::from_str("no grid pattern found"))?;
+
+    // One tile holds 2x2 dots, the first one half a dot distance from the
+    // tile corner.
+    let distance = grid.dot_distance();
+    let (near, far, size) = (distance / 2.0, 1.5 * distance, 2.0 * distance);
+    pattern.set_attribute("x", &(grid.origin.x - near).to_string())?;
+    pattern.set_attribute("y", &(grid.origin.y - near).to_string())?;
+    pattern.set_attribute("width", &size.to_string())?;
+    pattern.set_attribute("height", &size.to_string())?;
+    pattern.set_inner_html("");
+
+    for (cx, cy) in [(near, near), (far, near), (near, far), (far, far)] {
+        let circle =
+            DOCUMENT.with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "circle"))?;
+        circle.set_id("pattern_circle");
+        circle.set_attribute("cx", &cx.to_string())?;
+        circle.set_attribute("cy", &cy.to_string())?;
+        circle.set_attribute("r", "1")?;
+        pattern.append_child(&circle)?;
+    }
+
+    for (x1, y1, x2, y2) in [(0.0, near, size, near), (near, 0.0, near, size)] {
+        let line =
+            DOCUMENT.with(|d| d.create_element_ns(Some("http://www.w3.org/2000/svg"), "line"))?;
+        line.set_id("pattern_line");
+        line.set_attribute("x1", &x1.to_string())?;
+        line.set_attribute("y1", &y1.to_string())?;
+        line.set_attribute("x2", &x2.to_string())?;
+        line.set_attribute("y2", &y2.to_string())?;
+        pattern.append_child(&line)?;
+    }
+
+    Ok(())
+}
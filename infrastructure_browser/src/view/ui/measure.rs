@@ -0,0 +1,48 @@
+use commitcanvas::text::{ApproximateMeasure, TextMeasure};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::globals::DOCUMENT;
+
+/// Measures text with the font engine of the browser.
+pub struct CanvasMeasure {
+    context: web_sys::CanvasRenderingContext2d,
+}
+
+impl CanvasMeasure {
+    pub fn new() -> Result<Self, JsValue> {
+        let canvas = DOCUMENT
+            .with(|d| d.create_element("canvas"))?
+            .dyn_into::<web_sys::HtmlCanvasElement>()?;
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("no 2d context available"))?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+        Ok(Self { context })
+    }
+}
+
+impl TextMeasure for CanvasMeasure {
+    fn width(&self, text: &str, font_size: f32) -> f32 {
+        self.context
+            .set_font(&format!("{}px 'Patrick Hand'", font_size));
+        match self.context.measure_text(text) {
+            Ok(metrics) => metrics.width() as f32,
+            Err(e) => {
+                log::error!("failed to measure text: {:?}", e);
+                0.0
+            }
+        }
+    }
+}
+
+/// The measurement text is laid out with, falling back to an estimate if the
+/// browser can't measure.
+pub fn text_measure() -> Box<dyn TextMeasure> {
+    match CanvasMeasure::new() {
+        Ok(measure) => Box::new(measure),
+        Err(e) => {
+            log::warn!("failed to set up text measurement: {:?}", e);
+            Box::new(ApproximateMeasure::default())
+        }
+    }
+}
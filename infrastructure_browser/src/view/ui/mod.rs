@@ -1,25 +1,33 @@
 mod arrow;
+mod grid;
+mod measure;
 mod rect;
 mod text;
 mod utils;
-use arrow::create_arrow;
 
 use std::collections::HashMap;
 use std::error::Error;
 
 use self::{
-    arrow::update_arrow,
-    rect::update_rect,
+    arrow::{create_arrow, update_arrow},
+    rect::{create_rect, update_rect},
     text::{create_text, update_text},
 };
 
 use crate::utils::to_error;
-use crate::view::ui::rect::create_rect;
-use commitcanvas::model::{EventHistory, Guid, ShapeDetails};
+use commitcanvas::model::{EventHistory, Guid, ShapeConfig, ShapeDetails, TextDetails};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::text::TextMeasure;
 use commitcanvas::view::{Event, View};
+use infrastructure_svg::{shape, Element};
+use wasm_bindgen::JsValue;
+
+pub use measure::text_measure;
 
 pub struct UIView {
     pub items: HashMap<Guid, Item>,
+    grid: GridConfig,
+    measure: Box<dyn TextMeasure>,
 }
 
 #[derive(Debug)]
@@ -27,6 +35,8 @@ pub enum Item {
     Arrow {
         group: web_sys::SvgElement,
         path: web_sys::SvgPathElement,
+        head: web_sys::SvgElement,
+        head_fill: web_sys::SvgElement,
         selector: web_sys::SvgPathElement,
         #[allow(dead_code)]
         selector_closure: wasm_bindgen::closure::Closure<dyn Fn(web_sys::MouseEvent)>,
@@ -45,6 +55,8 @@ pub enum Item {
         selector: web_sys::SvgElement,
         #[allow(dead_code)]
         selector_closure: wasm_bindgen::closure::Closure<dyn Fn(web_sys::MouseEvent)>,
+        #[allow(dead_code)]
+        edit_closure: wasm_bindgen::closure::Closure<dyn Fn(web_sys::MouseEvent)>,
     },
 }
 
@@ -52,6 +64,8 @@ impl UIView {
     pub fn new() -> Self {
         Self {
             items: HashMap::new(),
+            grid: GridConfig::default(),
+            measure: text_measure(),
         }
     }
 }
@@ -72,49 +86,66 @@ impl Drop for Item {
     }
 }
 
+impl UIView {
+    /// The elements drawing a shape, shared with [`infrastructure_svg`].
+    fn element(&self, guid: Guid, config: &ShapeConfig) -> Element {
+        match &config.details {
+            // Empty text boxes show a placeholder so they can be found again
+            ShapeDetails::Text(details) if details.content.is_empty() => {
+                let placeholder = ShapeConfig {
+                    details: ShapeDetails::Text(TextDetails {
+                        content: "Text".to_string(),
+                        ..details.clone()
+                    }),
+                    ..config.clone()
+                };
+                shape(guid, &placeholder, &self.grid, self.measure.as_ref())
+            }
+            _ => shape(guid, config, &self.grid, self.measure.as_ref()),
+        }
+    }
+
+    fn create(&mut self, guid: Guid, config: &ShapeConfig) -> Result<(), JsValue> {
+        log::debug!("rendering shape: {:?}", guid);
+        let element = self.element(guid, config);
+        let item = match config.details {
+            ShapeDetails::Arrow(_) => create_arrow(guid, &element)?,
+            ShapeDetails::Rect(_) | ShapeDetails::Ellipse(_) => create_rect(guid, &element)?,
+            ShapeDetails::Text(_) => create_text(guid, &element, config, &self.grid)?,
+        };
+        self.items.insert(guid, item);
+        Ok(())
+    }
+
+    fn update(&self, guid: Guid, config: &ShapeConfig) -> Result<(), JsValue> {
+        let Some(item) = self.items.get(&guid) else {
+            log::warn!("Updating nonexistent config: {:?}", guid);
+            return Ok(());
+        };
+        let element = self.element(guid, config);
+        match config.details {
+            ShapeDetails::Arrow(_) => update_arrow(&element, item),
+            ShapeDetails::Rect(_) | ShapeDetails::Ellipse(_) => update_rect(&element, item),
+            ShapeDetails::Text(_) => update_text(&element, config, item, &self.grid),
+        }
+    }
+}
+
 impl View for UIView {
     fn process_event(&mut self, event: Event) -> Result<(), Box<dyn Error + Send + Sync>> {
         match event {
-            Event::Reload { shapes } => {
+            Event::Reload { shapes, settings } => {
                 self.items.clear();
+                self.grid = settings.grid;
+                grid::render_grid(&self.grid).map_err(to_error)?;
                 for (guid, config) in shapes {
-                    match config.details {
-                        ShapeDetails::Arrow(_) => {
-                            log::info!("rendering arrow: {:?}", guid);
-                            let item = create_arrow(*guid, config).map_err(to_error)?;
-                            self.items.insert(*guid, item);
-                        }
-                        ShapeDetails::Rect(_) => {
-                            log::info!("rendering rect: {:?}", guid);
-                            let item = create_rect(*guid, config).map_err(to_error)?;
-                            self.items.insert(*guid, item);
-                        }
-                        ShapeDetails::Text(_) => {
-                            log::debug!("rendering text: {:?}", guid);
-                            let item = create_text(*guid, config).map_err(to_error)?;
-                            self.items.insert(*guid, item);
-                        }
-                    }
+                    self.create(*guid, config).map_err(to_error)?;
                 }
             }
             Event::Modify { event } => match event {
-                EventHistory::Add { guid, config } => match config.details {
-                    ShapeDetails::Arrow(_) => {
-                        log::info!("rendering arrow: {:?}", guid);
-                        let item = create_arrow(guid, &config).map_err(to_error)?;
-                        self.items.insert(guid, item);
-                    }
-                    ShapeDetails::Rect(_) => {
-                        log::info!("rendering rect: {:?}", guid);
-                        let item = create_rect(guid, &config).map_err(to_error)?;
-                        self.items.insert(guid, item);
-                    }
-                    ShapeDetails::Text(_) => {
-                        log::debug!("rendering text: {:?}", guid);
-                        let item = create_text(guid, &config).map_err(to_error)?;
-                        self.items.insert(guid, item);
-                    }
-                },
+                EventHistory::Add { guid, config } => {
+                    self.create(guid, &config).map_err(to_error)?;
+                }
                 EventHistory::Remove { guid, .. } => {
                     if self.items.remove(&guid).is_some() {
                         log::info!("removing config: {:?}", guid);
@@ -122,31 +153,12 @@ impl View for UIView {
                         log::warn!("deleting nonexistent config: {:?}", guid);
                     }
                 }
-                EventHistory::Modify { guid, to, .. } => match to.details {
-                    ShapeDetails::Arrow(_) => {
-                        if let Some(item) = self.items.get(&guid) {
-                            update_arrow(&to, item).map_err(to_error)?;
-                        } else {
-                            log::warn!("Updating nonexistent config: {:?}", guid);
-                        }
-                    }
-                    ShapeDetails::Rect(_) => {
-                        if let Some(item) = self.items.get(&guid) {
-                            update_rect(&to, item).map_err(to_error)?;
-                        } else {
-                            log::warn!("Updating nonexistent config: {:?}", guid);
-                        }
-                    }
-                    ShapeDetails::Text(_) => {
-                        if let Some(item) = self.items.get(&guid) {
-                            update_text(&to, item).map_err(to_error)?;
-                        } else {
-                            log::warn!("Updating nonexistent config: {:?}", guid);
-                        }
-                    }
-                },
+                EventHistory::Modify { guid, to, .. } => {
+                    self.update(guid, &to).map_err(to_error)?;
+                }
                 EventHistory::Checkpoint => {}
             },
+            Event::Checkpoint { .. } => {}
         };
 
         Ok(())
@@ -1,7 +1,49 @@
 use commitcanvas::model::Guid;
+use infrastructure_svg::{Element, NAMESPACE};
+use wasm_bindgen::{JsCast, JsValue};
 
-pub fn to_identifier(guid: Guid) -> String {
-    format!("cc_id_{}", guid)
+use crate::globals::DOCUMENT;
+
+/// Builds `element` and its children into the page, detached.
+pub fn to_dom(element: &Element) -> Result<web_sys::SvgElement, JsValue> {
+    let dom = DOCUMENT
+        .with(|document| document.create_element_ns(Some(NAMESPACE), element.name))?
+        .dyn_into::<web_sys::SvgElement>()?;
+    set_attributes(&dom, element)?;
+    if let Some(text) = &element.text {
+        dom.set_text_content(Some(text));
+    }
+    for child in &element.children {
+        let child = to_dom(child)?;
+        dom.append_child(&child)?;
+    }
+    Ok(dom)
+}
+
+/// Gives `dom` the attributes of `element`, keeping its children.
+pub fn set_attributes(dom: &web_sys::Element, element: &Element) -> Result<(), JsValue> {
+    for (name, value) in &element.attributes {
+        dom.set_attribute(name, value)?;
+    }
+    Ok(())
+}
+
+/// Replaces the children of `dom` with the children of `element`.
+pub fn set_children(dom: &web_sys::Element, element: &Element) -> Result<(), JsValue> {
+    dom.set_inner_html("");
+    for child in &element.children {
+        let child = to_dom(child)?;
+        dom.append_child(&child)?;
+    }
+    Ok(())
+}
+
+/// The `index`th child of the group drawing a shape.
+pub fn part(element: &Element, index: usize) -> Result<&Element, JsValue> {
+    element
+        .children
+        .get(index)
+        .ok_or_else(|| JsValue::from_str("shape element is missing a part"))
 }
 
 #[allow(dead_code)]
@@ -1,19 +1,58 @@
+use commitcanvas::model::fragment::{self, FragmentError};
+use commitcanvas::model::Document;
 use commitcanvas::view::{Event, View};
+use wasm_bindgen::JsValue;
 
+use crate::globals::WINDOW;
+use crate::utils::to_error;
+
+/// Keeps the URL fragment in sync with the drawing, so the current URL can be
+/// shared as a link to it.
 pub struct UrlView {}
 
 impl UrlView {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// Reads the drawing stored in the current URL, if there is one.
+    pub fn load() -> Option<Result<Document, FragmentError>> {
+        let hash = WINDOW.with(|w| w.location().hash()).ok()?;
+        if hash.is_empty() || hash == "#" {
+            return None;
+        }
+        Some(fragment::decode(&hash))
+    }
+
+    fn replace_url(fragment: Option<&str>) -> Result<(), JsValue> {
+        WINDOW.with(|w| {
+            let location = w.location();
+            let url = format!(
+                "{}{}{}",
+                location.pathname()?,
+                location.search()?,
+                fragment.map(|f| format!("#{}", f)).unwrap_or_default()
+            );
+            w.history()?
+                .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+        })
+    }
 }
 
 impl View for UrlView {
     fn process_event(
         &mut self,
-        _event: Event,
+        event: Event,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        if let Event::Modify { .. } = _event {}
+        if let Event::Checkpoint { document } = event {
+            match fragment::encode(document) {
+                Ok(fragment) => Self::replace_url(Some(&fragment)).map_err(to_error)?,
+                Err(e) => {
+                    log::warn!("not storing drawing in url: {}", e);
+                    Self::replace_url(None).map_err(to_error)?;
+                }
+            }
+        }
         Ok(())
     }
 }
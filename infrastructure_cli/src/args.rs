@@ -0,0 +1,267 @@
+use std::path::{Path, PathBuf};
+
+use commitcanvas::model::DocumentFormat;
+use infrastructure_git::ImportOptions;
+
+pub const USAGE: &str = "\
+Usage: commitcanvas-cli [OPTIONS] <INPUT>
+
+Draws INPUT as a standalone SVG file or PNG image. INPUT is a saved document
+(.yaml, .yml, .json), a Mermaid gitGraph (.mmd, .mermaid), a gitGraph script
+(any other file) or a git repository (a directory). `-` reads from standard
+input.
+
+Options:
+  -o, --output <FILE>    Write to FILE instead of standard output
+  -f, --from <KIND>      Read INPUT as yaml, json, mermaid, script or git
+  -t, --to <FORMAT>      Write svg or png, png if FILE ends in .png
+  -r, --ref <REF>        Git: revision or range to walk from, repeatable
+  -n, --max-count <N>    Git: draw at most the N newest commits
+      --scale <FACTOR>   PNG: image pixels per pixel of the drawing [1]
+      --dpi <DPI>        PNG: resolution to store [96 times the scale]
+      --transparent      PNG: leave the background transparent
+      --font <FILE>      PNG: set text in FILE, not the bundled font, repeatable
+  -h, --help             Print this help
+  -V, --version          Print the version";
+
+/// What the input holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Document(DocumentFormat),
+    Mermaid,
+    Script,
+    Git,
+}
+
+impl Source {
+    fn from_name(name: &str) -> Option<Source> {
+        match name {
+            "yaml" => Some(Source::Document(DocumentFormat::Yaml)),
+            "json" => Some(Source::Document(DocumentFormat::Json)),
+            "mermaid" => Some(Source::Mermaid),
+            "script" => Some(Source::Script),
+            "git" => Some(Source::Git),
+            _ => None,
+        }
+    }
+
+    /// Guesses the source from the kind of file at `path`. Standard input is
+    /// expected to hold a YAML document.
+    pub fn detect(path: &Path) -> Source {
+        if path == Path::new("-") {
+            return Source::Document(DocumentFormat::Yaml);
+        }
+        if path.is_dir() {
+            return Source::Git;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => Source::Document(DocumentFormat::Yaml),
+            Some("json") => Source::Document(DocumentFormat::Json),
+            Some("mmd" | "mermaid") => Source::Mermaid,
+            _ => Source::Script,
+        }
+    }
+}
+
+/// What to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Png,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "svg" => Some(Format::Svg),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the name of the output file, SVG unless it
+    /// ends in `.png`.
+    pub fn detect(path: Option<&Path>) -> Format {
+        match path.and_then(|p| p.extension()).and_then(|e| e.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("png") => Format::Png,
+            _ => Format::Svg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    pub input: PathBuf,
+    /// Standard output if `None`.
+    pub output: Option<PathBuf>,
+    /// Detected from the input if `None`.
+    pub source: Option<Source>,
+    /// Detected from the output if `None`.
+    pub format: Option<Format>,
+    pub import: ImportOptions,
+    /// Scale 1 if `None`.
+    pub scale: Option<f32>,
+    /// Follows the scale if `None`.
+    pub dpi: Option<u32>,
+    pub transparent: bool,
+    pub fonts: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Render(Args),
+    Help,
+    Version,
+}
+
+/// Parses the arguments following the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let mut parsed = Args::default();
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} needs a value", option))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-o" | "--output" => parsed.output = Some(value(&arg)?.into()),
+            "-f" | "--from" => {
+                let name = value(&arg)?;
+                parsed.source = Some(
+                    Source::from_name(&name)
+                        .ok_or_else(|| format!("unknown input kind {}", name))?,
+                );
+            }
+            "-t" | "--to" => {
+                let name = value(&arg)?;
+                parsed.format = Some(
+                    Format::from_name(&name)
+                        .ok_or_else(|| format!("unknown output format {}", name))?,
+                );
+            }
+            "-r" | "--ref" => parsed.import.refs.push(value(&arg)?),
+            "-n" | "--max-count" => {
+                let count = value(&arg)?;
+                parsed.import.max_count = Some(
+                    count
+                        .parse()
+                        .map_err(|_| format!("invalid commit count {}", count))?,
+                );
+            }
+            "--scale" => {
+                let scale = value(&arg)?;
+                parsed.scale = Some(
+                    scale
+                        .parse()
+                        .ok()
+                        .filter(|scale: &f32| scale.is_finite() && *scale > 0.0)
+                        .ok_or_else(|| format!("invalid scale {}", scale))?,
+                );
+            }
+            "--dpi" => {
+                let dpi = value(&arg)?;
+                parsed.dpi = Some(
+                    dpi.parse()
+                        .ok()
+                        .filter(|dpi| *dpi > 0)
+                        .ok_or_else(|| format!("invalid resolution {}", dpi))?,
+                );
+            }
+            "--transparent" => parsed.transparent = true,
+            "--font" => parsed.fonts.push(value(&arg)?.into()),
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option {}", arg))
+            }
+            _ if input.is_some() => return Err(format!("unexpected argument {}", arg)),
+            _ => input = Some(PathBuf::from(arg)),
+        }
+    }
+    parsed.input = input.ok_or("missing input")?;
+    Ok(Command::Render(parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        super::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(&["-o", "out.svg", "--from", "git", "-r", "main", "-r", "v1", "-n", "5", "."]),
+            Ok(Command::Render(Args {
+                input: ".".into(),
+                output: Some("out.svg".into()),
+                source: Some(Source::Git),
+                import: ImportOptions {
+                    refs: vec!["main".to_string(), "v1".to_string()],
+                    max_count: Some(5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }))
+        );
+        assert_eq!(
+            parse(&[
+                "in.mmd",
+                "-t",
+                "png",
+                "--scale",
+                "2",
+                "--transparent",
+                "--font",
+                "a.ttf"
+            ]),
+            Ok(Command::Render(Args {
+                input: "in.mmd".into(),
+                format: Some(Format::Png),
+                scale: Some(2.0),
+                transparent: true,
+                fonts: vec!["a.ttf".into()],
+                ..Default::default()
+            }))
+        );
+        assert_eq!(parse(&["in.yaml", "--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse(&[]), Err("missing input".to_string()));
+        assert_eq!(parse(&["a", "b"]), Err("unexpected argument b".to_string()));
+        assert_eq!(parse(&["--bold"]), Err("unknown option --bold".to_string()));
+        assert_eq!(parse(&["a", "-o"]), Err("-o needs a value".to_string()));
+        assert_eq!(
+            parse(&["a", "-f", "png"]),
+            Err("unknown input kind png".to_string())
+        );
+        assert_eq!(
+            parse(&["a", "-n", "many"]),
+            Err("invalid commit count many".to_string())
+        );
+        assert_eq!(
+            parse(&["a", "-t", "gif"]),
+            Err("unknown output format gif".to_string())
+        );
+        assert_eq!(
+            parse(&["a", "--scale", "0"]),
+            Err("invalid scale 0".to_string())
+        );
+        assert_eq!(
+            parse(&["a", "--dpi", "-3"]),
+            Err("invalid resolution -3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(Format::detect(None), Format::Svg);
+        assert_eq!(Format::detect(Some(Path::new("out.svg"))), Format::Svg);
+        assert_eq!(Format::detect(Some(Path::new("out.PNG"))), Format::Png);
+    }
+}
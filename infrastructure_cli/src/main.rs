@@ -0,0 +1,187 @@
+//! `commitcanvas-cli` draws saved documents, gitGraph scripts and git
+//! repositories as standalone SVG files or PNG images, so diagrams can be
+//! regenerated without a browser.
+//!
+//! Exit codes tell failures apart:
+//!
+//! * 0 the drawing was written,
+//! * 2 the arguments are invalid,
+//! * 3 the input couldn't be parsed,
+//! * 4 a file or repository couldn't be read or written,
+//! * 5 the input was read but can't be drawn.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use args::{Args, Command, Format, Source, USAGE};
+use commitcanvas::gitgraph::{self, mermaid};
+use commitcanvas::model::{Document, Model};
+use commitcanvas::types::PointGrid;
+use infrastructure_git::ImportError;
+use infrastructure_png::{PngOptions, BASE_DPI};
+
+mod args;
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Parse {
+        path: PathBuf,
+        source: Box<dyn Error>,
+    },
+    Io {
+        path: PathBuf,
+        source: Box<dyn Error>,
+    },
+    Render(Box<dyn Error>),
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Parse { .. } => 3,
+            CliError::Io { .. } => 4,
+            CliError::Render(_) => 5,
+        }
+    }
+
+    fn parse(path: &Path, source: impl Into<Box<dyn Error>>) -> Self {
+        CliError::Parse {
+            path: path.to_path_buf(),
+            source: source.into(),
+        }
+    }
+
+    fn io(path: &Path, source: impl Into<Box<dyn Error>>) -> Self {
+        CliError::Io {
+            path: path.to_path_buf(),
+            source: source.into(),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Parse { path, source } => {
+                write!(f, "failed to parse {}: {}", path.display(), source)
+            }
+            CliError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            CliError::Render(source) => write!(f, "failed to render: {}", source),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, CliError> {
+    let mut input = String::new();
+    let result = if path == Path::new("-") {
+        io::stdin().read_to_string(&mut input).map(|_| ())
+    } else {
+        std::fs::read_to_string(path).map(|text| input = text)
+    };
+    result.map_err(|e| CliError::io(path, e))?;
+    Ok(input)
+}
+
+/// The document to draw, with generated diagrams starting at the origin.
+fn load(args: &Args) -> Result<Document, CliError> {
+    let path = args.input.as_path();
+    let source = args.source.unwrap_or_else(|| Source::detect(path));
+    log::info!("reading {} as {:?}", path.display(), source);
+    let mut model = Model::new();
+    let origin = PointGrid { x: 0, y: 0 };
+    match source {
+        Source::Document(format) => {
+            let document =
+                Document::parse(&read(path)?, format).map_err(|e| CliError::parse(path, e))?;
+            // Clashing GUIDs make the document malformed, not undrawable.
+            document.validate().map_err(|e| CliError::parse(path, e))?;
+            return Ok(document);
+        }
+        Source::Mermaid => {
+            mermaid::import(&read(path)?, &mut model, origin)
+                .map_err(|e| CliError::parse(path, e))?;
+        }
+        Source::Script => {
+            gitgraph::draw(&read(path)?, &mut model, origin)
+                .map_err(|e| CliError::parse(path, e))?;
+        }
+        Source::Git => {
+            infrastructure_git::import(path, &args.import, &mut model, origin).map_err(
+                |e| match e {
+                    ImportError::Git(_) => CliError::io(path, e),
+                    ImportError::UnknownRef { .. } => CliError::parse(path, e),
+                    ImportError::Layout(_) => CliError::Render(e.into()),
+                },
+            )?;
+        }
+    }
+    Ok(model.to_document())
+}
+
+fn png_options(args: &Args) -> Result<PngOptions, CliError> {
+    let scale = args.scale.unwrap_or(1.0);
+    let fonts = args
+        .fonts
+        .iter()
+        .map(|path| std::fs::read(path).map_err(|e| CliError::io(path, e)))
+        .collect::<Result<_, _>>()?;
+    Ok(PngOptions {
+        scale,
+        dpi: args
+            .dpi
+            .unwrap_or_else(|| (BASE_DPI as f32 * scale).round() as u32),
+        transparent: args.transparent,
+        fonts,
+    })
+}
+
+fn run(args: Args) -> Result<(), CliError> {
+    let document = load(&args)?;
+    let format = args
+        .format
+        .unwrap_or_else(|| Format::detect(args.output.as_deref()));
+    let output = match format {
+        Format::Svg => infrastructure_svg::render(&document)
+            .map_err(|e| CliError::Render(e.into()))?
+            .into_bytes(),
+        Format::Png => infrastructure_png::render(&document, &png_options(&args)?)
+            .map_err(|e| CliError::Render(e.into()))?,
+    };
+    match &args.output {
+        Some(path) => std::fs::write(path, output).map_err(|e| CliError::io(path, e)),
+        None => io::stdout()
+            .write_all(&output)
+            .map_err(|e| CliError::io(Path::new("-"), e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match args::parse(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Ok(Command::Version) => {
+            println!("commitcanvas-cli {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+        Ok(Command::Render(args)) => run(args),
+        Err(message) => Err(CliError::Usage(message)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("commitcanvas-cli: {}", e);
+            if let CliError::Usage(_) = e {
+                eprintln!("Try 'commitcanvas-cli --help' for more information.");
+            }
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
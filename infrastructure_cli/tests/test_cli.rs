@@ -0,0 +1,167 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use git2::{Repository, Signature};
+use tempfile::TempDir;
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_commitcanvas-cli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write(dir: &TempDir, name: &str, content: &str) -> String {
+    let path = dir.path().join(name);
+    std::fs::write(&path, content).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn test_script_to_file() {
+    let dir = TempDir::new().unwrap();
+    let script = write(&dir, "history.gitgraph", "commit\nbranch b\ncommit");
+    let svg = dir.path().join("history.svg");
+    let output = cli(&[&script, "-o", svg.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(output.stdout.is_empty());
+
+    let svg = std::fs::read_to_string(svg).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("class=\"cc_arrow\"").count(), 1);
+}
+
+#[test]
+fn test_script_to_png() {
+    let dir = TempDir::new().unwrap();
+    let script = write(&dir, "history.gitgraph", "commit\ncommit");
+    let image = dir.path().join("history.png");
+    let output = cli(&[&script, "-o", image.to_str().unwrap(), "--scale", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let decoder = png::Decoder::new(std::fs::File::open(&image).unwrap());
+    let reader = decoder.read_info().unwrap();
+    let info = reader.info();
+    assert_eq!(info.pixel_dims.unwrap().xppu, 7559);
+    let width = info.width;
+
+    let output = cli(&[&script, "--to", "png", "--transparent"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let decoder = png::Decoder::new(output.stdout.as_slice());
+    assert_eq!(decoder.read_info().unwrap().info().width * 2, width);
+
+    let missing = dir.path().join("missing.ttf");
+    let output = cli(&[&script, "-t", "png", "--font", missing.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_document_and_mermaid_to_stdout() {
+    let dir = TempDir::new().unwrap();
+    let document = write(
+        &dir,
+        "drawing.yaml",
+        "version: 3\n\
+         next_guid: 1\n\
+         settings: {}\n\
+         shapes:\n\
+         - guid: 0\n  \
+           config:\n    \
+             start: { x: 0, y: 0 }\n    \
+             end: { x: 4, y: 2 }\n    \
+             details: { type: rect, background: Blue }\n    \
+             options: { stroke_color: None, roughness: Medium, thickness: Thin }\n",
+    );
+    let output = cli(&[&document]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let svg = String::from_utf8(output.stdout).unwrap();
+    assert!(svg.contains("cc_fill_blue"));
+
+    let diagram = write(&dir, "flow.mmd", "gitGraph\n  commit id: \"one\"\n");
+    let output = cli(&[&diagram]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains(">one</tspan>"));
+}
+
+#[test]
+fn test_duplicate_guids() {
+    let dir = TempDir::new().unwrap();
+    let shape = "- guid: 0\n  \
+           config:\n    \
+             start: { x: 0, y: 0 }\n    \
+             end: { x: 4, y: 2 }\n    \
+             details: { type: rect, background: Blue }\n    \
+             options: { stroke_color: None, roughness: Medium, thickness: Thin }\n";
+    let document = write(
+        &dir,
+        "clash.yaml",
+        &format!(
+            "version: 3\nnext_guid: 1\nsettings: {{}}\nshapes:\n{}{}",
+            shape, shape
+        ),
+    );
+    let output = cli(&[&document]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("shape 0 appears more than once"));
+}
+
+#[test]
+fn test_git_repository() {
+    let dir = TempDir::new().unwrap();
+    let repository = Repository::init(dir.path()).unwrap();
+    let tree = repository.treebuilder(None).unwrap().write().unwrap();
+    let tree = repository.find_tree(tree).unwrap();
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    repository
+        .commit(Some("HEAD"), &signature, &signature, "first", &tree, &[])
+        .unwrap();
+
+    let path = dir.path().to_str().unwrap();
+    let output = cli(&[path]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("<svg"));
+
+    let output = cli(&[path, "--ref", "nope"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_exit_codes() {
+    let dir = TempDir::new().unwrap();
+
+    let output = cli(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("missing input"));
+
+    let script = write(&dir, "broken.gitgraph", "commit\nmerge nowhere");
+    let output = cli(&[&script]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("line 2, column 1: no branch named nowhere"));
+
+    let missing = dir.path().join("missing.yaml");
+    let output = cli(&[missing.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = cli(&[&script, "--from", "git"]);
+    assert_eq!(output.status.code(), Some(4));
+
+    let empty = write(
+        &dir,
+        "empty.yaml",
+        "version: 3\nnext_guid: 0\nsettings: {}\nshapes: []\n",
+    );
+    let output = cli(&[&empty]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stderr(&output).contains("no shapes"));
+
+    let unwritable = Path::new(&empty).join("out.svg");
+    let script = write(&dir, "fine.gitgraph", "commit");
+    let output = cli(&[&script, "-o", unwritable.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(4));
+}
@@ -0,0 +1,85 @@
+//! Reads the history of a local git repository and draws it as shapes.
+//!
+//! [`read_graph`] walks the commits reachable from a set of refs into a
+//! [`CommitGraph`]; [`events`] lays the graph out with
+//! [`commitcanvas::layout`] and turns it into [`Event::Add`] events for a
+//! [`Model`]: a node and abbreviated id per commit, an arrow from every commit
+//! to each of its parents and a label per branch and tag. [`import`]
+//! does both and adds the shapes in a single undo step.
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use commitcanvas::layout::LayoutError;
+use commitcanvas::model::{Event, Guid, Model};
+use commitcanvas::types::PointGrid;
+
+mod shapes;
+mod walk;
+
+pub use shapes::events;
+pub use walk::{read_graph, Commit, CommitGraph, ImportOptions, Label, LabelKind};
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// The repository couldn't be opened or read.
+    Git(git2::Error),
+    /// A ref to walk from doesn't name a commit.
+    UnknownRef { name: String, source: git2::Error },
+    /// The commit graph couldn't be laid out.
+    Layout(LayoutError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Git(e) => write!(f, "failed to read repository: {}", e),
+            ImportError::UnknownRef { name, .. } => write!(f, "unknown ref: {}", name),
+            ImportError::Layout(e) => write!(f, "failed to lay out commits: {}", e),
+        }
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ImportError::Git(e) => Some(e),
+            ImportError::UnknownRef { source, .. } => Some(source),
+            ImportError::Layout(e) => Some(e),
+        }
+    }
+}
+
+impl From<git2::Error> for ImportError {
+    fn from(e: git2::Error) -> Self {
+        ImportError::Git(e)
+    }
+}
+
+impl From<LayoutError> for ImportError {
+    fn from(e: LayoutError) -> Self {
+        ImportError::Layout(e)
+    }
+}
+
+/// Adds the history of the repository at `path` to `model` as one undo step,
+/// with the oldest commit at `origin`.
+///
+/// Returns the guids of the added shapes.
+pub fn import(
+    path: &Path,
+    options: &ImportOptions,
+    model: &mut Model,
+    origin: PointGrid,
+) -> Result<Vec<Guid>, ImportError> {
+    let repository = git2::Repository::open(path)?;
+    let graph = read_graph(&repository, options)?;
+    log::info!(
+        "importing {} commits and {} labels",
+        graph.commits.len(),
+        graph.labels.len()
+    );
+    let events: Vec<Event> = events(&graph, origin)?;
+    Ok(model.process_events(events))
+}
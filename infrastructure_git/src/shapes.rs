@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use commitcanvas::layout::{
+    layout, Branch, Dag, Layout, LayoutConfig, LayoutError, Node, Orientation,
+};
+use commitcanvas::model::{
+    ArrowDetails, Color, EllipseDetails, Event, FontSize, Options, RectDetails, ShapeConfig,
+    ShapeDetails, TextAlign, TextDetails, VerticalAlign,
+};
+use commitcanvas::types::{PointGrid, VecGrid};
+
+use crate::walk::{CommitGraph, LabelKind};
+use crate::ImportError;
+
+/// Grid cells between two consecutive commits.
+const COMMIT_SPACING: i32 = 8;
+/// Grid cells between two lanes.
+const LANE_SPACING: i32 = 6;
+/// Width and height of a commit node.
+const NODE_SIZE: i32 = 2;
+/// Width of the abbreviated id and of labels.
+const LABEL_WIDTH: i32 = 6;
+/// Height of the abbreviated id and of labels.
+const LABEL_HEIGHT: i32 = 2;
+
+/// The commit graph laid out left to right from oldest to newest, the
+/// checked out branch in the top lane. Positions are the top left corners of
+/// the commit nodes, relative to the oldest commit.
+fn place(graph: &CommitGraph) -> Result<Layout, LayoutError> {
+    let mut branches: Vec<Branch> = graph
+        .labels
+        .iter()
+        .filter(|label| label.kind == LabelKind::Branch)
+        .map(|label| Branch {
+            name: label.name.clone(),
+            head: label.target.clone(),
+        })
+        .collect();
+    // Stable, so the other branches stay sorted by name.
+    branches.sort_by_key(|branch| Some(&branch.name) != graph.head.as_ref());
+    let dag = Dag {
+        nodes: graph
+            .commits
+            .iter()
+            .rev()
+            .map(|commit| Node {
+                id: commit.id.clone(),
+                parents: commit.parents.clone(),
+            })
+            .collect(),
+        branches,
+    };
+    let config = LayoutConfig {
+        orientation: Orientation::Horizontal,
+        commit_spacing: COMMIT_SPACING,
+        lane_spacing: LANE_SPACING,
+    };
+    layout(&dag, &config)
+}
+
+fn add(start: PointGrid, end: PointGrid, details: ShapeDetails) -> Event {
+    Event::Add {
+        guid: None,
+        config: ShapeConfig {
+            start,
+            end,
+            details,
+            options: Options::default(),
+            seed: 0,
+        },
+    }
+}
+
+fn text(content: &str) -> ShapeDetails {
+    ShapeDetails::Text(TextDetails {
+        content: content.to_string(),
+        font_size: FontSize::Small,
+        align: TextAlign::Center,
+        vertical_align: VerticalAlign::Middle,
+    })
+}
+
+/// The shapes drawing `graph`, with the oldest commit at `origin`.
+///
+/// Every commit is a node with its abbreviated id below and an arrow to each
+/// of its parents, which follows the lanes of the layout. Branches and tags
+/// are stacked above the node they point at.
+///
+/// Fails if the graph can't be laid out, e.g. because a parent is missing.
+pub fn events(graph: &CommitGraph, origin: PointGrid) -> Result<Vec<Event>, ImportError> {
+    let layout = place(graph)?;
+    let offset = VecGrid {
+        x: origin.x,
+        y: origin.y,
+    };
+    let positions: HashMap<&str, PointGrid> = layout
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node.position + offset))
+        .collect();
+    let node = |id: &str| positions.get(id).copied();
+    let center_y = NODE_SIZE / 2;
+    let label_offset = VecGrid {
+        x: (NODE_SIZE - LABEL_WIDTH) / 2,
+        y: 0,
+    };
+
+    let mut events = Vec::new();
+    for commit in &graph.commits {
+        let Some(p) = node(&commit.id) else {
+            continue;
+        };
+        events.push(add(
+            p,
+            p + VecGrid {
+                x: NODE_SIZE,
+                y: NODE_SIZE,
+            },
+            ShapeDetails::Ellipse(EllipseDetails::default()),
+        ));
+        let id_start = p + label_offset + VecGrid { x: 0, y: NODE_SIZE };
+        events.push(add(
+            id_start,
+            id_start
+                + VecGrid {
+                    x: LABEL_WIDTH,
+                    y: LABEL_HEIGHT,
+                },
+            text(commit.short_id()),
+        ));
+    }
+
+    // Git points from a commit to its parents, so the arrows run along the
+    // routes of the layout backwards, through the centers of the nodes.
+    let center = VecGrid {
+        x: NODE_SIZE / 2,
+        y: center_y,
+    };
+    for edge in &layout.edges {
+        let (Some(parent), Some(child)) = (node(&edge.parent), node(&edge.child)) else {
+            continue;
+        };
+        let waypoints = edge.points[1..edge.points.len() - 1]
+            .iter()
+            .rev()
+            .map(|&point| point + offset + center)
+            .collect();
+        events.push(add(
+            child + VecGrid { x: 0, y: center_y },
+            parent
+                + VecGrid {
+                    x: NODE_SIZE,
+                    y: center_y,
+                },
+            ShapeDetails::Arrow(ArrowDetails {
+                waypoints,
+                ..Default::default()
+            }),
+        ));
+    }
+
+    let mut stacked: HashMap<&str, i32> = HashMap::new();
+    for label in &graph.labels {
+        let Some(p) = node(&label.target) else {
+            continue;
+        };
+        let level = stacked.entry(&label.target).or_default();
+        *level += 1;
+        let start = p
+            + label_offset
+            + VecGrid {
+                x: 0,
+                y: -*level * LABEL_HEIGHT,
+            };
+        let end = start
+            + VecGrid {
+                x: LABEL_WIDTH,
+                y: LABEL_HEIGHT,
+            };
+        let background = match label.kind {
+            LabelKind::Branch => Color::Sky,
+            LabelKind::Tag => Color::None,
+        };
+        events.push(add(
+            start,
+            end,
+            ShapeDetails::Rect(RectDetails {
+                background,
+                ..Default::default()
+            }),
+        ));
+        events.push(add(start, end, text(&label.name)));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::walk::Commit;
+
+    fn commit(id: &str, parents: &[&str]) -> Commit {
+        Commit {
+            id: id.to_string(),
+            summary: String::new(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            time: 0,
+        }
+    }
+
+    #[test]
+    fn test_positions() {
+        // d merges the side branch c into b, a is the root.
+        let graph = CommitGraph {
+            commits: vec![
+                commit("d", &["b", "c"]),
+                commit("c", &["a"]),
+                commit("b", &["a"]),
+                commit("a", &[]),
+            ],
+            labels: vec![],
+            head: None,
+        };
+        let layout = place(&graph).unwrap();
+        let position = |id: &str| layout.node(id).unwrap().position;
+        let lane = |id: &str| position(id).y / LANE_SPACING;
+        assert_eq!(lane("d"), 0);
+        assert_eq!(lane("b"), 0);
+        assert_eq!(lane("a"), 0);
+        assert_eq!(lane("c"), 1);
+        assert_eq!(position("a").x, 0);
+        assert_eq!(position("d").x, 3 * COMMIT_SPACING);
+    }
+
+    #[test]
+    fn test_arrows_follow_lanes() {
+        let graph = CommitGraph {
+            commits: vec![
+                commit("d", &["b", "c"]),
+                commit("c", &["a"]),
+                commit("b", &["a"]),
+                commit("a", &[]),
+            ],
+            labels: vec![],
+            head: None,
+        };
+        let arrows: Vec<ShapeConfig> = events(&graph, PointGrid { x: 0, y: 0 })
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Add { config, .. } if matches!(config.details, ShapeDetails::Arrow(_)) => {
+                    Some(config)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(arrows.len(), 4);
+        // The edges to and from c bend into its lane instead of cutting
+        // across, the others run straight along the top lane.
+        let bent = arrows
+            .iter()
+            .filter(|config| config.points().len() == 4)
+            .count();
+        assert_eq!(bent, 2);
+        for config in &arrows {
+            assert!(config
+                .points()
+                .windows(2)
+                .all(|segment| segment[0].x == segment[1].x || segment[0].y == segment[1].y));
+        }
+    }
+}
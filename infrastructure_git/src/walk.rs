@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use git2::{Oid, Repository, Sort};
+
+use crate::ImportError;
+
+/// Which commits to import.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportOptions {
+    /// Revisions to walk from, e.g. `main`, `v1.0` or a range like
+    /// `v1.0..main`. `HEAD` if empty.
+    pub refs: Vec<String>,
+    /// At most this many commits, the newest ones.
+    pub max_count: Option<usize>,
+    /// Only commits committed at or after this time, in seconds since the
+    /// epoch.
+    pub since: Option<i64>,
+    /// Only commits committed at or before this time, in seconds since the
+    /// epoch.
+    pub until: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    /// The full hexadecimal id.
+    pub id: String,
+    /// The first line of the message.
+    pub summary: String,
+    /// Ids of the parents, in order. Parents that weren't imported are left
+    /// out.
+    pub parents: Vec<String>,
+    /// Commit time in seconds since the epoch.
+    pub time: i64,
+}
+
+impl Commit {
+    pub fn short_id(&self) -> &str {
+        &self.id[..7.min(self.id.len())]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelKind {
+    Branch,
+    Tag,
+}
+
+/// A branch or tag pointing at an imported commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub name: String,
+    pub kind: LabelKind,
+    /// Id of the commit the label points at.
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitGraph {
+    /// Newest first; every commit comes before its parents.
+    pub commits: Vec<Commit>,
+    /// Sorted by kind and name.
+    pub labels: Vec<Label>,
+    /// The branch checked out, unless HEAD is detached.
+    pub head: Option<String>,
+}
+
+/// Walks the commits selected by `options`.
+pub fn read_graph(
+    repository: &Repository,
+    options: &ImportOptions,
+) -> Result<CommitGraph, ImportError> {
+    let mut walk = repository.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    let refs = if options.refs.is_empty() {
+        vec!["HEAD".to_string()]
+    } else {
+        options.refs.clone()
+    };
+    for name in &refs {
+        let unknown = |source| ImportError::UnknownRef {
+            name: name.clone(),
+            source,
+        };
+        if name.contains("..") {
+            walk.push_range(name).map_err(unknown)?;
+        } else {
+            let commit = repository
+                .revparse_single(name)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(unknown)?;
+            walk.push(commit.id())?;
+        }
+    }
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        if options.max_count.is_some_and(|max| commits.len() >= max) {
+            break;
+        }
+        let commit = repository.find_commit(oid?)?;
+        let time = commit.time().seconds();
+        if options.since.is_some_and(|since| time < since)
+            || options.until.is_some_and(|until| time > until)
+        {
+            continue;
+        }
+        commits.push((commit.id(), commit));
+    }
+
+    let imported: HashSet<Oid> = commits.iter().map(|(oid, _)| *oid).collect();
+    let labels = read_labels(repository, &imported)?;
+    let commits = commits
+        .into_iter()
+        .map(|(oid, commit)| Commit {
+            id: oid.to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            parents: commit
+                .parent_ids()
+                .filter(|parent| imported.contains(parent))
+                .map(|parent| parent.to_string())
+                .collect(),
+            time: commit.time().seconds(),
+        })
+        .collect();
+
+    let head = repository
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string));
+
+    Ok(CommitGraph {
+        commits,
+        labels,
+        head,
+    })
+}
+
+/// The local branches and tags pointing at one of the `imported` commits.
+fn read_labels(
+    repository: &Repository,
+    imported: &HashSet<Oid>,
+) -> Result<Vec<Label>, ImportError> {
+    let mut labels = Vec::new();
+    for reference in repository.references()? {
+        let reference = reference?;
+        let kind = if reference.is_branch() {
+            LabelKind::Branch
+        } else if reference.is_tag() {
+            LabelKind::Tag
+        } else {
+            continue;
+        };
+        // Tags may point at other objects than commits, e.g. a blob.
+        let Ok(target) = reference.peel_to_commit() else {
+            continue;
+        };
+        if !imported.contains(&target.id()) {
+            continue;
+        }
+        if let Some(name) = reference.shorthand() {
+            labels.push(Label {
+                name: name.to_string(),
+                kind,
+                target: target.id().to_string(),
+            });
+        }
+    }
+    labels.sort_by(|a, b| {
+        (a.kind == LabelKind::Tag, &a.name).cmp(&(b.kind == LabelKind::Tag, &b.name))
+    });
+    Ok(labels)
+}
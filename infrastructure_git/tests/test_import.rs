@@ -0,0 +1,200 @@
+use commitcanvas::model::{Model, ShapeDetails};
+use commitcanvas::types::PointGrid;
+use git2::{Oid, Repository, Signature, Time};
+use infrastructure_git::{import, read_graph, ImportError, ImportOptions, LabelKind};
+use tempfile::TempDir;
+
+fn repository() -> (TempDir, Repository) {
+    let dir = TempDir::new().unwrap();
+    let repository = Repository::init(dir.path()).unwrap();
+    (dir, repository)
+}
+
+/// Commits an empty tree at `time` without moving any ref.
+fn commit(repository: &Repository, message: &str, time: i64, parents: &[Oid]) -> Oid {
+    let tree = repository.treebuilder(None).unwrap().write().unwrap();
+    let tree = repository.find_tree(tree).unwrap();
+    let signature = Signature::new("Test", "test@example.com", &Time::new(time, 0)).unwrap();
+    let parents: Vec<_> = parents
+        .iter()
+        .map(|oid| repository.find_commit(*oid).unwrap())
+        .collect();
+    let parents: Vec<_> = parents.iter().collect();
+    repository
+        .commit(None, &signature, &signature, message, &tree, &parents)
+        .unwrap()
+}
+
+fn branch(repository: &Repository, name: &str, target: Oid) {
+    let commit = repository.find_commit(target).unwrap();
+    repository.branch(name, &commit, true).unwrap();
+}
+
+/// Three commits a <- b <- c with `main` at c and HEAD on `main`.
+fn linear(repository: &Repository) -> [Oid; 3] {
+    let a = commit(repository, "a", 100, &[]);
+    let b = commit(repository, "b", 200, &[a]);
+    let c = commit(repository, "c", 300, &[b]);
+    branch(repository, "main", c);
+    repository.set_head("refs/heads/main").unwrap();
+    [a, b, c]
+}
+
+fn ids(oids: &[Oid]) -> Vec<String> {
+    oids.iter().map(|oid| oid.to_string()).collect()
+}
+
+fn options(refs: &[&str]) -> ImportOptions {
+    ImportOptions {
+        refs: refs.iter().map(|r| r.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_linear_history() {
+    let (_dir, repository) = repository();
+    let [a, b, c] = linear(&repository);
+
+    let graph = read_graph(&repository, &ImportOptions::default()).unwrap();
+    let commits: Vec<_> = graph.commits.iter().map(|c| c.id.clone()).collect();
+    assert_eq!(commits, ids(&[c, b, a]));
+    assert_eq!(graph.commits[0].parents, ids(&[b]));
+    assert_eq!(graph.commits[0].summary, "c");
+    assert_eq!(graph.commits[0].time, 300);
+    assert!(graph.commits[2].parents.is_empty());
+
+    assert_eq!(graph.labels.len(), 1);
+    assert_eq!(graph.labels[0].name, "main");
+    assert_eq!(graph.labels[0].kind, LabelKind::Branch);
+    assert_eq!(graph.labels[0].target, c.to_string());
+    assert_eq!(graph.head.as_deref(), Some("main"));
+}
+
+#[test]
+fn test_merge() {
+    let (_dir, repository) = repository();
+    let a = commit(&repository, "a", 100, &[]);
+    let b = commit(&repository, "b", 200, &[a]);
+    let c = commit(&repository, "c", 300, &[a]);
+    let d = commit(&repository, "merge", 400, &[b, c]);
+    branch(&repository, "main", d);
+    branch(&repository, "feature", c);
+
+    let graph = read_graph(&repository, &options(&["main"])).unwrap();
+    assert_eq!(graph.commits.len(), 4);
+    assert_eq!(graph.commits[0].parents, ids(&[b, c]));
+    let labels: Vec<_> = graph.labels.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(labels, ["feature", "main"]);
+}
+
+#[test]
+fn test_max_count() {
+    let (_dir, repository) = repository();
+    let [_, b, c] = linear(&repository);
+
+    let options = ImportOptions {
+        max_count: Some(2),
+        ..Default::default()
+    };
+    let graph = read_graph(&repository, &options).unwrap();
+    let commits: Vec<_> = graph.commits.iter().map(|c| c.id.clone()).collect();
+    assert_eq!(commits, ids(&[c, b]));
+    // The parent of b wasn't imported.
+    assert!(graph.commits[1].parents.is_empty());
+}
+
+#[test]
+fn test_date_range() {
+    let (_dir, repository) = repository();
+    let [_, b, _] = linear(&repository);
+
+    let options = ImportOptions {
+        since: Some(150),
+        until: Some(250),
+        ..Default::default()
+    };
+    let graph = read_graph(&repository, &options).unwrap();
+    assert_eq!(graph.commits.len(), 1);
+    assert_eq!(graph.commits[0].id, b.to_string());
+    // main points at c, which is outside the range.
+    assert!(graph.labels.is_empty());
+}
+
+#[test]
+fn test_range_and_tags() {
+    let (_dir, repository) = repository();
+    let [a, b, c] = linear(&repository);
+    repository
+        .tag_lightweight("v1", &repository.find_object(a, None).unwrap(), false)
+        .unwrap();
+    let signature = Signature::new("Test", "test@example.com", &Time::new(250, 0)).unwrap();
+    repository
+        .tag(
+            "v2",
+            &repository.find_object(b, None).unwrap(),
+            &signature,
+            "second release",
+            false,
+        )
+        .unwrap();
+
+    let graph = read_graph(&repository, &options(&["v1..main"])).unwrap();
+    let commits: Vec<_> = graph.commits.iter().map(|c| c.id.clone()).collect();
+    assert_eq!(commits, ids(&[c, b]));
+
+    let labels: Vec<_> = graph
+        .labels
+        .iter()
+        .map(|l| (l.name.as_str(), l.kind, l.target.clone()))
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            ("main", LabelKind::Branch, c.to_string()),
+            ("v2", LabelKind::Tag, b.to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_import_into_model() {
+    let (dir, repository) = repository();
+    linear(&repository);
+
+    let mut model = Model::new();
+    let guids = import(
+        dir.path(),
+        &ImportOptions::default(),
+        &mut model,
+        PointGrid { x: 2, y: 4 },
+    )
+    .unwrap();
+    // A node and an id per commit, two arrows and a label with its text.
+    assert_eq!(guids.len(), 3 * 2 + 2 + 2);
+    assert_eq!(model.shapes().count(), guids.len());
+    let arrows = model
+        .shapes()
+        .filter(|(_, config)| matches!(config.details, ShapeDetails::Arrow(_)))
+        .count();
+    assert_eq!(arrows, 2);
+    // The oldest commit is at the origin.
+    assert!(model
+        .shapes()
+        .any(|(_, config)| config.start == PointGrid { x: 2, y: 4 }));
+
+    model.undo();
+    assert_eq!(model.shapes().count(), 0);
+}
+
+#[test]
+fn test_unknown_ref() {
+    let (_dir, repository) = repository();
+    linear(&repository);
+
+    let result = read_graph(&repository, &options(&["does-not-exist"]));
+    match result {
+        Err(ImportError::UnknownRef { name, .. }) => assert_eq!(name, "does-not-exist"),
+        other => panic!("expected an unknown ref, got {:?}", other),
+    }
+}
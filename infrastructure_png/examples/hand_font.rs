@@ -0,0 +1,798 @@
+//! Generates `fonts/CommitcanvasHand-Regular.ttf`, the hand-drawn font PNGs
+//! set text in:
+//!
+//! ```sh
+//! cargo run -p infrastructure_png --example hand_font
+//! ```
+//!
+//! Every glyph is drawn as a few strokes of a round pen on a grid of design
+//! units, 100 font units each, with the baseline at 0, the x-height at 4.8
+//! and the cap height at 7. A stroke is a list of points and arcs:
+//!
+//! * `x,y` is a point,
+//! * `a:cx,cy,rx,ry,from,to` runs along the ellipse around `(cx, cy)` from the
+//!   angle `from` to `to` in degrees, counterclockwise when `to` is larger,
+//! * `|` starts a new stroke.
+//!
+//! The points are moved by a small, fixed amount each so lines wobble a
+//! little like drawn by hand. The output only depends on the tables below.
+
+use std::f32::consts::PI;
+use std::path::Path;
+
+/// Font units per design unit.
+const UNIT: f32 = 100.0;
+/// Width of the pen in design units.
+const PEN: f32 = 0.62;
+/// How far points move at most, in design units.
+const WOBBLE: f32 = 0.05;
+const UNITS_PER_EM: u16 = 1000;
+const ASCENDER: i16 = 920;
+const DESCENDER: i16 = -280;
+const X_HEIGHT: f32 = 4.8;
+const CAP_HEIGHT: f32 = 7.0;
+
+const FAMILY: &str = "Commitcanvas Hand";
+const POSTSCRIPT_NAME: &str = "CommitcanvasHand-Regular";
+const VERSION: &str = "Version 1.000";
+const COPYRIGHT: &str = "Copyright 2026 The Commitcanvas Hand Project Authors";
+const LICENSE: &str = "This Font Software is licensed under the SIL Open Font License, \
+                       Version 1.1. This license is available with a FAQ at: \
+                       https://openfontlicense.org";
+const LICENSE_URL: &str = "https://openfontlicense.org";
+
+/// Characters with their advance width and strokes.
+const GLYPHS: &[(char, f32, &str)] = &[
+    (' ', 2.4, ""),
+    ('\u{a0}', 2.4, ""),
+    ('!', 1.8, "0.9,7 0.9,2.2 | 0.9,0.2 0.9,0.4"),
+    ('"', 2.6, "0.8,7 0.8,5.4 | 1.8,7 1.8,5.4"),
+    (
+        '#',
+        5.4,
+        "1.8,6.4 1.2,0.4 | 4,6.4 3.4,0.4 | 0.4,4.4 5,4.4 | 0.3,2.2 4.9,2.2",
+    ),
+    (
+        '$',
+        4.8,
+        "a:2.4,4.8,1.6,1.3,30,270 a:2.4,2.2,1.8,1.3,90,-150 | 2.4,7.4 2.4,-0.6",
+    ),
+    (
+        '%',
+        5.6,
+        "a:1.4,5.6,0.9,1.1,0,360 | a:4.2,1.4,0.9,1.1,0,360 | 4.6,7 1,0",
+    ),
+    (
+        '&',
+        5.6,
+        "5.2,0 1.4,4.8 1.6,6.4 2.6,7 3.4,6.2 3,5 0.8,2.6 0.8,1 2,0 3.4,0.4 5,2.6",
+    ),
+    ('\'', 1.6, "0.8,7 0.8,5.4"),
+    ('(', 2.6, "a:3.6,3,2.6,4.4,130,230"),
+    (')', 2.6, "a:-1,3,2.6,4.4,50,-50"),
+    ('*', 4.0, "2,6.6 2,3.8 | 0.8,5.9 3.2,4.5 | 3.2,5.9 0.8,4.5"),
+    ('+', 4.8, "2.4,5 2.4,1 | 0.4,3 4.4,3"),
+    (',', 1.8, "1.1,0.4 0.6,-1.2"),
+    ('-', 3.6, "0.6,2.8 3,2.8"),
+    ('.', 1.6, "0.8,0.1 0.8,0.3"),
+    ('/', 3.6, "3.2,7.2 0.4,-0.6"),
+    ('0', 4.8, "a:2.4,3.5,1.9,3.5,0,360"),
+    ('1', 4.8, "1.2,5.6 2.6,7 2.6,0 | 1.2,0 4,0"),
+    ('2', 4.8, "a:2.4,5.1,1.8,1.9,160,-30 0.6,0 4.3,0"),
+    (
+        '3',
+        4.8,
+        "a:2.3,5.3,1.7,1.7,150,-90 a:2.3,1.8,2,1.8,90,-150",
+    ),
+    ('4', 4.8, "3.4,0 3.4,7 0.4,2 4.5,2"),
+    ('5', 4.8, "4.1,7 1,7 0.8,3.9 a:2.3,2.2,2,2.2,130,-150"),
+    ('6', 4.8, "3.6,7 1.8,5 0.6,2.6 | a:2.4,2,1.9,2,0,360"),
+    ('7', 4.8, "0.6,7 4.3,7 1.8,0"),
+    (
+        '8',
+        4.8,
+        "a:2.4,5.3,1.5,1.7,0,360 | a:2.4,1.8,1.9,1.8,0,360",
+    ),
+    ('9', 4.8, "a:2.4,5,1.9,2,0,360 | 4.3,5 4.1,2.6 2.8,0"),
+    (':', 1.6, "0.8,4.2 0.8,4.4 | 0.8,0.1 0.8,0.3"),
+    (';', 1.8, "1.1,4.2 1.1,4.4 | 1.1,0.4 0.6,-1.2"),
+    ('<', 4.4, "3.8,5.6 0.6,3 3.8,0.4"),
+    ('=', 4.4, "0.6,4 3.8,4 | 0.6,2 3.8,2"),
+    ('>', 4.4, "0.6,5.6 3.8,3 0.6,0.4"),
+    (
+        '?',
+        4.4,
+        "a:2.2,5.2,1.7,1.8,160,-60 2.2,2.2 | 2.2,0.2 2.2,0.4",
+    ),
+    (
+        '@',
+        7.0,
+        "a:3.4,3.1,1.1,1.3,0,360 | 4.5,4.4 4.5,2 5.5,1.5 6.4,3 a:3.5,3.2,2.9,3.2,0,300",
+    ),
+    ('A', 5.6, "0.4,0 2.8,7 5.2,0 | 1.2,2.4 4.4,2.4"),
+    (
+        'B',
+        5.2,
+        "0.8,0 0.8,7 3,7 a:3,5.3,1.7,1.7,90,-90 0.8,3.6 | 3,3.6 a:3,1.8,2,1.8,90,-90 0.8,0",
+    ),
+    ('C', 5.4, "a:3,3.5,2.5,3.5,50,310"),
+    ('D', 5.6, "0.8,0 0.8,7 2.4,7 a:2.4,3.5,2.6,3.5,90,-90 0.8,0"),
+    ('E', 4.8, "4.3,7 0.8,7 0.8,0 4.3,0 | 0.8,3.6 3.6,3.6"),
+    ('F', 4.6, "4.2,7 0.8,7 0.8,0 | 0.8,3.6 3.5,3.6"),
+    ('G', 5.8, "a:3,3.5,2.5,3.5,45,350 | 3.5,3 5.5,3"),
+    ('H', 5.6, "0.8,0 0.8,7 | 4.8,0 4.8,7 | 0.8,3.6 4.8,3.6"),
+    ('I', 2.2, "1.1,0 1.1,7"),
+    ('J', 4.2, "3.4,7 3.4,1.6 a:2,1.6,1.4,1.6,0,-180"),
+    ('K', 5.2, "0.8,0 0.8,7 | 4.6,7 0.8,2.6 | 2.2,4.2 4.8,0"),
+    ('L', 4.4, "0.8,7 0.8,0 4.2,0"),
+    ('M', 6.8, "0.6,0 1,7 3.4,2 5.8,7 6.2,0"),
+    ('N', 5.8, "0.8,0 0.8,7 5,0 5,7"),
+    ('O', 6.0, "a:3,3.5,2.5,3.5,0,360"),
+    ('P', 5.2, "0.8,0 0.8,7 3,7 a:3,5.1,1.9,1.9,90,-90 0.8,3.2"),
+    ('Q', 6.0, "a:3,3.5,2.5,3.5,0,360 | 3.4,1.6 5.4,-0.4"),
+    (
+        'R',
+        5.4,
+        "0.8,0 0.8,7 3,7 a:3,5.1,1.9,1.9,90,-90 0.8,3.2 | 2.8,3.2 4.9,0",
+    ),
+    (
+        'S',
+        5.0,
+        "a:2.5,5.3,1.9,1.7,30,270 a:2.5,1.8,2.1,1.8,90,-150",
+    ),
+    ('T', 5.2, "0.4,7 4.8,7 | 2.6,7 2.6,0"),
+    ('U', 5.6, "0.8,7 0.8,2 a:2.8,2,2,2,180,360 4.8,7"),
+    ('V', 5.6, "0.4,7 2.8,0 5.2,7"),
+    ('W', 7.6, "0.4,7 2,0 3.8,5 5.6,0 7.2,7"),
+    ('X', 5.2, "0.6,7 4.6,0 | 4.6,7 0.6,0"),
+    ('Y', 5.2, "0.5,7 2.6,3.5 4.7,7 | 2.6,3.5 2.6,0"),
+    ('Z', 5.0, "0.6,7 4.4,7 0.6,0 4.4,0"),
+    ('[', 2.6, "2.2,7.4 0.8,7.4 0.8,-0.6 2.2,-0.6"),
+    ('\\', 3.6, "0.4,7.2 3.2,-0.6"),
+    (']', 2.6, "0.4,7.4 1.8,7.4 1.8,-0.6 0.4,-0.6"),
+    ('^', 4.0, "0.6,5 2,7 3.4,5"),
+    ('_', 4.4, "0.2,-0.8 4.2,-0.8"),
+    ('`', 2.0, "0.6,7.2 1.4,6.2"),
+    ('a', 4.6, "a:2.1,2.4,1.7,2.4,0,360 | 3.8,4.8 3.8,0"),
+    ('b', 4.6, "0.8,7.4 0.8,0 | a:2.5,2.4,1.7,2.4,0,360"),
+    ('c', 4.2, "a:2.3,2.4,1.8,2.4,45,315"),
+    ('d', 4.6, "3.8,7.4 3.8,0 | a:2.1,2.4,1.7,2.4,0,360"),
+    ('e', 4.6, "0.5,2.4 3.9,2.4 a:2.2,2.4,1.7,2.4,0,320"),
+    (
+        'f',
+        3.4,
+        "3,7 a:2.2,6,0.9,1.2,45,180 1.3,0 | 0.3,4.6 2.8,4.6",
+    ),
+    (
+        'g',
+        4.6,
+        "a:2.1,2.6,1.7,2.2,0,360 | 3.8,4.8 3.8,-0.8 a:2.2,-0.8,1.6,1.4,0,-160",
+    ),
+    (
+        'h',
+        4.6,
+        "0.8,7.4 0.8,0 | 0.8,3 a:2.3,3,1.5,1.8,180,0 3.8,0",
+    ),
+    ('i', 1.8, "0.9,4.6 0.9,0 | 0.9,6.2 0.9,6.4"),
+    (
+        'j',
+        2.4,
+        "1.5,4.6 1.5,-1.2 a:0.7,-1.2,0.8,1,0,-150 | 1.5,6.2 1.5,6.4",
+    ),
+    ('k', 4.2, "0.8,7.4 0.8,0 | 3.6,4.8 0.8,1.8 | 2,3 3.8,0"),
+    ('l', 1.8, "0.9,7.4 0.9,0"),
+    (
+        'm',
+        6.6,
+        "0.8,4.8 0.8,0 | 0.8,3.3 a:2.1,3.3,1.3,1.5,180,0 3.4,0 | \
+         3.4,3.3 a:4.7,3.3,1.3,1.5,180,0 6,0",
+    ),
+    (
+        'n',
+        4.6,
+        "0.8,4.8 0.8,0 | 0.8,3 a:2.3,3,1.5,1.8,180,0 3.8,0",
+    ),
+    ('o', 4.6, "a:2.3,2.4,1.8,2.4,0,360"),
+    ('p', 4.6, "0.8,4.8 0.8,-2.2 | a:2.5,2.4,1.7,2.4,0,360"),
+    ('q', 4.6, "3.8,4.8 3.8,-2.2 | a:2.1,2.4,1.7,2.4,0,360"),
+    ('r', 3.4, "0.8,4.8 0.8,0 | 0.8,3 a:2.2,3,1.4,1.6,180,60"),
+    ('s', 4.0, "a:2,3.6,1.4,1.2,20,270 a:2,1.2,1.5,1.2,90,-160"),
+    (
+        't',
+        3.2,
+        "1.3,6.2 1.3,0.9 a:2.3,0.9,1,0.9,180,300 | 0.3,4.6 2.8,4.6",
+    ),
+    (
+        'u',
+        4.6,
+        "0.8,4.8 0.8,1.8 a:2.3,1.8,1.5,1.8,180,360 | 3.8,4.8 3.8,0",
+    ),
+    ('v', 4.4, "0.4,4.8 2.2,0 4,4.8"),
+    ('w', 6.4, "0.4,4.8 1.7,0 3.2,3.6 4.7,0 6,4.8"),
+    ('x', 4.2, "0.5,4.8 3.7,0 | 3.7,4.8 0.5,0"),
+    ('y', 4.4, "0.4,4.8 2.2,0.2 | 4,4.8 2,-1.4 1.2,-2 0.4,-2"),
+    ('z', 4.2, "0.5,4.8 3.7,4.8 0.5,0 3.7,0"),
+    (
+        '{',
+        3.0,
+        "2.6,7.4 1.8,7 1.7,4.2 0.8,3.4 1.7,2.6 1.8,-0.2 2.6,-0.6",
+    ),
+    ('|', 1.8, "0.9,7.6 0.9,-1.2"),
+    (
+        '}',
+        3.0,
+        "0.4,7.4 1.2,7 1.3,4.2 2.2,3.4 1.3,2.6 1.2,-0.2 0.4,-0.6",
+    ),
+    ('~', 4.8, "0.5,3 1.4,3.8 2.4,3.2 3.4,2.6 4.3,3.4"),
+    ('°', 2.6, "a:1.3,6,0.7,0.8,0,360"),
+    ('·', 1.6, "0.8,3 0.8,3.2"),
+    ('×', 4.4, "0.8,4.6 3.6,1.4 | 3.6,4.6 0.8,1.4"),
+    (
+        'ß',
+        4.8,
+        "0.8,0 0.8,5.6 a:2.2,5.6,1.4,1.6,180,-40 2,3.8 a:2.3,1.9,1.9,1.9,90,-150",
+    ),
+    ('ı', 1.8, "0.9,4.6 0.9,0"),
+    ('–', 4.8, "0.4,2.8 4.4,2.8"),
+    ('—', 8.0, "0.4,2.8 7.6,2.8"),
+    ('‘', 1.8, "1.2,7.2 0.7,5.8"),
+    ('’', 1.8, "1.1,7.2 0.6,5.8"),
+    ('“', 2.8, "1.2,7.2 0.7,5.8 | 2.2,7.2 1.7,5.8"),
+    ('”', 2.8, "1.1,7.2 0.6,5.8 | 2.1,7.2 1.6,5.8"),
+    ('•', 2.6, "a:1.3,3,0.3,0.3,0,360"),
+    (
+        '…',
+        5.2,
+        "0.8,0.1 0.8,0.3 | 2.6,0.1 2.6,0.3 | 4.4,0.1 4.4,0.3",
+    ),
+    (
+        '€',
+        5.4,
+        "a:3.2,3.5,2.3,3.5,50,310 | 0.3,4.2 3.4,4.2 | 0.3,2.8 3.2,2.8",
+    ),
+];
+
+/// Accents, relative to the middle of the letter just above its top.
+const GRAVE: &str = "-0.5,1.2 0.3,0.3";
+const ACUTE: &str = "-0.3,0.3 0.5,1.2";
+const CIRCUMFLEX: &str = "-0.8,0.3 0,1.1 0.8,0.3";
+const TILDE: &str = "-0.9,0.5 -0.4,1 0.2,0.6 0.9,1";
+const DIAERESIS: &str = "-0.6,0.6 -0.6,0.8 | 0.6,0.6 0.6,0.8";
+const RING: &str = "a:0,0.9,0.5,0.5,0,360";
+/// Relative to the middle of the letter at the baseline.
+const CEDILLA: &str = "0,0 0.4,-0.6 -0.3,-1.1";
+
+/// Letters drawn as another letter with an accent.
+const ACCENTED: &[(char, char, &str)] = &[
+    ('À', 'A', GRAVE),
+    ('Á', 'A', ACUTE),
+    ('Â', 'A', CIRCUMFLEX),
+    ('Ã', 'A', TILDE),
+    ('Ä', 'A', DIAERESIS),
+    ('Å', 'A', RING),
+    ('Ç', 'C', CEDILLA),
+    ('È', 'E', GRAVE),
+    ('É', 'E', ACUTE),
+    ('Ê', 'E', CIRCUMFLEX),
+    ('Ë', 'E', DIAERESIS),
+    ('Ì', 'I', GRAVE),
+    ('Í', 'I', ACUTE),
+    ('Î', 'I', CIRCUMFLEX),
+    ('Ï', 'I', DIAERESIS),
+    ('Ñ', 'N', TILDE),
+    ('Ò', 'O', GRAVE),
+    ('Ó', 'O', ACUTE),
+    ('Ô', 'O', CIRCUMFLEX),
+    ('Õ', 'O', TILDE),
+    ('Ö', 'O', DIAERESIS),
+    ('Ù', 'U', GRAVE),
+    ('Ú', 'U', ACUTE),
+    ('Û', 'U', CIRCUMFLEX),
+    ('Ü', 'U', DIAERESIS),
+    ('Ý', 'Y', ACUTE),
+    ('à', 'a', GRAVE),
+    ('á', 'a', ACUTE),
+    ('â', 'a', CIRCUMFLEX),
+    ('ã', 'a', TILDE),
+    ('ä', 'a', DIAERESIS),
+    ('å', 'a', RING),
+    ('ç', 'c', CEDILLA),
+    ('è', 'e', GRAVE),
+    ('é', 'e', ACUTE),
+    ('ê', 'e', CIRCUMFLEX),
+    ('ë', 'e', DIAERESIS),
+    ('ì', 'ı', GRAVE),
+    ('í', 'ı', ACUTE),
+    ('î', 'ı', CIRCUMFLEX),
+    ('ï', 'ı', DIAERESIS),
+    ('ñ', 'n', TILDE),
+    ('ò', 'o', GRAVE),
+    ('ó', 'o', ACUTE),
+    ('ô', 'o', CIRCUMFLEX),
+    ('õ', 'o', TILDE),
+    ('ö', 'o', DIAERESIS),
+    ('ù', 'u', GRAVE),
+    ('ú', 'u', ACUTE),
+    ('û', 'u', CIRCUMFLEX),
+    ('ü', 'u', DIAERESIS),
+    ('ý', 'y', ACUTE),
+    ('ÿ', 'y', DIAERESIS),
+];
+
+type Point = (f32, f32);
+
+/// Reads the strokes of a glyph in design units.
+fn parse(strokes: &str) -> Vec<Vec<Point>> {
+    strokes
+        .split('|')
+        .map(|stroke| {
+            let mut points = Vec::new();
+            for token in stroke.split_whitespace() {
+                let number = |s: &str| -> f32 {
+                    s.parse()
+                        .unwrap_or_else(|_| panic!("invalid number in {}", token))
+                };
+                if let Some(arc) = token.strip_prefix("a:") {
+                    let v: Vec<f32> = arc.split(',').map(number).collect();
+                    let [cx, cy, rx, ry, from, to] = v[..] else {
+                        panic!("invalid arc {}", token);
+                    };
+                    let steps = ((to - from).abs() / 15.0).ceil().max(4.0) as usize;
+                    for i in 0..=steps {
+                        let angle = (from + (to - from) * i as f32 / steps as f32) * PI / 180.0;
+                        points.push((cx + rx * angle.cos(), cy + ry * angle.sin()));
+                    }
+                } else {
+                    let (x, y) = token
+                        .split_once(',')
+                        .unwrap_or_else(|| panic!("invalid point {}", token));
+                    points.push((number(x), number(y)));
+                }
+            }
+            points
+        })
+        .filter(|points| !points.is_empty())
+        .collect()
+}
+
+fn translate(strokes: Vec<Vec<Point>>, (dx, dy): Point) -> Vec<Vec<Point>> {
+    strokes
+        .into_iter()
+        .map(|stroke| stroke.into_iter().map(|(x, y)| (x + dx, y + dy)).collect())
+        .collect()
+}
+
+/// A fixed pseudo random number in `[-1, 1]`.
+fn noise(seed: u32) -> f32 {
+    let mut x = seed.wrapping_mul(0x9e37_79b9) ^ 0x85eb_ca6b;
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2c1b_3c6d);
+    x ^= x >> 12;
+    x = x.wrapping_mul(0x297a_2d39);
+    x ^= x >> 15;
+    x as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// A contour in font units, clockwise.
+type Contour = Vec<(i16, i16)>;
+
+fn contour(points: &[Point]) -> Contour {
+    let area: f32 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    let mut contour: Contour = points
+        .iter()
+        .map(|(x, y)| ((x * UNIT).round() as i16, (y * UNIT).round() as i16))
+        .collect();
+    if area > 0.0 {
+        contour.reverse();
+    }
+    contour.dedup();
+    contour
+}
+
+/// Outlines the strokes of the character `c` with a round pen. Overlapping
+/// contours all wind the same way, so they merge when filled.
+fn outline(c: char, strokes: &[Vec<Point>]) -> Vec<Contour> {
+    let radius = PEN / 2.0;
+    let mut contours = Vec::new();
+    for (s, stroke) in strokes.iter().enumerate() {
+        let seed = (c as u32) << 12 ^ (s as u32) << 6;
+        let points: Vec<Point> = stroke
+            .iter()
+            .enumerate()
+            .map(|(i, (x, y))| {
+                let seed = seed + 2 * i as u32;
+                (x + WOBBLE * noise(seed), y + WOBBLE * noise(seed + 1))
+            })
+            .collect();
+        let pen = radius * (1.0 + 0.06 * noise(seed + 63));
+        for &(x, y) in &points {
+            let nib: Vec<Point> = (0..10)
+                .map(|i| {
+                    let angle = i as f32 * PI / 5.0;
+                    (x + pen * angle.cos(), y + pen * angle.sin())
+                })
+                .collect();
+            contours.push(contour(&nib));
+        }
+        for pair in points.windows(2) {
+            let ((ax, ay), (bx, by)) = (pair[0], pair[1]);
+            let length = ((bx - ax).powi(2) + (by - ay).powi(2)).sqrt();
+            if length < 0.01 {
+                continue;
+            }
+            let (nx, ny) = (-(by - ay) / length * pen, (bx - ax) / length * pen);
+            contours.push(contour(&[
+                (ax + nx, ay + ny),
+                (bx + nx, by + ny),
+                (bx - nx, by - ny),
+                (ax - nx, ay - ny),
+            ]));
+        }
+    }
+    contours
+}
+
+struct Glyph {
+    advance: u16,
+    contours: Vec<Contour>,
+}
+
+impl Glyph {
+    fn bounds(&self) -> (i16, i16, i16, i16) {
+        let points = self.contours.iter().flatten();
+        (
+            points.clone().map(|p| p.0).min().unwrap_or(0),
+            points.clone().map(|p| p.1).min().unwrap_or(0),
+            points.clone().map(|p| p.0).max().unwrap_or(0),
+            points.map(|p| p.1).max().unwrap_or(0),
+        )
+    }
+
+    fn points(&self) -> usize {
+        self.contours.iter().map(Vec::len).sum()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        if self.contours.is_empty() {
+            return data;
+        }
+        let (x_min, y_min, x_max, y_max) = self.bounds();
+        push_i16(&mut data, self.contours.len() as i16);
+        for value in [x_min, y_min, x_max, y_max] {
+            push_i16(&mut data, value);
+        }
+        let mut end = 0;
+        for contour in &self.contours {
+            end += contour.len();
+            push_u16(&mut data, end as u16 - 1);
+        }
+        // No hinting instructions.
+        push_u16(&mut data, 0);
+        // Every point is on the curve, coordinates are 16 bit deltas.
+        data.extend(std::iter::repeat_n(1u8, self.points()));
+        let points: Vec<(i16, i16)> = self.contours.iter().flatten().copied().collect();
+        let mut previous = 0;
+        for &(x, _) in &points {
+            push_i16(&mut data, x - previous);
+            previous = x;
+        }
+        previous = 0;
+        for &(_, y) in &points {
+            push_i16(&mut data, y - previous);
+            previous = y;
+        }
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+        data
+    }
+}
+
+fn push_u16(data: &mut Vec<u8>, value: u16) {
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_i16(data: &mut Vec<u8>, value: i16) {
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4)
+        .map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_be_bytes(word)
+        })
+        .fold(0, u32::wrapping_add)
+}
+
+/// The glyphs in font order, `.notdef` first, and the characters they draw.
+fn glyphs() -> (Vec<Glyph>, Vec<(char, u16)>) {
+    let notdef = Glyph {
+        advance: 500,
+        contours: vec![
+            vec![(100, 0), (100, 700), (400, 700), (400, 0)],
+            vec![(160, 60), (340, 60), (340, 640), (160, 640)],
+        ],
+    };
+    let mut glyphs = vec![notdef];
+    let mut characters = Vec::new();
+    let mut add = |c: char, advance: f32, strokes: Vec<Vec<Point>>| {
+        characters.push((c, glyphs.len() as u16));
+        glyphs.push(Glyph {
+            advance: (advance * UNIT).round() as u16,
+            contours: outline(c, &strokes),
+        });
+    };
+    for &(c, advance, strokes) in GLYPHS {
+        add(c, advance, parse(strokes));
+    }
+    for &(c, base, accent) in ACCENTED {
+        let &(_, advance, strokes) = GLYPHS
+            .iter()
+            .find(|(g, _, _)| *g == base)
+            .expect("accent on a missing letter");
+        let mut drawn = parse(strokes);
+        let at = if accent == CEDILLA {
+            (advance / 2.0, 0.0)
+        } else if base.is_uppercase() {
+            (advance / 2.0, CAP_HEIGHT + 0.5)
+        } else {
+            (advance / 2.0, X_HEIGHT + 0.5)
+        };
+        drawn.extend(translate(parse(accent), at));
+        add(c, advance, drawn);
+    }
+    characters.sort();
+    (glyphs, characters)
+}
+
+fn name_table() -> Vec<u8> {
+    let unique = format!("{};{}", POSTSCRIPT_NAME, VERSION);
+    let names = [
+        (0, COPYRIGHT),
+        (1, FAMILY),
+        (2, "Regular"),
+        (3, &unique),
+        (4, FAMILY),
+        (5, VERSION),
+        (6, POSTSCRIPT_NAME),
+        (13, LICENSE),
+        (14, LICENSE_URL),
+    ];
+    let mut strings = Vec::new();
+    let mut data = Vec::new();
+    push_u16(&mut data, 0);
+    push_u16(&mut data, names.len() as u16);
+    push_u16(&mut data, 6 + 12 * names.len() as u16);
+    for (id, name) in names {
+        let encoded: Vec<u8> = name.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        // Windows, Unicode BMP, US English.
+        for value in [3, 1, 0x409, id, encoded.len() as u16, strings.len() as u16] {
+            push_u16(&mut data, value);
+        }
+        strings.extend(encoded);
+    }
+    data.extend(strings);
+    data
+}
+
+fn cmap_table(characters: &[(char, u16)]) -> Vec<u8> {
+    // One segment per character and the closing segment.
+    let segments: Vec<(u16, i16)> = characters
+        .iter()
+        .map(|&(c, glyph)| (c as u16, glyph.wrapping_sub(c as u16) as i16))
+        .chain([(0xffff, 1)])
+        .collect();
+    let count = segments.len() as u16;
+    let search_range = 2 * (1 << (15 - count.leading_zeros()));
+    let mut table = Vec::new();
+    push_u16(&mut table, 4);
+    push_u16(&mut table, 16 + 8 * count);
+    push_u16(&mut table, 0);
+    push_u16(&mut table, 2 * count);
+    push_u16(&mut table, search_range);
+    push_u16(&mut table, (search_range / 2).trailing_zeros() as u16);
+    push_u16(&mut table, 2 * count - search_range);
+    for &(c, _) in &segments {
+        push_u16(&mut table, c);
+    }
+    push_u16(&mut table, 0);
+    for &(c, _) in &segments {
+        push_u16(&mut table, c);
+    }
+    for &(_, delta) in &segments {
+        push_i16(&mut table, delta);
+    }
+    for _ in &segments {
+        push_u16(&mut table, 0);
+    }
+
+    let mut data = Vec::new();
+    push_u16(&mut data, 0);
+    push_u16(&mut data, 2);
+    // Unicode BMP and Windows Unicode BMP share the subtable.
+    for (platform, encoding) in [(0, 3), (3, 1)] {
+        push_u16(&mut data, platform);
+        push_u16(&mut data, encoding);
+        push_u32(&mut data, 20);
+    }
+    data.extend(table);
+    data
+}
+
+fn font() -> Vec<u8> {
+    let (glyphs, characters) = glyphs();
+    let bounds: Vec<_> = glyphs
+        .iter()
+        .filter(|glyph| !glyph.contours.is_empty())
+        .map(Glyph::bounds)
+        .collect();
+    let x_min = bounds.iter().map(|b| b.0).min().unwrap();
+    let y_min = bounds.iter().map(|b| b.1).min().unwrap();
+    let x_max = bounds.iter().map(|b| b.2).max().unwrap();
+    let y_max = bounds.iter().map(|b| b.3).max().unwrap();
+    let advance_max = glyphs.iter().map(|glyph| glyph.advance).max().unwrap();
+    let advance_average =
+        (glyphs.iter().map(|glyph| glyph.advance as u32).sum::<u32>() / glyphs.len() as u32) as i16;
+
+    let mut glyf = Vec::new();
+    let mut loca = Vec::new();
+    let mut hmtx = Vec::new();
+    for glyph in &glyphs {
+        push_u32(&mut loca, glyf.len() as u32);
+        glyf.extend(glyph.encode());
+        push_u16(&mut hmtx, glyph.advance);
+        push_i16(&mut hmtx, glyph.bounds().0);
+    }
+    push_u32(&mut loca, glyf.len() as u32);
+
+    let mut head = Vec::new();
+    push_u32(&mut head, 0x0001_0000);
+    push_u32(&mut head, 0x0001_0000);
+    // The checksum adjustment, filled in once the font is complete.
+    push_u32(&mut head, 0);
+    push_u32(&mut head, 0x5f0f_3cf5);
+    push_u16(&mut head, 0b1011);
+    push_u16(&mut head, UNITS_PER_EM);
+    head.extend([0; 16]);
+    for value in [x_min, y_min, x_max, y_max] {
+        push_i16(&mut head, value);
+    }
+    // Regular style, smallest readable size, mixed directions, long offsets.
+    for value in [0, 8, 2, 1, 0] {
+        push_i16(&mut head, value);
+    }
+
+    let mut hhea = Vec::new();
+    push_u32(&mut hhea, 0x0001_0000);
+    for value in [ASCENDER, DESCENDER, 0] {
+        push_i16(&mut hhea, value);
+    }
+    push_u16(&mut hhea, advance_max);
+    push_i16(&mut hhea, x_min);
+    push_i16(&mut hhea, 0);
+    push_i16(&mut hhea, x_max);
+    // Upright caret, reserved fields and the metric data format.
+    for value in [1, 0, 0, 0, 0, 0, 0, 0] {
+        push_i16(&mut hhea, value);
+    }
+    push_u16(&mut hhea, glyphs.len() as u16);
+
+    let mut maxp = Vec::new();
+    push_u32(&mut maxp, 0x0001_0000);
+    push_u16(&mut maxp, glyphs.len() as u16);
+    push_u16(
+        &mut maxp,
+        glyphs.iter().map(Glyph::points).max().unwrap() as u16,
+    );
+    push_u16(
+        &mut maxp,
+        glyphs.iter().map(|g| g.contours.len()).max().unwrap() as u16,
+    );
+    // No composite glyphs and no hinting.
+    for value in [0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0] {
+        push_u16(&mut maxp, value);
+    }
+
+    let mut os2 = Vec::new();
+    push_u16(&mut os2, 4);
+    push_i16(&mut os2, advance_average);
+    // Regular weight, normal width, installable.
+    for value in [400, 5, 0] {
+        push_u16(&mut os2, value);
+    }
+    // Sub- and superscript sizes and offsets, strikeout, family class.
+    for value in [650, 600, 0, 75, 650, 600, 0, 350, 50, 260, 0] {
+        push_i16(&mut os2, value);
+    }
+    // Panose: a hand written script face.
+    os2.extend([3, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    // Basic Latin and the Latin-1 supplement.
+    for value in [0b11, 0, 0, 0] {
+        push_u32(&mut os2, value);
+    }
+    os2.extend(b"NONE");
+    push_u16(&mut os2, 0x40);
+    push_u16(&mut os2, characters.first().unwrap().0 as u16);
+    push_u16(&mut os2, characters.last().unwrap().0 as u16);
+    for value in [ASCENDER, DESCENDER, 0] {
+        push_i16(&mut os2, value);
+    }
+    push_u16(&mut os2, ASCENDER as u16);
+    push_u16(&mut os2, (-DESCENDER) as u16);
+    // Latin 1 code page.
+    push_u32(&mut os2, 1);
+    push_u32(&mut os2, 0);
+    push_i16(&mut os2, (X_HEIGHT * UNIT) as i16);
+    push_i16(&mut os2, (CAP_HEIGHT * UNIT) as i16);
+    for value in [0, ' ' as u16, 1] {
+        push_u16(&mut os2, value);
+    }
+
+    let mut post = Vec::new();
+    // Version 3 has no glyph names.
+    push_u32(&mut post, 0x0003_0000);
+    push_u32(&mut post, 0);
+    push_i16(&mut post, -120);
+    push_i16(&mut post, 60);
+    post.extend([0; 20]);
+
+    let mut tables: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"OS/2", os2),
+        (b"cmap", cmap_table(&characters)),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+        (b"name", name_table()),
+        (b"post", post),
+    ];
+    tables.sort_by_key(|(tag, _)| **tag);
+
+    let count = tables.len() as u16;
+    let search_range = 16 * (1 << (15 - count.leading_zeros()));
+    let mut font = Vec::new();
+    push_u32(&mut font, 0x0001_0000);
+    push_u16(&mut font, count);
+    push_u16(&mut font, search_range);
+    push_u16(&mut font, (search_range / 16).trailing_zeros() as u16);
+    push_u16(&mut font, 16 * count - search_range);
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = 0;
+    for (tag, data) in &tables {
+        if *tag == b"head" {
+            head_offset = offset;
+        }
+        font.extend(*tag);
+        push_u32(&mut font, checksum(data));
+        push_u32(&mut font, offset as u32);
+        push_u32(&mut font, data.len() as u32);
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &tables {
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    let adjustment = 0xb1b0_afbau32.wrapping_sub(checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
+
+fn main() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/CommitcanvasHand-Regular.ttf");
+    let font = font();
+    std::fs::write(&path, &font).expect("failed to write the font");
+    println!("wrote {} bytes to {}", font.len(), path.display());
+}
@@ -0,0 +1,157 @@
+//! Rasterizes drawings to PNG without a browser.
+//!
+//! The SVG written by [`infrastructure_svg`] is rendered on the CPU, so a PNG
+//! shows the same rough strokes, pencil texture and fills as the SVG. Text is
+//! set in [`HAND_FONT`], a hand-drawn font bundled with the crate, or in the
+//! first of [`PngOptions::fonts`] when given. Outside of browsers, the fonts of
+//! the system are used for anything these fonts don't cover.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use commitcanvas::model::Document;
+use infrastructure_svg::RenderError;
+use resvg::tiny_skia::{Color, Pixmap, Transform};
+use resvg::usvg;
+
+/// Resolution of the drawing at scale 1, the CSS pixel.
+pub const BASE_DPI: u32 = 96;
+
+/// The hand-drawn font text is set in by default, Commitcanvas Hand (SIL Open
+/// Font License 1.1, see `fonts/OFL.txt`).
+pub const HAND_FONT: &[u8] = include_bytes!("../fonts/CommitcanvasHand-Regular.ttf");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PngOptions {
+    /// Image pixels per pixel of the drawing.
+    pub scale: f32,
+    /// Resolution stored in the image, for programs that size images by
+    /// their physical size. `BASE_DPI * scale` keeps the size of the drawing.
+    pub dpi: u32,
+    /// Leaves the background transparent instead of white.
+    pub transparent: bool,
+    /// Font files (TTF, OTF) to set text in instead of [`HAND_FONT`].
+    pub fonts: Vec<Vec<u8>>,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            dpi: BASE_DPI,
+            transparent: false,
+            fonts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PngError {
+    Render(RenderError),
+    /// The SVG couldn't be read back, which points to a bug in the SVG view.
+    Svg(usvg::Error),
+    /// The image would be empty or too large at the chosen scale.
+    Size {
+        width: f32,
+        height: f32,
+    },
+    Encode(png::EncodingError),
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PngError::Render(e) => write!(f, "{}", e),
+            PngError::Svg(e) => write!(f, "failed to read the drawing: {}", e),
+            PngError::Size { width, height } => {
+                write!(f, "can't create an image of {}x{} pixels", width, height)
+            }
+            PngError::Encode(e) => write!(f, "failed to encode PNG: {}", e),
+        }
+    }
+}
+
+impl Error for PngError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PngError::Render(e) => Some(e),
+            PngError::Svg(e) => Some(e),
+            PngError::Size { .. } => None,
+            PngError::Encode(e) => Some(e),
+        }
+    }
+}
+
+fn font_database(fonts: &[Vec<u8>]) -> usvg::fontdb::Database {
+    let mut database = usvg::fontdb::Database::new();
+    for font in fonts {
+        database.load_font_data(font.clone());
+    }
+    database.load_font_data(HAND_FONT.to_vec());
+    // Text asks for the hand-drawn font by name, or any cursive one.
+    let first = database
+        .faces()
+        .next()
+        .and_then(|face| face.families.first())
+        .map(|(family, _)| family.clone());
+    #[cfg(not(target_arch = "wasm32"))]
+    database.load_system_fonts();
+    if let Some(family) = first {
+        log::debug!("setting text in {}", family);
+        database.set_cursive_family(family);
+    }
+    database
+}
+
+/// Rasterizes an SVG file.
+pub fn svg_to_png(svg: &str, options: &PngOptions) -> Result<Vec<u8>, PngError> {
+    let usvg_options = usvg::Options {
+        fontdb: Arc::new(font_database(&options.fonts)),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &usvg_options).map_err(PngError::Svg)?;
+    let (width, height) = (
+        (tree.size().width() * options.scale).ceil(),
+        (tree.size().height() * options.scale).ceil(),
+    );
+    let mut pixmap =
+        Pixmap::new(width as u32, height as u32).ok_or(PngError::Size { width, height })?;
+    if !options.transparent {
+        pixmap.fill(Color::WHITE);
+    }
+    resvg::render(
+        &tree,
+        Transform::from_scale(options.scale, options.scale),
+        &mut pixmap.as_mut(),
+    );
+
+    let mut image = Vec::new();
+    let mut encoder = png::Encoder::new(&mut image, pixmap.width(), pixmap.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let pixels_per_meter = (options.dpi as f32 / 0.0254).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: pixels_per_meter,
+        yppu: pixels_per_meter,
+        unit: png::Unit::Meter,
+    }));
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    let mut writer = encoder.write_header().map_err(PngError::Encode)?;
+    writer.write_image_data(&data).map_err(PngError::Encode)?;
+    writer.finish().map_err(PngError::Encode)?;
+    Ok(image)
+}
+
+/// Draws `document` as a PNG image fitting its shapes.
+pub fn render(document: &Document, options: &PngOptions) -> Result<Vec<u8>, PngError> {
+    let svg = infrastructure_svg::render(document).map_err(PngError::Render)?;
+    svg_to_png(&svg, options)
+}
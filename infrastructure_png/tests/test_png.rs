@@ -0,0 +1,136 @@
+use commitcanvas::gitgraph;
+use commitcanvas::model::Model;
+use commitcanvas::types::PointGrid;
+use infrastructure_png::{render, svg_to_png, PngError, PngOptions, HAND_FONT};
+
+fn drawing() -> Model {
+    let mut model = Model::new();
+    gitgraph::draw(
+        "commit msg: first\nbranch develop\ncommit type: highlight\ncheckout main\nmerge develop",
+        &mut model,
+        PointGrid { x: 0, y: 0 },
+    )
+    .unwrap();
+    model
+}
+
+struct Image {
+    width: u32,
+    height: u32,
+    dpi: u32,
+    pixels: Vec<u8>,
+}
+
+fn decode(png: &[u8]) -> Image {
+    let decoder = png::Decoder::new(png);
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut pixels).unwrap();
+    let info = reader.info();
+    let dims = info.pixel_dims.unwrap();
+    assert_eq!(dims.unit, png::Unit::Meter);
+    Image {
+        width: info.width,
+        height: info.height,
+        dpi: (dims.xppu as f32 * 0.0254).round() as u32,
+        pixels,
+    }
+}
+
+#[test]
+fn test_render() {
+    let document = drawing().to_document();
+    let svg = infrastructure_svg::render(&document).unwrap();
+    let view_box: Vec<f32> = svg
+        .split("viewBox=\"")
+        .nth(1)
+        .unwrap()
+        .split('"')
+        .next()
+        .unwrap()
+        .split(' ')
+        .map(|n| n.parse().unwrap())
+        .collect();
+
+    let image = decode(&render(&document, &PngOptions::default()).unwrap());
+    assert_eq!(
+        (image.width, image.height),
+        (view_box[2] as u32, view_box[3] as u32)
+    );
+    assert_eq!(image.dpi, 96);
+    // An opaque white background with something drawn on it.
+    assert!(image.pixels.chunks(4).all(|pixel| pixel[3] == 255));
+    assert!(image.pixels.chunks(4).any(|pixel| pixel[..3] != [255; 3]));
+}
+
+#[test]
+fn test_scale_dpi_and_transparency() {
+    let document = drawing().to_document();
+    let small = decode(&render(&document, &PngOptions::default()).unwrap());
+    let options = PngOptions {
+        scale: 2.0,
+        dpi: 192,
+        transparent: true,
+        ..Default::default()
+    };
+    let large = decode(&render(&document, &options).unwrap());
+    assert_eq!(
+        (large.width, large.height),
+        (2 * small.width, 2 * small.height)
+    );
+    assert_eq!(large.dpi, 192);
+    assert_eq!(large.pixels[..4], [0, 0, 0, 0]);
+    assert!(large.pixels.chunks(4).any(|pixel| pixel[3] != 0));
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        render(&Model::new().to_document(), &PngOptions::default()),
+        Err(PngError::Render(_))
+    ));
+    assert!(matches!(
+        svg_to_png("<svg", &PngOptions::default()),
+        Err(PngError::Svg(_))
+    ));
+    let options = PngOptions {
+        scale: 0.0,
+        ..Default::default()
+    };
+    assert!(matches!(
+        render(&drawing().to_document(), &options),
+        Err(PngError::Size { .. })
+    ));
+}
+
+#[test]
+fn test_bundled_font() {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="120" height="40">
+        <text x="4" y="30" font-size="24" font-family="'Patrick Hand', cursive">Hello</text>
+    </svg>"#;
+    let blank = r#"<svg xmlns="http://www.w3.org/2000/svg" width="120" height="40"/>"#;
+    let bundled = svg_to_png(svg, &PngOptions::default()).unwrap();
+    assert_ne!(
+        decode(&bundled).pixels,
+        decode(&svg_to_png(blank, &PngOptions::default()).unwrap()).pixels
+    );
+    let options = PngOptions {
+        fonts: vec![HAND_FONT.to_vec()],
+        ..Default::default()
+    };
+    assert_eq!(bundled, svg_to_png(svg, &options).unwrap());
+}
+
+#[test]
+#[ignore = "writes an image for a look"]
+fn write_sample() {
+    let options = PngOptions {
+        scale: 2.0,
+        fonts: std::env::var("FONT")
+            .map(|path| vec![std::fs::read(path).unwrap()])
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let png = render(&drawing().to_document(), &options).unwrap();
+    std::fs::write(std::env::temp_dir().join("commitcanvas_sample.png"), png).unwrap();
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+/// The namespace of every element.
+pub const NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// An SVG element, detached from any document.
+///
+/// Attributes keep the order they were added in, and elements print the way
+/// a browser serializes them, so an element built into a page and the same
+/// element written to a file read the same.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Element {
+    pub name: &'static str,
+    pub attributes: Vec<(&'static str, String)>,
+    pub children: Vec<Element>,
+    /// Text content, elements have either text or children.
+    pub text: Option<String>,
+}
+
+impl Element {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+
+    pub fn attribute(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.attributes.push((name, value.to_string()));
+        self
+    }
+
+    pub fn child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn text(mut self, text: impl ToString) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    /// The value of the attribute `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}", self.name)?;
+        for (name, value) in &self.attributes {
+            write!(f, " {}=\"{}\"", name, escape(value, true))?;
+        }
+        write!(f, ">")?;
+        if let Some(text) = &self.text {
+            write!(f, "{}", escape(text, false))?;
+        }
+        for child in &self.children {
+            write!(f, "{}", child)?;
+        }
+        write!(f, "</{}>", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let element = Element::new("g")
+            .attribute("id", "a\"b")
+            .child(Element::new("tspan").attribute("x", 1.5).text("1 < 2 & 3"))
+            .child(Element::new("path"));
+        assert_eq!(
+            element.to_string(),
+            "<g id=\"a&quot;b\"><tspan x=\"1.5\">1 &lt; 2 &amp; 3</tspan><path></path></g>"
+        );
+        assert_eq!(element.get("id"), Some("a\"b"));
+        assert_eq!(element.get("class"), None);
+    }
+
+    #[test]
+    fn test_escaped_text_is_xml() {
+        let text = "a\u{a0}b & <c> \"d\"";
+        let element = Element::new("text")
+            .attribute("data-content", text)
+            .child(Element::new("tspan").text(text));
+        let svg = element.to_string();
+        let document = roxmltree::Document::parse(&svg).expect("invalid XML");
+        let root = document.root_element();
+        assert_eq!(root.attribute("data-content"), Some(text));
+        assert_eq!(
+            root.first_child().and_then(|tspan| tspan.text()),
+            Some(text)
+        );
+    }
+}
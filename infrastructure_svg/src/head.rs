@@ -0,0 +1,118 @@
+//! Arrowheads, drawn with rough strokes like the line they end.
+
+use commitcanvas::model::Head;
+use rough::{ellipse_to_path, polygon_fill_path, polyline_to_path, Fill, Path};
+
+/// The strokes of an arrowhead and the area it fills.
+pub struct DrawnHead {
+    pub outline: Path,
+    pub fill: Path,
+    /// How far the line stops short of the tip, so it doesn't run through a
+    /// hollow head.
+    pub inset: f32,
+}
+
+/// Draws `head` with its tip at `tip`, pointing away from `from`, for a line
+/// `width` pixels wide. The head grows with the width.
+pub fn head(
+    head: Head,
+    tip: (f32, f32),
+    from: (f32, f32),
+    width: f32,
+    roughness: f32,
+    seed: u64,
+) -> DrawnHead {
+    let size = 6.0 + 4.0 * width;
+    let (dx, dy) = (tip.0 - from.0, tip.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    let (ux, uy) = if length > 0.0 {
+        (dx / length, dy / length)
+    } else {
+        (1.0, 0.0)
+    };
+    // `along` back from the tip towards the line, `across` to its side.
+    let at = |along: f32, across: f32| {
+        (
+            tip.0 - along * ux - across * uy,
+            tip.1 - along * uy + across * ux,
+        )
+    };
+    let strokes = |points: &[(f32, f32)]| polyline_to_path(points, roughness, 2, 2.0, seed);
+
+    let (outline, fill, inset) = match head {
+        Head::None => (Path::new(), Path::new(), 0.0),
+        Head::Open => (
+            strokes(&[at(size, size / 2.0), tip, at(size, -size / 2.0)]),
+            Path::new(),
+            0.0,
+        ),
+        Head::Triangle => {
+            let corners = [tip, at(size, size / 2.0), at(size, -size / 2.0)];
+            (
+                strokes(&[corners[0], corners[1], corners[2], corners[0]]),
+                polygon_fill_path(&corners, Fill::Solid, roughness, seed),
+                // Inside the fill, so the end of the line never shows.
+                size / 2.0,
+            )
+        }
+        Head::Circle => {
+            let radius = size / 3.0;
+            (
+                ellipse_to_path(at(radius, 0.0), (radius, radius), roughness, seed),
+                Path::new(),
+                2.0 * radius,
+            )
+        }
+        Head::Diamond => {
+            let corners = [
+                tip,
+                at(size / 2.0, size / 3.0),
+                at(size, 0.0),
+                at(size / 2.0, -size / 3.0),
+            ];
+            (
+                strokes(&[corners[0], corners[1], corners[2], corners[3], corners[0]]),
+                Path::new(),
+                size,
+            )
+        }
+        Head::Bar => (
+            strokes(&[at(0.0, size / 2.0), at(0.0, -size / 2.0)]),
+            Path::new(),
+            0.0,
+        ),
+    };
+    DrawnHead {
+        outline,
+        fill,
+        inset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heads_grow_with_width() {
+        let extent = |width| {
+            let drawn = head(Head::Open, (100.0, 0.0), (0.0, 0.0), width, 0.0, 1);
+            let ys: Vec<f32> = drawn.outline.end_points().map(|(_, y)| y).collect();
+            ys.iter().cloned().fold(0.0, f32::max) - ys.iter().cloned().fold(0.0, f32::min)
+        };
+        assert!(extent(3.0) > extent(1.0));
+    }
+
+    #[test]
+    fn test_insets() {
+        let inset = |kind| head(kind, (0.0, 0.0), (0.0, 50.0), 1.0, 0.4, 1).inset;
+        assert_eq!(inset(Head::Open), 0.0);
+        assert_eq!(inset(Head::Bar), 0.0);
+        assert!(inset(Head::Diamond) > inset(Head::Circle));
+        let triangle = head(Head::Triangle, (0.0, 0.0), (0.0, 50.0), 1.0, 0.4, 1);
+        assert!(!triangle.fill.is_empty());
+        assert!(head(Head::None, (0.0, 0.0), (0.0, 50.0), 1.0, 0.4, 1)
+            .outline
+            .is_empty());
+    }
+}
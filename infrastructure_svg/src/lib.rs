@@ -0,0 +1,71 @@
+//! Draws models as SVG without a browser.
+//!
+//! [`shape`] builds the elements of a shape: the same `rough` strokes and
+//! arrowheads, pencil filter and color classes the browser draws, which
+//! builds its page from the very same elements. [`SvgView`] keeps these
+//! elements up to date as a [`View`](commitcanvas::view::View) of a model
+//! and writes them as a standalone file, with the filter and a stylesheet
+//! with the color palette embedded. [`render`] draws a saved document in one go.
+
+use std::error::Error;
+use std::fmt;
+
+use commitcanvas::model::{Document, DocumentError};
+use commitcanvas::view::{Event, View};
+
+mod element;
+mod head;
+mod shapes;
+mod style;
+mod view;
+
+pub use element::{Element, NAMESPACE};
+pub use shapes::{identifier, shape};
+pub use view::SvgView;
+
+#[derive(Debug)]
+pub enum RenderError {
+    /// The document can't be loaded into a model.
+    Document(DocumentError),
+    /// The document has no shapes.
+    Empty,
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Document(e) => write!(f, "invalid document: {}", e),
+            RenderError::Empty => write!(f, "the document has no shapes to draw"),
+        }
+    }
+}
+
+impl Error for RenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RenderError::Document(e) => Some(e),
+            RenderError::Empty => None,
+        }
+    }
+}
+
+/// Draws `document` as an SVG file whose view box fits its shapes, text laid
+/// out with an [`ApproximateMeasure`](commitcanvas::text::ApproximateMeasure).
+pub fn render(document: &Document) -> Result<String, RenderError> {
+    document.validate().map_err(RenderError::Document)?;
+    if document.shapes.is_empty() {
+        return Err(RenderError::Empty);
+    }
+    let mut view = SvgView::new();
+    // Drawing into memory can't fail.
+    let _ = view.process_event(Event::Reload {
+        shapes: document
+            .shapes
+            .iter()
+            .map(|shape| (&shape.guid, &shape.config))
+            .collect(),
+        settings: &document.settings,
+    });
+    log::debug!("rendered {} shapes", document.shapes.len());
+    Ok(view.svg())
+}
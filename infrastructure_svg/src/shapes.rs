@@ -0,0 +1,256 @@
+use commitcanvas::model::{
+    ArrowDetails, Color, Dash, FillStyle, Guid, Options, Routing, ShapeConfig, ShapeDetails,
+    TextAlign, TextDetails,
+};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::text::{TextLayout, TextMeasure};
+use commitcanvas::utils::coords_to_pixels;
+use rough::{
+    curve_to_path, ellipse_fill_path, ellipse_to_path, polyline_to_path, rect_fill_path,
+    rect_to_path, Fill, Path,
+};
+
+use crate::element::Element;
+use crate::head::head;
+
+/// The id of the group drawing the shape `guid`.
+pub fn identifier(guid: Guid) -> String {
+    format!("cc_id_{}", guid)
+}
+
+/// The rough fill drawing `style`, lines slanted like a right-handed hatch.
+fn fill(style: FillStyle) -> Fill {
+    const ANGLE: f32 = -0.7;
+    const GAP: f32 = 6.0;
+    match style {
+        FillStyle::Solid => Fill::Solid,
+        FillStyle::Hachure => Fill::Hachure {
+            angle: ANGLE,
+            gap: GAP,
+        },
+        FillStyle::CrossHatch => Fill::CrossHatch {
+            angle: ANGLE,
+            gap: GAP,
+        },
+        FillStyle::ZigZag => Fill::ZigZag {
+            angle: ANGLE,
+            gap: GAP,
+        },
+        FillStyle::Dots => Fill::Dots { gap: GAP },
+    }
+}
+
+/// The outline `path` of a shape with the class `class`, cut into the dashes
+/// or dots of its options. They are as long as the stroke is wide, so they
+/// grow with the thickness like the stroke.
+fn outline(path: Path, class: &str, options: &Options) -> Element {
+    let width: f32 = (&options.thickness).into();
+    let path = match options.dash {
+        Dash::Solid => path,
+        Dash::Dashed => path.dashed(4.0 + 4.0 * width, 4.0 + 2.0 * width),
+        Dash::Dotted => path.dashed(1.5 * width, 2.0 + 2.0 * width),
+    };
+    Element::new("path")
+        .attribute("d", path.to_svg())
+        .attribute("class", class)
+        .attribute("filter", "url(#cc_pencil_texture_4)")
+        .attribute("stroke-width", <&str>::from(&options.thickness))
+}
+
+/// Moves `point` towards `towards` by `distance`, at most halfway.
+fn pull_back(point: (f32, f32), towards: (f32, f32), distance: f32) -> (f32, f32) {
+    let length = ((towards.0 - point.0).powi(2) + (towards.1 - point.1).powi(2)).sqrt();
+    if length == 0.0 {
+        return point;
+    }
+    let t = distance.min(length / 2.0) / length;
+    (
+        point.0 + t * (towards.0 - point.0),
+        point.1 + t * (towards.1 - point.1),
+    )
+}
+
+/// The line of an arrow, the outlines of its heads and the areas they fill.
+fn arrow(config: &ShapeConfig, details: &ArrowDetails, grid: &GridConfig) -> [Element; 3] {
+    let mut points: Vec<(f32, f32)> = config
+        .points()
+        .into_iter()
+        .map(|p| coords_to_pixels(p, grid).into())
+        .collect();
+    let roughness = (&config.options.roughness).into();
+    let width: f32 = (&config.options.thickness).into();
+    let seed: u64 = config.seed.into();
+    let last = points.len() - 1;
+    // Each head points along the segment it ends, with strokes of its own.
+    let heads = [
+        head(
+            details.start_head,
+            points[0],
+            points[1],
+            width,
+            roughness,
+            seed + 1,
+        ),
+        head(
+            details.end_head,
+            points[last],
+            points[last - 1],
+            width,
+            roughness,
+            seed + 2,
+        ),
+    ];
+    points[0] = pull_back(points[0], points[1], heads[0].inset);
+    points[last] = pull_back(points[last], points[last - 1], heads[1].inset);
+
+    // A curve needs a waypoint to bend at, without one it is a straight line.
+    let path = match details.routing {
+        Routing::Curved if !details.waypoints.is_empty() => curve_to_path(&points, roughness, seed),
+        _ => polyline_to_path(&points, roughness, 2, 2.0, seed),
+    };
+    let (mut head_outline, mut head_fill) = (Path::new(), Path::new());
+    for drawn in heads {
+        head_outline.append(drawn.outline);
+        head_fill.append(drawn.fill);
+    }
+    [
+        outline(path, "cc_arrow", &config.options),
+        Element::new("path")
+            .attribute("d", head_outline.to_svg())
+            .attribute("class", "cc_arrow_head")
+            .attribute("filter", "url(#cc_pencil_texture_4)")
+            .attribute("stroke-width", <&str>::from(&config.options.thickness)),
+        Element::new("path")
+            .attribute("d", head_fill.to_svg())
+            .attribute("class", "cc_arrow_head_fill"),
+    ]
+}
+
+fn rect(
+    config: &ShapeConfig,
+    background: Color,
+    style: FillStyle,
+    grid: &GridConfig,
+) -> [Element; 2] {
+    let start = coords_to_pixels(config.start, grid);
+    let end = coords_to_pixels(config.end, grid);
+    let roughness = (&config.options.roughness).into();
+    let path = rect_to_path(
+        start.into(),
+        end.into(),
+        roughness,
+        grid.spacing,
+        config.seed.into(),
+    );
+    let fill_path = rect_fill_path(
+        start.into(),
+        end.into(),
+        grid.spacing,
+        fill(style),
+        roughness,
+        config.seed.into(),
+    );
+    [
+        outline(path, "cc_rect", &config.options),
+        Element::new("path")
+            .attribute("d", fill_path.to_svg())
+            .attribute(
+                "class",
+                format!(
+                    "cc_rect_fill {} {}",
+                    style.class(),
+                    <&str>::from(&background)
+                ),
+            ),
+    ]
+}
+
+fn ellipse(
+    config: &ShapeConfig,
+    background: Color,
+    style: FillStyle,
+    grid: &GridConfig,
+) -> [Element; 2] {
+    let start = coords_to_pixels(config.start, grid);
+    let end = coords_to_pixels(config.end, grid);
+    let center = ((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+    let radii = ((end.x - start.x).abs() / 2.0, (end.y - start.y).abs() / 2.0);
+    let roughness = (&config.options.roughness).into();
+    let path = ellipse_to_path(center, radii, roughness, config.seed.into());
+    let fill_path = ellipse_fill_path(center, radii, fill(style), roughness, config.seed.into());
+    [
+        outline(path, "cc_ellipse", &config.options),
+        Element::new("path")
+            .attribute("d", fill_path.to_svg())
+            .attribute(
+                "class",
+                format!(
+                    "cc_ellipse_fill {} {}",
+                    style.class(),
+                    <&str>::from(&background)
+                ),
+            ),
+    ]
+}
+
+fn text(
+    config: &ShapeConfig,
+    details: &TextDetails,
+    grid: &GridConfig,
+    measure: &dyn TextMeasure,
+) -> Element {
+    let layout = TextLayout::new(
+        details,
+        coords_to_pixels(config.start, grid),
+        coords_to_pixels(config.end, grid),
+        measure,
+    );
+    let anchor = match details.align {
+        TextAlign::Left => "start",
+        TextAlign::Center => "middle",
+        TextAlign::Right => "end",
+    };
+    let mut text = Element::new("text")
+        .attribute("class", "cc_text")
+        .attribute("dominant-baseline", "hanging")
+        .attribute("font-size", <&str>::from(&details.font_size))
+        .attribute("text-anchor", anchor);
+    for line in layout.lines {
+        text = text.child(
+            Element::new("tspan")
+                .attribute("x", line.position.x)
+                .attribute("y", line.position.y)
+                .text(line.text),
+        );
+    }
+    text
+}
+
+/// The group drawing a shape: an arrow is a path for its line, one for the
+/// outlines of its heads and one for what they fill, a rect or an ellipse an
+/// outline path and a fill path, a text a `text` with one `tspan` per line.
+pub fn shape(
+    guid: Guid,
+    config: &ShapeConfig,
+    grid: &GridConfig,
+    measure: &dyn TextMeasure,
+) -> Element {
+    let group = Element::new("g")
+        .attribute("id", identifier(guid))
+        .attribute("class", config.options.stroke_color.stroke_class());
+    match &config.details {
+        ShapeDetails::Arrow(details) => {
+            let [line, head, head_fill] = arrow(config, details, grid);
+            group.child(line).child(head).child(head_fill)
+        }
+        ShapeDetails::Rect(details) => {
+            let [outline, fill] = rect(config, details.background, details.fill, grid);
+            group.child(outline).child(fill)
+        }
+        ShapeDetails::Ellipse(details) => {
+            let [outline, fill] = ellipse(config, details.background, details.fill, grid);
+            group.child(outline).child(fill)
+        }
+        ShapeDetails::Text(details) => group.child(text(config, details, grid, measure)),
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt::Write;
+
+use commitcanvas::model::Color;
+
+/// Turbulence filter giving strokes their pencil texture, shared with the
+/// browser.
+const PENCIL_FILTER: &str = include_str!("../../www/html/pencil_filter_4.html");
+
+/// Outlines and text without a color of their own, stone-800.
+const DEFAULT_STROKE: &str = "#292524";
+
+/// The tailwind colors the browser fills (300) and strokes (500) with.
+fn palette(color: Color) -> Option<(&'static str, &'static str)> {
+    match color {
+        Color::None => None,
+        Color::Red => Some(("#fca5a5", "#ef4444")),
+        Color::Organge => Some(("#fdba74", "#f97316")),
+        Color::Amber => Some(("#fcd34d", "#f59e0b")),
+        Color::Yellow => Some(("#fde047", "#eab308")),
+        Color::Lime => Some(("#bef264", "#84cc16")),
+        Color::Green => Some(("#86efac", "#22c55e")),
+        Color::Emerald => Some(("#6ee7b7", "#10b981")),
+        Color::Teal => Some(("#5eead4", "#14b8a6")),
+        Color::Cyan => Some(("#67e8f9", "#06b6d4")),
+        Color::Sky => Some(("#7dd3fc", "#0ea5e9")),
+        Color::Blue => Some(("#93c5fd", "#3b82f6")),
+        Color::Indigo => Some(("#a5b4fc", "#6366f1")),
+        Color::Purple => Some(("#d8b4fe", "#a855f7")),
+        Color::Fuchsia => Some(("#f0abfc", "#d946ef")),
+        Color::Pink => Some(("#f9a8d4", "#ec4899")),
+        Color::Rose => Some(("#fda4af", "#f43f5e")),
+    }
+}
+
+/// The classes of `www/css/input.css` shapes are drawn with, in plain CSS.
+fn stylesheet() -> String {
+    let mut css = String::new();
+    css.push_str(
+        "@import url('https://fonts.googleapis.com/css2?family=Patrick+Hand&display=swap');\n",
+    );
+    css.push_str(
+        ".cc_arrow, .cc_arrow_head, .cc_rect, .cc_ellipse { stroke: currentColor; fill: transparent; }\n",
+    );
+    css.push_str(".cc_arrow_head_fill { stroke: none; fill: currentColor; }\n");
+    css.push_str(".cc_rect_fill, .cc_ellipse_fill { fill: none; stroke: none; }\n");
+    css.push_str(
+        ".cc_text { stroke: none; fill: currentColor; font-family: 'Patrick Hand', cursive; }\n",
+    );
+    css.push_str(".cc_fill_none { fill: none; }\n");
+    for color in Color::ALL {
+        let class: &str = (&color).into();
+        match palette(color) {
+            Some((fill, stroke)) => {
+                let _ = writeln!(
+                    css,
+                    ".{} {{ fill: {}; stroke: {}; opacity: 0.25; }}",
+                    class, fill, fill
+                );
+                let _ = writeln!(css, ".{} {{ color: {}; }}", color.stroke_class(), stroke);
+            }
+            None => {
+                let _ = writeln!(
+                    css,
+                    ".{} {{ color: {}; }}",
+                    color.stroke_class(),
+                    DEFAULT_STROKE
+                );
+            }
+        }
+    }
+    // After the colors, which they partly undo.
+    css.push_str(".cc_fill_style_solid { stroke: none; }\n");
+    css.push_str(
+        ".cc_fill_style_hachure, .cc_fill_style_cross_hatch, .cc_fill_style_zigzag { fill: none; opacity: 1; stroke-width: 1; }\n",
+    );
+    css.push_str(".cc_fill_style_dots { opacity: 1; stroke-width: 1; }\n");
+    css
+}
+
+/// The stylesheet and pencil filter shapes refer to, laid out like the page
+/// of the browser.
+pub fn defs() -> String {
+    format!(
+        "<style><![CDATA[\n{}]]></style>\n<filter filterUnits=\"objectBoundingBox\" id=\"cc_pencil_texture_4\">{}</filter>\n",
+        stylesheet(),
+        PENCIL_FILTER
+    )
+}
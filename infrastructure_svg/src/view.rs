@@ -0,0 +1,184 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+use commitcanvas::model::{EventHistory, Guid, ShapeConfig};
+use commitcanvas::settings::GridConfig;
+use commitcanvas::text::{ApproximateMeasure, TextMeasure};
+use commitcanvas::types::PointPixel;
+use commitcanvas::utils::coords_to_pixels;
+use commitcanvas::view::{Event, View};
+
+use crate::element::{Element, NAMESPACE};
+use crate::shapes::shape;
+use crate::style;
+
+/// Grid steps of empty space around the drawing.
+const PADDING: f32 = 2.0;
+
+struct State {
+    /// Guids in drawing order, later shapes on top.
+    order: Vec<Guid>,
+    items: HashMap<Guid, (ShapeConfig, Element)>,
+    grid: GridConfig,
+    measure: Box<dyn TextMeasure>,
+}
+
+impl State {
+    fn draw(&mut self, guid: Guid, config: &ShapeConfig) {
+        let element = shape(guid, config, &self.grid, self.measure.as_ref());
+        if self.items.insert(guid, (config.clone(), element)).is_none() {
+            self.order.push(guid);
+        }
+    }
+}
+
+/// Keeps an SVG drawing of a model, element for element what the browser
+/// shows minus the invisible selection targets.
+///
+/// Clones share the drawing, so one can be handed to the model while the
+/// other reads the result:
+///
+/// ```
+/// # use commitcanvas::model::Model;
+/// # use infrastructure_svg::SvgView;
+/// let view = SvgView::new();
+/// let mut model = Model::new();
+/// model.add_view(Box::new(view.clone()));
+/// let svg = view.svg();
+/// ```
+#[derive(Clone)]
+pub struct SvgView {
+    state: Rc<RefCell<State>>,
+}
+
+impl Default for SvgView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgView {
+    /// A view laying out text with an [`ApproximateMeasure`].
+    pub fn new() -> Self {
+        Self::with_measure(Box::new(ApproximateMeasure::default()))
+    }
+
+    pub fn with_measure(measure: Box<dyn TextMeasure>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(State {
+                order: Vec::new(),
+                items: HashMap::new(),
+                grid: GridConfig::default(),
+                measure,
+            })),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.borrow().order.is_empty()
+    }
+
+    /// The group drawing the shape `guid`.
+    pub fn element(&self, guid: Guid) -> Option<Element> {
+        let state = self.state.borrow();
+        state.items.get(&guid).map(|(_, element)| element.clone())
+    }
+
+    /// The pixel corners of the area all shapes lie in, padded.
+    fn bounds(state: &State) -> (PointPixel, PointPixel) {
+        let grid = &state.grid;
+        let mut points = state.items.values().flat_map(|(config, _)| {
+            config
+                .points()
+                .into_iter()
+                .map(|p| coords_to_pixels(p, grid))
+        });
+        let first = points.next().unwrap_or(grid.origin);
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (
+                PointPixel {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                PointPixel {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        });
+        let padding = PADDING * grid.spacing;
+        (
+            PointPixel {
+                x: min.x - padding,
+                y: min.y - padding,
+            },
+            PointPixel {
+                x: max.x + padding,
+                y: max.y + padding,
+            },
+        )
+    }
+
+    /// The drawing as a standalone SVG file whose view box fits the shapes.
+    pub fn svg(&self) -> String {
+        let state = self.state.borrow();
+        let (min, max) = Self::bounds(&state);
+        let (width, height) = (max.x - min.x, max.y - min.y);
+        let mut svg = format!(
+            "<svg xmlns=\"{}\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">\n",
+            NAMESPACE, min.x, min.y, width, height, width, height
+        );
+        svg.push_str(&style::defs());
+        svg.push_str("<g id=\"cc_group_view\">\n");
+        for guid in &state.order {
+            if let Some((_, element)) = state.items.get(guid) {
+                svg.push_str(&element.to_string());
+                svg.push('\n');
+            }
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+impl View for SvgView {
+    fn process_event(&mut self, event: Event) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut state = self.state.borrow_mut();
+        match event {
+            Event::Reload {
+                mut shapes,
+                settings,
+            } => {
+                state.order.clear();
+                state.items.clear();
+                state.grid = settings.grid;
+                shapes.sort_by_key(|(guid, _)| **guid);
+                for (guid, config) in shapes {
+                    state.draw(*guid, config);
+                }
+            }
+            Event::Modify { event } => match event {
+                EventHistory::Add { guid, config } => state.draw(guid, &config),
+                EventHistory::Remove { guid, .. } => {
+                    if state.items.remove(&guid).is_some() {
+                        state.order.retain(|other| *other != guid);
+                    } else {
+                        log::warn!("deleting nonexistent config: {:?}", guid);
+                    }
+                }
+                EventHistory::Modify { guid, to, .. } => {
+                    if state.items.contains_key(&guid) {
+                        state.draw(guid, &to);
+                    } else {
+                        log::warn!("Updating nonexistent config: {:?}", guid);
+                    }
+                }
+                EventHistory::Checkpoint => {}
+            },
+            Event::Checkpoint { .. } => {}
+        }
+        Ok(())
+    }
+}
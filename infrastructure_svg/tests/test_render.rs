@@ -0,0 +1,375 @@
+use commitcanvas::model::PartialShapeConfig;
+use commitcanvas::model::{
+    ArrowDetails, Color, Dash, EllipseDetails, Event, FillStyle, FontSize, Head, Model, Options,
+    RectDetails, Routing, ShapeConfig, ShapeDetails, TextAlign, TextDetails, Thickness,
+    VerticalAlign,
+};
+use commitcanvas::types::PointGrid;
+use infrastructure_svg::{render, RenderError, SvgView};
+
+fn add(model: &mut Model, start: (i32, i32), end: (i32, i32), details: ShapeDetails) {
+    model.process_event(Event::Add {
+        guid: None,
+        config: ShapeConfig {
+            start: PointGrid {
+                x: start.0,
+                y: start.1,
+            },
+            end: PointGrid { x: end.0, y: end.1 },
+            details,
+            options: Options {
+                stroke_color: Color::Rose,
+                ..Default::default()
+            },
+            seed: 0,
+        },
+    });
+}
+
+fn sample() -> Model {
+    let mut model = Model::new();
+    add(
+        &mut model,
+        (0, 0),
+        (4, 2),
+        ShapeDetails::Rect(RectDetails {
+            background: Color::Sky,
+            ..Default::default()
+        }),
+    );
+    add(
+        &mut model,
+        (4, 1),
+        (10, 1),
+        ShapeDetails::Arrow(ArrowDetails::default()),
+    );
+    add(
+        &mut model,
+        (10, 0),
+        (20, 2),
+        ShapeDetails::Text(TextDetails {
+            content: "a < b & c".to_string(),
+            font_size: FontSize::Small,
+            align: TextAlign::Center,
+            vertical_align: VerticalAlign::Top,
+        }),
+    );
+    model
+}
+
+#[test]
+fn test_render() {
+    let svg = render(&sample().to_document()).unwrap();
+    // The default grid puts point (0, 0) at pixel (6, 6) with 12 pixels per
+    // step, the drawing is padded by two steps.
+    assert!(svg.starts_with(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-18 -18 288 72\" width=\"288\" height=\"72\">"
+    ));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    for part in [
+        "<filter filterUnits=\"objectBoundingBox\" id=\"cc_pencil_texture_4\">",
+        ".cc_fill_sky { fill: #7dd3fc; stroke: #7dd3fc; opacity: 0.25; }",
+        ".cc_stroke_rose { color: #f43f5e; }",
+        "<g id=\"cc_id_0\" class=\"cc_stroke_rose\">",
+        "class=\"cc_rect\" filter=\"url(#cc_pencil_texture_4)\" stroke-width=\"1.0\"",
+        // Corners round off by 30% of the shorter side.
+        "<path d=\"M 46.8 6 L 50.05 6.42 L 52.29 7.71 L 53.58 9.95 L 54 13.2 L 54 22.8",
+        "class=\"cc_rect_fill cc_fill_style_solid cc_fill_sky\"></path>",
+        "class=\"cc_arrow\" filter=\"url(#cc_pencil_texture_4)\" stroke-width=\"1.0\"",
+        "class=\"cc_arrow_head\" filter=\"url(#cc_pencil_texture_4)\"",
+        "font-size=\"12px\" text-anchor=\"middle\"",
+        ">a &lt; b &amp; c</tspan>",
+    ] {
+        assert!(svg.contains(part), "missing {}", part);
+    }
+}
+
+#[test]
+fn test_render_is_deterministic() {
+    let document = sample().to_document();
+    assert_eq!(render(&document).unwrap(), render(&document).unwrap());
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        render(&Model::new().to_document()),
+        Err(RenderError::Empty)
+    ));
+    let mut document = sample().to_document();
+    document.shapes[1].guid = 0;
+    assert!(matches!(render(&document), Err(RenderError::Document(_))));
+}
+
+#[test]
+fn test_view_follows_the_model() {
+    let view = SvgView::new();
+    let mut model = sample();
+    model.add_view(Box::new(view.clone()));
+    assert_eq!(view.svg(), render(&model.to_document()).unwrap());
+    let before = view.element(0).unwrap();
+
+    model.process_event(Event::Modify {
+        guid: 0,
+        config: PartialShapeConfig {
+            start: None,
+            end: Some(PointGrid { x: 6, y: 3 }),
+            details: None,
+            options: None,
+            seed: None,
+        },
+    });
+    model.process_event(Event::Checkpoint);
+    model.process_event(Event::Remove { guid: 1 });
+    model.process_event(Event::Checkpoint);
+    assert_ne!(view.element(0), Some(before.clone()));
+    assert!(view.element(1).is_none());
+    assert_eq!(view.svg(), render(&model.to_document()).unwrap());
+
+    model.undo();
+    model.undo();
+    assert_eq!(view.element(0), Some(before));
+    assert!(view.element(1).is_some());
+}
+
+#[test]
+fn test_elements() {
+    let view = SvgView::new();
+    let mut model = sample();
+    model.add_view(Box::new(view.clone()));
+    let rect = view.element(0).unwrap();
+    assert_eq!(rect.get("id"), Some("cc_id_0"));
+    assert_eq!(rect.get("class"), Some("cc_stroke_rose"));
+    let names: Vec<_> = rect.children.iter().map(|child| child.name).collect();
+    assert_eq!(names, ["path", "path"]);
+
+    let text = view.element(2).unwrap();
+    let lines: Vec<_> = text.children[0]
+        .children
+        .iter()
+        .map(|line| line.text.as_deref().unwrap())
+        .collect();
+    assert_eq!(lines, ["a < b & c"]);
+}
+
+#[test]
+fn test_ellipse() {
+    let view = SvgView::new();
+    let mut model = Model::new();
+    model.add_view(Box::new(view.clone()));
+    add(
+        &mut model,
+        (0, 0),
+        (4, 2),
+        ShapeDetails::Ellipse(EllipseDetails {
+            background: Color::Lime,
+            ..Default::default()
+        }),
+    );
+    let ellipse = view.element(0).unwrap();
+    let names: Vec<_> = ellipse.children.iter().map(|child| child.name).collect();
+    assert_eq!(names, ["path", "path"]);
+    assert_eq!(ellipse.children[0].get("class"), Some("cc_ellipse"));
+    let fill = &ellipse.children[1];
+    assert_eq!(
+        fill.get("class"),
+        Some("cc_ellipse_fill cc_fill_style_solid cc_fill_lime")
+    );
+    // The outline of the fill runs through the ends of the axes.
+    let d = fill.get("d").unwrap();
+    assert!(d.starts_with("M 54 18 L"));
+    assert!(d.contains(" L 30 30 L") && d.contains(" L 6 18 L") && d.contains(" L 30 6 L"));
+}
+
+#[test]
+fn test_fill_styles() {
+    let view = SvgView::new();
+    let mut model = Model::new();
+    model.add_view(Box::new(view.clone()));
+    let styles = [
+        (FillStyle::Hachure, "cc_fill_style_hachure"),
+        (FillStyle::CrossHatch, "cc_fill_style_cross_hatch"),
+        (FillStyle::ZigZag, "cc_fill_style_zigzag"),
+        (FillStyle::Dots, "cc_fill_style_dots"),
+    ];
+    for (fill, _) in styles {
+        add(
+            &mut model,
+            (0, 0),
+            (10, 10),
+            ShapeDetails::Rect(RectDetails {
+                background: Color::Blue,
+                fill,
+            }),
+        );
+    }
+    let mut strokes = Vec::new();
+    for (guid, (_, class)) in styles.iter().enumerate() {
+        let rect = view.element(guid as i32).unwrap();
+        let fill = &rect.children[1];
+        assert_eq!(
+            fill.get("class"),
+            Some(format!("cc_rect_fill {} cc_fill_blue", class).as_str())
+        );
+        strokes.push(fill.get("d").unwrap().matches('M').count());
+    }
+    // Cross-hatching doubles the hachure, the zigzag is one stroke.
+    assert_eq!(strokes[1], 2 * strokes[0]);
+    assert_eq!(strokes[2], 1);
+    assert!(strokes[3] > strokes[1]);
+    assert!(view.svg().contains(
+        ".cc_fill_style_hachure, .cc_fill_style_cross_hatch, .cc_fill_style_zigzag { fill: none;"
+    ));
+}
+
+#[test]
+fn test_seeds() {
+    let view = SvgView::new();
+    let mut model = Model::new();
+    model.add_view(Box::new(view.clone()));
+    for _ in 0..2 {
+        add(
+            &mut model,
+            (0, 0),
+            (6, 4),
+            ShapeDetails::Rect(RectDetails::default()),
+        );
+    }
+    let outline = |guid| view.element(guid).unwrap().children[0].clone();
+    // The same rectangle twice, drawn with the seeds of different GUIDs.
+    assert_ne!(outline(0), outline(1));
+
+    let modify = |seed| Event::Modify {
+        guid: 1,
+        config: PartialShapeConfig {
+            start: None,
+            end: None,
+            details: None,
+            options: None,
+            seed: Some(seed),
+        },
+    };
+    let seed = model.get_shape(0).unwrap().seed;
+    model.process_event(modify(seed));
+    assert_eq!(outline(0), outline(1));
+    model.process_event(modify(seed + 1));
+    assert_ne!(outline(0), outline(1));
+}
+
+#[test]
+fn test_arrow_waypoints() {
+    let view = SvgView::new();
+    let mut model = Model::new();
+    model.add_view(Box::new(view.clone()));
+    for routing in [Routing::Straight, Routing::Curved] {
+        add(
+            &mut model,
+            (0, 0),
+            (10, 0),
+            ShapeDetails::Arrow(ArrowDetails {
+                waypoints: vec![PointGrid { x: 5, y: 8 }],
+                routing,
+                ..Default::default()
+            }),
+        );
+    }
+    let d = |guid| {
+        view.element(guid).unwrap().children[0]
+            .get("d")
+            .unwrap()
+            .to_string()
+    };
+    // Two strokes along each segment, or two along the whole curve.
+    assert_eq!(d(0).matches('M').count(), 4);
+    assert_eq!(d(1).matches('M').count(), 2);
+    // The drawing reaches down to the waypoint, padded by two steps.
+    assert!(view.svg().contains("viewBox=\"-18 -18 168 144\""));
+}
+
+#[test]
+fn test_arrow_heads() {
+    let view = SvgView::new();
+    let mut model = Model::new();
+    model.add_view(Box::new(view.clone()));
+    let heads = [
+        (Head::None, Head::Open),
+        (Head::Open, Head::Open),
+        (Head::Triangle, Head::Circle),
+        (Head::None, Head::None),
+    ];
+    for (start_head, end_head) in heads {
+        add(
+            &mut model,
+            (0, 0),
+            (10, 0),
+            ShapeDetails::Arrow(ArrowDetails {
+                start_head,
+                end_head,
+                ..Default::default()
+            }),
+        );
+    }
+    let parts = |guid| {
+        let arrow = view.element(guid).unwrap();
+        let names: Vec<_> = arrow.children.iter().map(|child| child.name).collect();
+        assert_eq!(names, ["path", "path", "path"]);
+        let d = |i: usize| arrow.children[i].get("d").unwrap().to_string();
+        (d(0), d(1), d(2))
+    };
+    // Two strokes along each side of an open head.
+    let (line, head, fill) = parts(0);
+    assert_eq!(head.matches('M').count(), 4);
+    assert!(fill.is_empty());
+    // The line reaches the tip of an open head.
+    assert!(line.ends_with(" 126 6") || line.contains(" 126 6 M"));
+    let (_, head, _) = parts(1);
+    assert_eq!(head.matches('M').count(), 8);
+    // A filled triangle at the start; the line stops short of the hollow
+    // circle at the end.
+    let (line, _, fill) = parts(2);
+    assert!(fill.starts_with("M 6 6 L"));
+    assert!(!line.contains(" 126 6"));
+    let (_, head, fill) = parts(3);
+    assert!(head.is_empty() && fill.is_empty());
+}
+
+#[test]
+fn test_dashes() {
+    let view = SvgView::new();
+    let mut model = Model::new();
+    model.add_view(Box::new(view.clone()));
+    let styles = [
+        (Dash::Solid, Thickness::Thin),
+        (Dash::Dashed, Thickness::Thin),
+        (Dash::Dashed, Thickness::Thick),
+        (Dash::Dotted, Thickness::Thin),
+    ];
+    for (dash, thickness) in styles {
+        model.process_event(Event::Add {
+            guid: None,
+            config: ShapeConfig {
+                start: PointGrid { x: 0, y: 0 },
+                end: PointGrid { x: 20, y: 10 },
+                details: ShapeDetails::Ellipse(EllipseDetails::default()),
+                options: Options {
+                    thickness,
+                    dash,
+                    ..Default::default()
+                },
+                seed: 0,
+            },
+        });
+    }
+    let strokes = |guid| {
+        view.element(guid).unwrap().children[0]
+            .get("d")
+            .unwrap()
+            .matches('M')
+            .count()
+    };
+    // Dashes are strokes of their own, longer on thicker outlines, and dots
+    // shorter still.
+    assert!(strokes(1) > strokes(0));
+    assert!(strokes(2) < strokes(1));
+    assert!(strokes(3) > strokes(1));
+}
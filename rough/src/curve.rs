@@ -0,0 +1,97 @@
+use rand::rngs::SmallRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::f32::consts::TAU;
+
+use crate::path::Path;
+
+/// Times a curve is traced, like the two strokes of [`crate::to_path`].
+pub(crate) const PASSES: usize = 2;
+
+/// A smooth curve through `points` as cubic Béziers, the Catmull-Rom spline
+/// through them.
+pub(crate) fn curve_through(points: &[(f32, f32)]) -> Path {
+    let at = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+    let mut path = Path::new();
+    path.move_to(points[0]);
+    for i in 0..points.len() as isize - 1 {
+        let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        path.cubic_to(
+            (p1.0 + (p2.0 - p0.0) / 6.0, p1.1 + (p2.1 - p0.1) / 6.0),
+            (p2.0 - (p3.0 - p1.0) / 6.0, p2.1 - (p3.1 - p1.1) / 6.0),
+            p2,
+        );
+    }
+    path
+}
+
+/// A smooth curve through `points`, traced twice. Each stroke misses the
+/// points by up to about `roughness` pixels, less at its ends, and bows a
+/// little between them.
+///
+/// The same `seed` always gives the same strokes, wherever the curve is.
+pub fn curve_to_path(points: &[(f32, f32)], roughness: f32, seed: u64) -> Path {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut path = Path::new();
+    if points.len() < 2 {
+        return path;
+    }
+    for _ in 0..PASSES {
+        let mut wobbly = Vec::with_capacity(2 * points.len() - 1);
+        for (i, &point) in points.iter().enumerate() {
+            if i > 0 {
+                let previous = points[i - 1];
+                let (dx, dy) = (point.0 - previous.0, point.1 - previous.1);
+                let bow = 0.01 * roughness * rng.gen_range(-1.0..=1.0);
+                wobbly.push((
+                    (previous.0 + point.0) / 2.0 - dy * bow,
+                    (previous.1 + point.1) / 2.0 + dx * bow,
+                ));
+            }
+            let damp = if i == 0 || i == points.len() - 1 {
+                2.0
+            } else {
+                1.0
+            };
+            let r = rng.gen_range(0.0..=roughness).sqrt() / damp;
+            let phi = rng.gen_range(0.0..TAU);
+            wobbly.push((point.0 + r * phi.cos(), point.1 + r * phi.sin()));
+        }
+        path.append(curve_through(&wobbly));
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Command;
+
+    #[test]
+    fn test_curve_to_path() {
+        let points = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0)];
+        let path = curve_to_path(&points, 1.0, 7);
+        assert_eq!(path, curve_to_path(&points, 1.0, 7));
+        assert_ne!(path, curve_to_path(&points, 1.0, 8));
+        // Two strokes, with a curve to each point and each bow between them.
+        let moves = path
+            .commands()
+            .iter()
+            .filter(|command| matches!(command, Command::Move(_)))
+            .count();
+        assert_eq!(moves, 2);
+        assert_eq!(path.commands().len(), 2 * 5);
+        let ends: Vec<_> = path.end_points().collect();
+        for (point, end) in [
+            (points[0], ends[0]),
+            (points[1], ends[2]),
+            (points[2], ends[9]),
+        ] {
+            assert!((point.0 - end.0).hypot(point.1 - end.1) <= 1.0);
+        }
+
+        let smooth = curve_to_path(&points, 0.0, 7);
+        assert_eq!(smooth.end_points().nth(2), Some((100.0, 0.0)));
+        assert!(curve_to_path(&points[..1], 1.0, 7).is_empty());
+    }
+}
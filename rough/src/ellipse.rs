@@ -0,0 +1,139 @@
+use rand::rngs::SmallRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::f32::consts::TAU;
+
+use crate::curve::{curve_through, PASSES};
+use crate::path::Path;
+
+/// Ramanujan's approximation of the circumference.
+fn perimeter((rx, ry): (f32, f32)) -> f32 {
+    let (a, b) = (rx.abs(), ry.abs());
+    std::f32::consts::PI * (3.0 * (a + b) - ((3.0 * a + b) * (a + 3.0 * b)).sqrt())
+}
+
+/// Points along the ellipse from `start` over `sweep` radians, each pushed
+/// off the outline by up to `roughness` times a few percent of the radius.
+fn wobbly_points(
+    center: (f32, f32),
+    radii: (f32, f32),
+    start: f32,
+    sweep: f32,
+    roughness: f32,
+    rng: &mut SmallRng,
+) -> Vec<(f32, f32)> {
+    let length = perimeter(radii) * sweep.abs() / TAU;
+    let count = ((length / 40.0).ceil() as usize).clamp(4, 32);
+    let step = sweep / count as f32;
+    let wobble = 0.025 * roughness;
+    // Each stroke is a little off as a whole, a smoother difference than
+    // that between single points.
+    let pass = 1.0 + rng.gen_range(-wobble..=wobble);
+    (0..=count)
+        .map(|i| {
+            let jitter = if 0 < i && i < count {
+                0.2 * roughness * rng.gen_range(-step.abs()..=step.abs())
+            } else {
+                0.0
+            };
+            let angle = start + step * i as f32 + jitter;
+            let scale = pass + rng.gen_range(-wobble..=wobble);
+            (
+                center.0 + radii.0 * scale * angle.cos(),
+                center.1 + radii.1 * scale * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// An ellipse around `center` with the half axes `radii`, traced twice from
+/// random angles and overshooting where each stroke closes, like a pen that
+/// doesn't stop exactly where it started.
+///
+/// The same `seed` always gives the same strokes, wherever the ellipse is. A
+/// circle is an ellipse with equal radii.
+pub fn ellipse_to_path(center: (f32, f32), radii: (f32, f32), roughness: f32, seed: u64) -> Path {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut path = Path::new();
+    for _ in 0..PASSES {
+        let start = rng.gen_range(0.0..TAU);
+        let overshoot = TAU * rng.gen_range(0.03..0.08) * (0.5 + roughness);
+        path.append(curve_through(&wobbly_points(
+            center,
+            radii,
+            start,
+            TAU + overshoot,
+            roughness,
+            &mut rng,
+        )));
+    }
+    path
+}
+
+/// The part of the ellipse of [`ellipse_to_path`] from the angle
+/// `angles.0` to `angles.1`, in radians clockwise from the positive x axis
+/// on screen.
+pub fn arc_to_path(
+    center: (f32, f32),
+    radii: (f32, f32),
+    angles: (f32, f32),
+    roughness: f32,
+    seed: u64,
+) -> Path {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut path = Path::new();
+    for _ in 0..PASSES {
+        path.append(curve_through(&wobbly_points(
+            center,
+            radii,
+            angles.0,
+            angles.1 - angles.0,
+            roughness,
+            &mut rng,
+        )));
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Command;
+
+    #[test]
+    fn test_ellipse() {
+        let path = ellipse_to_path((10.0, -20.0), (40.0, 20.0), 0.8, 7);
+        assert_eq!(path, ellipse_to_path((10.0, -20.0), (40.0, 20.0), 0.8, 7));
+        assert_ne!(path, ellipse_to_path((10.0, -20.0), (40.0, 20.0), 0.8, 8));
+        // Moving the ellipse moves its strokes.
+        let moved = ellipse_to_path((-10.0, 20.0), (40.0, 20.0), 0.8, 7);
+        for ((x, y), (p, q)) in path.end_points().zip(moved.end_points()) {
+            assert!((p - x + 20.0).abs() < 0.001 && (q - y - 40.0).abs() < 0.001);
+        }
+        let moves = path
+            .commands()
+            .iter()
+            .filter(|c| matches!(c, Command::Move(_)))
+            .count();
+        assert_eq!(moves, PASSES);
+        for (x, y) in path.end_points() {
+            let distance = ((x - 10.0) / 40.0).powi(2) + ((y + 20.0) / 20.0).powi(2);
+            assert!((0.9..1.1).contains(&distance), "{} {} is off", x, y);
+        }
+
+        let smooth = ellipse_to_path((0.0, 0.0), (5.0, 5.0), 0.0, 7);
+        for (x, y) in smooth.end_points() {
+            assert!((x.hypot(y) - 5.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_arc() {
+        let path = arc_to_path((0.0, 0.0), (10.0, 10.0), (0.0, TAU / 4.0), 0.0, 7);
+        let points: Vec<(f32, f32)> = path.end_points().collect();
+        assert_eq!(points[0], (10.0, 0.0));
+        let (x, y) = points[points.len() - 1];
+        assert!(x.abs() < 0.001 && (y - 10.0).abs() < 0.001);
+        assert!(points.iter().all(|(x, y)| *x > -0.001 && *y >= 0.0));
+    }
+}
@@ -0,0 +1,410 @@
+use rand::rngs::SmallRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::f32::consts::TAU;
+
+use crate::geometry::rounded_rect_polygon;
+use crate::path::Path;
+
+/// How the inside of a shape is drawn.
+///
+/// Angles are in radians clockwise from the positive x axis on screen, gaps
+/// the distance between neighbouring lines or dots in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    /// The outline itself, to be filled rather than stroked.
+    Solid,
+    /// Parallel lines.
+    Hachure { angle: f32, gap: f32 },
+    /// Hachure in two directions at right angles.
+    CrossHatch { angle: f32, gap: f32 },
+    /// Hachure lines joined by diagonals into one stroke.
+    ZigZag { angle: f32, gap: f32 },
+    /// Dots on the hachure lines.
+    Dots { gap: f32 },
+}
+
+/// Points per outline when an ellipse is filled as a polygon.
+const ELLIPSE_POINTS: usize = 48;
+
+type Segment = ((f32, f32), (f32, f32));
+
+fn rotate((x, y): (f32, f32), angle: f32) -> (f32, f32) {
+    let (sin, cos) = angle.sin_cos();
+    (x * cos - y * sin, x * sin + y * cos)
+}
+
+/// The parts of the lines `gap` apart at `angle` that lie inside `polygon`,
+/// row by row. Points inside are found by the even-odd rule, so the polygon
+/// may be concave or cross itself.
+fn hachure_rows(polygon: &[(f32, f32)], angle: f32, gap: f32) -> Vec<Vec<Segment>> {
+    if polygon.len() < 3 || gap <= 0.0 {
+        return Vec::new();
+    }
+    // Turned so the lines run along the x axis.
+    let turned: Vec<(f32, f32)> = polygon.iter().map(|p| rotate(*p, -angle)).collect();
+    let (min, max) = turned
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+            (min.min(p.1), max.max(p.1))
+        });
+
+    let mut rows = Vec::new();
+    let mut y = min + gap / 2.0;
+    while y < max {
+        let mut crossings: Vec<f32> = (0..turned.len())
+            .filter_map(|i| {
+                let (p, q) = (turned[i], turned[(i + 1) % turned.len()]);
+                ((p.1 <= y) != (q.1 <= y)).then(|| p.0 + (y - p.1) * (q.0 - p.0) / (q.1 - p.1))
+            })
+            .collect();
+        crossings.sort_by(f32::total_cmp);
+        rows.push(
+            crossings
+                .chunks_exact(2)
+                .map(|pair| (rotate((pair[0], y), angle), rotate((pair[1], y), angle)))
+                .collect(),
+        );
+        y += gap;
+    }
+    rows
+}
+
+/// A slightly bowed stroke from `a` to `b` that starts and ends exactly on
+/// them, so fills don't poke out of their outline.
+fn line(path: &mut Path, (a, b): Segment, roughness: f32, move_to: bool, rng: &mut SmallRng) {
+    let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+    let bow = rng.gen_range(-1.0..=1.0) * roughness * (0.02 * length).min(2.0);
+    let normal = if length > 0.0 {
+        (-(b.1 - a.1) / length, (b.0 - a.0) / length)
+    } else {
+        (0.0, 0.0)
+    };
+    let at = |t: f32| {
+        (
+            a.0 + t * (b.0 - a.0) + bow * normal.0,
+            a.1 + t * (b.1 - a.1) + bow * normal.1,
+        )
+    };
+    let (c1, c2) = (at(1.0 / 3.0), at(2.0 / 3.0));
+    if move_to {
+        path.move_to(a);
+    }
+    path.cubic_to(c1, c2, b);
+}
+
+fn hachure(path: &mut Path, rows: &[Vec<Segment>], roughness: f32, rng: &mut SmallRng) {
+    for segment in rows.iter().flatten() {
+        line(path, *segment, roughness, true, rng);
+    }
+}
+
+/// Joins each row to the next with a diagonal. Rows crossing the shape more
+/// than once would need diagonals through the outside and stay separate.
+fn zigzag(path: &mut Path, rows: &[Vec<Segment>], roughness: f32, rng: &mut SmallRng) {
+    let mut previous: Option<(f32, f32)> = None;
+    for row in rows {
+        match (row.as_slice(), previous) {
+            ([segment], Some(end)) => {
+                line(path, (end, segment.0), roughness, false, rng);
+                line(path, *segment, roughness, false, rng);
+                previous = Some(segment.1);
+            }
+            ([segment], None) => {
+                line(path, *segment, roughness, true, rng);
+                previous = Some(segment.1);
+            }
+            (segments, _) => {
+                for segment in segments {
+                    line(path, *segment, roughness, true, rng);
+                }
+                previous = None;
+            }
+        }
+    }
+}
+
+/// A closed circle of four quarters.
+fn dot(path: &mut Path, (x, y): (f32, f32), radius: f32) {
+    const AXES: [(f32, f32); 4] = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+    // Control points of a quarter are this far along its tangents.
+    let k = 0.5523 * radius;
+    path.move_to((x + radius, y));
+    for i in 0..AXES.len() {
+        let (from, to) = (AXES[i], AXES[(i + 1) % AXES.len()]);
+        path.cubic_to(
+            (
+                x + radius * from.0 + k * to.0,
+                y + radius * from.1 + k * to.1,
+            ),
+            (
+                x + radius * to.0 + k * from.0,
+                y + radius * to.1 + k * from.1,
+            ),
+            (x + radius * to.0, y + radius * to.1),
+        );
+    }
+    path.close();
+}
+
+fn dots(path: &mut Path, rows: &[Vec<Segment>], gap: f32, roughness: f32, rng: &mut SmallRng) {
+    let radius = (gap / 8.0).max(0.5);
+    for &(a, b) in rows.iter().flatten() {
+        let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        if length < 2.0 * radius {
+            continue;
+        }
+        let count = (length / gap).floor().max(1.0);
+        let margin = (length - (count - 1.0) * gap) / 2.0;
+        for i in 0..count as usize {
+            let jitter = 0.2 * gap * roughness * rng.gen_range(-1.0..=1.0);
+            let t = (margin + i as f32 * gap + jitter).clamp(radius, length - radius) / length;
+            dot(path, (a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1)), radius);
+        }
+    }
+}
+
+/// The fill of the polygon through `points`, for any polygon including
+/// concave ones. Lines and dots are kept inside the outline.
+///
+/// [`Fill::Solid`] gives the closed polygon, to be filled. All other fills
+/// give strokes, to be drawn with a thin pen and without filling.
+pub fn polygon_fill_path(points: &[(f32, f32)], fill: Fill, roughness: f32, seed: u64) -> Path {
+    let mut path = Path::new();
+    if points.len() < 3 {
+        return path;
+    }
+    let mut rng = SmallRng::seed_from_u64(seed);
+    match fill {
+        Fill::Solid => {
+            path.move_to(points[0]);
+            for point in &points[1..] {
+                path.line_to(*point);
+            }
+            path.close();
+        }
+        Fill::Hachure { angle, gap } => hachure(
+            &mut path,
+            &hachure_rows(points, angle, gap),
+            roughness,
+            &mut rng,
+        ),
+        Fill::CrossHatch { angle, gap } => {
+            for angle in [angle, angle + TAU / 4.0] {
+                hachure(
+                    &mut path,
+                    &hachure_rows(points, angle, gap),
+                    roughness,
+                    &mut rng,
+                );
+            }
+        }
+        Fill::ZigZag { angle, gap } => zigzag(
+            &mut path,
+            &hachure_rows(points, angle, gap),
+            roughness,
+            &mut rng,
+        ),
+        Fill::Dots { gap } => dots(
+            &mut path,
+            &hachure_rows(points, 0.0, gap),
+            gap,
+            roughness,
+            &mut rng,
+        ),
+    }
+    path
+}
+
+/// The fill of the rectangle of [`crate::rect_to_path`], see
+/// [`polygon_fill_path`].
+pub fn rect_fill_path(
+    start: (f32, f32),
+    end: (f32, f32),
+    rounding: f32,
+    fill: Fill,
+    roughness: f32,
+    seed: u64,
+) -> Path {
+    polygon_fill_path(
+        &rounded_rect_polygon(start, end, rounding),
+        fill,
+        roughness,
+        seed,
+    )
+}
+
+/// The fill of the ellipse around `center` with the half axes `radii`, see
+/// [`polygon_fill_path`].
+pub fn ellipse_fill_path(
+    center: (f32, f32),
+    radii: (f32, f32),
+    fill: Fill,
+    roughness: f32,
+    seed: u64,
+) -> Path {
+    let points: Vec<(f32, f32)> = (0..ELLIPSE_POINTS)
+        .map(|i| {
+            let angle = TAU * i as f32 / ELLIPSE_POINTS as f32;
+            (
+                center.0 + radii.0 * angle.cos(),
+                center.1 + radii.1 * angle.sin(),
+            )
+        })
+        .collect();
+    polygon_fill_path(&points, fill, roughness, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Command;
+
+    const SQUARE: [(f32, f32); 4] = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+
+    /// All points of `path`, control points included.
+    fn points(path: &Path) -> Vec<(f32, f32)> {
+        path.commands()
+            .iter()
+            .flat_map(|command| match *command {
+                Command::Move(p) | Command::Line(p) => vec![p],
+                Command::Cubic(a, b, p) => vec![a, b, p],
+                Command::Close => vec![],
+            })
+            .collect()
+    }
+
+    /// The number of strokes in `path`.
+    fn strokes(path: &Path) -> usize {
+        path.commands()
+            .iter()
+            .filter(|c| matches!(c, Command::Move(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_hachure_stays_inside() {
+        let fill = Fill::Hachure {
+            angle: -0.7,
+            gap: 10.0,
+        };
+        let path = polygon_fill_path(&SQUARE, fill, 0.8, 1);
+        assert_eq!(path, polygon_fill_path(&SQUARE, fill, 0.8, 1));
+        assert!(strokes(&path) > 10);
+        for (x, y) in points(&path) {
+            assert!((-2.0..=102.0).contains(&x) && (-2.0..=102.0).contains(&y));
+        }
+
+        // Horizontal lines 10 apart from 5 to 95.
+        let path = polygon_fill_path(
+            &SQUARE,
+            Fill::Hachure {
+                angle: 0.0,
+                gap: 10.0,
+            },
+            0.0,
+            1,
+        );
+        assert_eq!(strokes(&path), 10);
+        assert!(path.to_svg().starts_with("M 0 5 C"));
+    }
+
+    #[test]
+    fn test_concave_polygon() {
+        // A U open to the top, rows through the arms cross it twice.
+        let u = [
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 20.0),
+            (20.0, 20.0),
+            (20.0, 0.0),
+            (30.0, 0.0),
+            (30.0, 30.0),
+            (0.0, 30.0),
+        ];
+        let fill = Fill::Hachure {
+            angle: 0.0,
+            gap: 10.0,
+        };
+        let path = polygon_fill_path(&u, fill, 0.0, 1);
+        // Two rows with two segments each, one across the bottom.
+        assert_eq!(strokes(&path), 5);
+        for (x, y) in points(&path) {
+            assert!(
+                !(10.0 < x && x < 20.0 && y < 20.0),
+                "{} {} is outside",
+                x,
+                y
+            );
+        }
+
+        let zigzag = polygon_fill_path(
+            &u,
+            Fill::ZigZag {
+                angle: 0.0,
+                gap: 10.0,
+            },
+            0.0,
+            1,
+        );
+        assert_eq!(strokes(&zigzag), 5);
+    }
+
+    #[test]
+    fn test_styles() {
+        let cross = polygon_fill_path(
+            &SQUARE,
+            Fill::CrossHatch {
+                angle: 0.0,
+                gap: 10.0,
+            },
+            0.0,
+            1,
+        );
+        assert_eq!(strokes(&cross), 20);
+
+        let zigzag = polygon_fill_path(
+            &SQUARE,
+            Fill::ZigZag {
+                angle: 0.0,
+                gap: 10.0,
+            },
+            0.0,
+            1,
+        );
+        assert_eq!(strokes(&zigzag), 1);
+        assert_eq!(zigzag.commands().len(), 20);
+
+        let dots = polygon_fill_path(&SQUARE, Fill::Dots { gap: 20.0 }, 0.8, 1);
+        assert_eq!(
+            dots.commands()
+                .iter()
+                .filter(|c| **c == Command::Close)
+                .count(),
+            25
+        );
+
+        let solid = polygon_fill_path(&SQUARE, Fill::Solid, 0.8, 1);
+        assert_eq!(solid.to_svg(), "M 0 0 L 100 0 L 100 100 L 0 100 Z");
+
+        assert!(polygon_fill_path(&SQUARE[..2], Fill::Solid, 0.0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_ellipse_fill() {
+        let path = ellipse_fill_path(
+            (50.0, 50.0),
+            (40.0, 20.0),
+            Fill::Hachure {
+                angle: 0.3,
+                gap: 5.0,
+            },
+            0.0,
+            1,
+        );
+        for (x, y) in points(&path) {
+            let distance = ((x - 50.0) / 40.0).powi(2) + ((y - 50.0) / 20.0).powi(2);
+            assert!(distance <= 1.001, "{} {} is outside", x, y);
+        }
+    }
+}
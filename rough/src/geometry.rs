@@ -6,18 +6,32 @@ use std::ops::Add;
 use std::ops::Mul;
 use std::ops::Sub;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
-    pub x: i32,
-    pub y: i32,
+    pub x: f32,
+    pub y: f32,
 }
 
 impl Point {
-    pub fn new(x: i32, y: i32) -> Point {
+    pub fn new(x: f32, y: f32) -> Point {
+        Point { x, y }
+    }
+}
+
+impl From<(f32, f32)> for Point {
+    fn from((x, y): (f32, f32)) -> Point {
         Point { x, y }
     }
 }
 
+impl From<Point> for (f32, f32) {
+    fn from(point: Point) -> (f32, f32) {
+        (point.x, point.y)
+    }
+}
+
 impl Serialize for Point {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -35,8 +49,8 @@ impl Add<&Vector> for Point {
 
     fn add(self, rhs: &Vector) -> Point {
         Point {
-            x: self.x + rhs.x as i32,
-            y: self.y + rhs.y as i32,
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
         }
     }
 }
@@ -46,8 +60,8 @@ impl Sub<&Point> for Point {
 
     fn sub(self, rhs: &Point) -> Vector {
         Vector {
-            x: self.x as f32 - rhs.x as f32,
-            y: self.y as f32 - rhs.y as f32,
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
         }
     }
 }
@@ -75,8 +89,8 @@ impl Vector {
 
     pub fn from_points(start: &Point, end: &Point) -> Vector {
         Vector {
-            x: end.x as f32 - start.x as f32,
-            y: end.y as f32 - start.y as f32,
+            x: end.x - start.x,
+            y: end.y - start.y,
         }
     }
 
@@ -110,74 +124,39 @@ impl Line {
     }
 
     pub fn length(&self) -> f32 {
-        let x = self.end.x as f32 - self.start.x as f32;
-        let y = self.end.y as f32 - self.start.y as f32;
-        (x * x + y * y).sqrt()
+        (self.end - &self.start).length()
     }
 
-    pub fn to_catmull_rom_spline(&self, roughness: f32, count: i32, end_damp: f32) -> [Point; 4] {
-        let length = self.length();
-        let mut rng = SmallRng::seed_from_u64(
-            (self.start.x + self.start.y + self.end.x + self.end.y + count) as u64,
-        );
-        let roughness = f32::clamp(0.1 * roughness * length, 0.1, 100.0);
-        let r1 = f32::sqrt(rng.gen_range(0.0..roughness)) / end_damp;
-        let r2 = f32::sqrt(rng.gen_range(0.0..roughness)) / end_damp;
-        let phi1 = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-        let phi2 = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-        let mid_point_offset = 0.005 * length * rng.gen::<f32>();
-        let dis_along = rng.gen_range(-0.1..0.1);
-        let dis_orth = rng.gen_range(-0.1..0.1) * roughness;
-
-        let start = Point {
-            x: (self.start.x as f32 + r1 * f32::cos(phi1)) as i32,
-            y: (self.start.y as f32 + r1 * f32::sin(phi1)) as i32,
-        };
-        let end = Point {
-            x: (self.end.x as f32 + r2 * f32::cos(phi2)) as i32,
-            y: (self.end.y as f32 + r2 * f32::sin(phi2)) as i32,
-        };
+    pub fn to_catmull_rom_spline(&self, roughness: f32, end_damp: f32, seed: u64) -> [Point; 4] {
+        to_catmull_rom_spline(
+            self.start.into(),
+            self.end.into(),
+            roughness,
+            end_damp,
+            seed,
+        )
+    }
 
-        let along = Vector::from_points(&start, &end).normalize();
-        let orth = Vector::new(-along.y, along.x);
-
-        let mid_point = self.start + &(0.5 * length * &along) + &(mid_point_offset * &orth);
-        let control_point =
-            self.start + &((0.75 + dis_along) * length * &along) + &(dis_orth * &orth);
-
-        [start, mid_point, control_point, end]
-    }
-
-    pub fn to_svg_path(&self, roughness: f32, count: i32, end_damp: f32) -> String {
-        let mut path = String::new();
-        for i in 0..count {
-            let spline = self.to_catmull_rom_spline(roughness, i, end_damp);
-            path += &format!(
-                " M {} {} C {} {} {} {} {} {}",
-                spline[0].x,
-                spline[0].y,
-                spline[1].x,
-                spline[1].y,
-                spline[2].x,
-                spline[2].y,
-                spline[3].x,
-                spline[3].y
-            );
-        }
-        path
+    pub fn to_path(&self, roughness: f32, count: i32, end_damp: f32, seed: u64) -> Path {
+        to_path(
+            self.start.into(),
+            self.end.into(),
+            roughness,
+            count,
+            end_damp,
+            seed,
+        )
     }
 }
 
-pub fn to_catmull_rom_spline(
+fn spline(
     start: (f32, f32),
     end: (f32, f32),
     roughness: f32,
-    count: i32,
     end_damp: f32,
+    rng: &mut SmallRng,
 ) -> [Point; 4] {
     let length = f32::sqrt((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2));
-    let mut rng =
-        SmallRng::seed_from_u64((start.0 + start.1 + end.0 + end.1 + count as f32) as u64);
     let roughness = f32::clamp(0.1 * roughness * length, 0.1, 100.0);
     let r1 = f32::sqrt(rng.gen_range(0.0..roughness)) / end_damp;
     let r2 = f32::sqrt(rng.gen_range(0.0..roughness)) / end_damp;
@@ -188,26 +167,19 @@ pub fn to_catmull_rom_spline(
     let dis_orth = rng.gen_range(-0.1..0.1) * roughness;
 
     let r_start = Point {
-        x: (start.0 + r1 * f32::cos(phi1)) as i32,
-        y: (start.1 + r1 * f32::sin(phi1)) as i32,
+        x: start.0 + r1 * f32::cos(phi1),
+        y: start.1 + r1 * f32::sin(phi1),
     };
     let r_end = Point {
-        x: (end.0 + r2 * f32::cos(phi2)) as i32,
-        y: (end.1 + r2 * f32::sin(phi2)) as i32,
+        x: end.0 + r2 * f32::cos(phi2),
+        y: end.1 + r2 * f32::sin(phi2),
     };
 
     let along = Vector::from_points(&r_start, &r_end).normalize();
     let orth = Vector::new(-along.y, along.x);
 
-    let start = Point {
-        x: start.0 as i32,
-        y: start.1 as i32,
-    };
-
-    let end = Point {
-        x: end.0 as i32,
-        y: end.1 as i32,
-    };
+    let start = Point::from(start);
+    let end = Point::from(end);
 
     let mid_point = start + &(0.5 * length * &along) + &(mid_point_offset * &orth);
     let control_point = start + &((0.75 + dis_along) * length * &along) + &(dis_orth * &orth);
@@ -215,31 +187,155 @@ pub fn to_catmull_rom_spline(
     [start, mid_point, control_point, end]
 }
 
-pub fn to_svg_path(
+/// A slightly bent line from `start` to `end` as a cubic Bézier, its shape
+/// depending only on `seed`, not on where the line is.
+pub fn to_catmull_rom_spline(
     start: (f32, f32),
     end: (f32, f32),
     roughness: f32,
+    end_damp: f32,
+    seed: u64,
+) -> [Point; 4] {
+    spline(
+        start,
+        end,
+        roughness,
+        end_damp,
+        &mut SmallRng::seed_from_u64(seed),
+    )
+}
+
+fn line(
+    path: &mut Path,
+    (start, end): ((f32, f32), (f32, f32)),
+    roughness: f32,
     count: i32,
     end_damp: f32,
-) -> String {
-    let mut path = String::new();
-    for i in 0..count {
-        let spline = to_catmull_rom_spline(start, end, roughness, i, end_damp);
-        path += &format!(
-            " M {} {} C {} {} {} {} {} {}",
-            spline[0].x,
-            spline[0].y,
-            spline[1].x,
-            spline[1].y,
-            spline[2].x,
-            spline[2].y,
-            spline[3].x,
-            spline[3].y
+    rng: &mut SmallRng,
+) {
+    for _ in 0..count {
+        let [start, first, second, end] = spline(start, end, roughness, end_damp, rng);
+        path.move_to(start.into());
+        path.cubic_to(first.into(), second.into(), end.into());
+    }
+}
+
+/// A line from `start` to `end` drawn `count` times, each stroke a slightly
+/// different curve.
+///
+/// The same `seed` always gives the same strokes, so a line drawn with the
+/// seed of its shape keeps its character when it is moved.
+pub fn to_path(
+    start: (f32, f32),
+    end: (f32, f32),
+    roughness: f32,
+    count: i32,
+    end_damp: f32,
+    seed: u64,
+) -> Path {
+    let mut path = Path::new();
+    line(
+        &mut path,
+        (start, end),
+        roughness,
+        count,
+        end_damp,
+        &mut SmallRng::seed_from_u64(seed),
+    );
+    path
+}
+
+/// Straight lines from each of `points` to the next, each drawn like
+/// [`to_path`]. With two points the strokes are those of [`to_path`] with
+/// the same `seed`.
+pub fn polyline_to_path(
+    points: &[(f32, f32)],
+    roughness: f32,
+    count: i32,
+    end_damp: f32,
+    seed: u64,
+) -> Path {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut path = Path::new();
+    for segment in points.windows(2) {
+        line(
+            &mut path,
+            (segment[0], segment[1]),
+            roughness,
+            count,
+            end_damp,
+            &mut rng,
         );
     }
     path
 }
 
+/// A rectangle spanned by the corners `start` and `end`, its sides drawn
+/// with [`to_path`] and its corners rounded with radius `rounding`.
+///
+/// The radius shrinks on small rectangles so the corners never take more
+/// than 30% of a side.
+pub fn rect_to_path(
+    start: (f32, f32),
+    end: (f32, f32),
+    roughness: f32,
+    rounding: f32,
+    seed: u64,
+) -> Path {
+    let x = start.0.min(end.0);
+    let y = start.1.min(end.1);
+    let p = start.0.max(end.0);
+    let q = start.1.max(end.1);
+    let r = rounding.min(0.3 * (p - x)).min(0.3 * (q - y));
+    let f = 0.3 * r;
+    // Each side followed by the corner after it, clockwise from the top.
+    let sides = [
+        [(x + r, y), (p - r, y), (p - f, y), (p, y + f), (p, y + r)],
+        [(p, y + r), (p, q - r), (p, q - f), (p - f, q), (p - r, q)],
+        [(p - r, q), (x + r, q), (x + f, q), (x, q - f), (x, q - r)],
+        [(x, q - r), (x, y + r), (x, y + f), (x + f, y), (x + r, y)],
+    ];
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut path = Path::new();
+    for [from, to, first, second, corner] in sides {
+        line(&mut path, (from, to), roughness, 2, 1.0, &mut rng);
+        path.move_to(to);
+        path.cubic_to(first, second, corner);
+    }
+    path
+}
+
+/// The outline of [`rect_to_path`] without its wobble, as a polygon with
+/// a few points on every rounded corner.
+pub fn rounded_rect_polygon(start: (f32, f32), end: (f32, f32), rounding: f32) -> Vec<(f32, f32)> {
+    const CORNER_STEPS: usize = 4;
+    let x = start.0.min(end.0);
+    let y = start.1.min(end.1);
+    let p = start.0.max(end.0);
+    let q = start.1.max(end.1);
+    let r = rounding.min(0.3 * (p - x)).min(0.3 * (q - y));
+    let f = 0.3 * r;
+    // The corners of rect_to_path, clockwise from the top right.
+    let corners = [
+        [(p - r, y), (p - f, y), (p, y + f), (p, y + r)],
+        [(p, q - r), (p, q - f), (p - f, q), (p - r, q)],
+        [(x + r, q), (x + f, q), (x, q - f), (x, q - r)],
+        [(x, y + r), (x, y + f), (x + f, y), (x + r, y)],
+    ];
+    let mut points = Vec::with_capacity(corners.len() * (CORNER_STEPS + 1));
+    for [a, b, c, d] in corners {
+        for step in 0..=CORNER_STEPS {
+            let t = step as f32 / CORNER_STEPS as f32;
+            let u = 1.0 - t;
+            let bezier = |a: f32, b: f32, c: f32, d: f32| {
+                u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+            };
+            points.push((bezier(a.0, b.0, c.0, d.0), bezier(a.1, b.1, c.1, d.1)));
+        }
+    }
+    points
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,9 +343,50 @@ mod tests {
     #[test]
     fn line_length() {
         let line = Line {
-            start: Point { x: 0, y: 0 },
-            end: Point { x: 3, y: 4 },
+            start: Point { x: 0.0, y: 0.0 },
+            end: Point { x: 3.0, y: 4.0 },
         };
         assert_eq!(line.length(), 5.0);
     }
+
+    #[test]
+    fn test_seed() {
+        let path = to_path((0.0, 0.0), (100.0, 20.0), 0.8, 2, 1.0, 3);
+        assert_eq!(path, to_path((0.0, 0.0), (100.0, 20.0), 0.8, 2, 1.0, 3));
+        assert_ne!(path, to_path((0.0, 0.0), (100.0, 20.0), 0.8, 2, 1.0, 4));
+        // A moved line keeps its wobble, also at negative coordinates.
+        let moved = to_path((-300.0, -50.0), (-200.0, -30.0), 0.8, 2, 1.0, 3);
+        for ((x, y), (p, q)) in path.end_points().zip(moved.end_points()) {
+            assert!((p - x + 300.0).abs() < 0.001 && (q - y + 50.0).abs() < 0.001);
+        }
+        // The two strokes differ.
+        assert_ne!(path.commands()[1], path.commands()[3]);
+    }
+
+    #[test]
+    fn test_polyline() {
+        let points = [(0.0, 0.0), (100.0, 20.0), (100.0, 80.0)];
+        assert_eq!(
+            polyline_to_path(&points[..2], 0.8, 2, 1.0, 3),
+            to_path(points[0], points[1], 0.8, 2, 1.0, 3)
+        );
+        let path = polyline_to_path(&points, 0.8, 2, 1.0, 3);
+        assert_eq!(path.commands().len(), 8);
+        let (x, y) = path.end_points().last().unwrap();
+        assert!((x - 100.0).abs() < 1.0 && (y - 80.0).abs() < 1.0);
+        assert!(polyline_to_path(&points[..1], 0.8, 2, 1.0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_rounded_rect_polygon() {
+        let points = rounded_rect_polygon((40.0, 20.0), (0.0, 0.0), 5.0);
+        assert_eq!(points.len(), 20);
+        assert_eq!(points[0], (35.0, 0.0));
+        assert_eq!(points[4], (40.0, 5.0));
+        assert!(points
+            .iter()
+            .all(|(x, y)| (0.0..=40.0).contains(x) && (0.0..=20.0).contains(y)));
+        // No corner point reaches the corner of the rectangle.
+        assert!(!points.contains(&(40.0, 0.0)));
+    }
 }
@@ -1,7 +1,22 @@
 extern crate rand;
+pub mod curve;
+pub mod ellipse;
+pub mod fill;
 pub mod geometry;
+pub mod path;
 
 pub use geometry::Line;
 pub use geometry::Point;
+pub use path::Command;
+pub use path::Path;
 
-pub use geometry::to_svg_path;
+pub use curve::curve_to_path;
+pub use ellipse::arc_to_path;
+pub use ellipse::ellipse_to_path;
+pub use fill::ellipse_fill_path;
+pub use fill::polygon_fill_path;
+pub use fill::rect_fill_path;
+pub use fill::Fill;
+pub use geometry::polyline_to_path;
+pub use geometry::rect_to_path;
+pub use geometry::to_path;
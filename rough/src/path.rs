@@ -0,0 +1,240 @@
+use std::fmt;
+
+/// A step of a [`Path`], in absolute pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    /// Lifts the pen and puts it down at a point, starting a new stroke.
+    Move((f32, f32)),
+    /// A straight line to a point.
+    Line((f32, f32)),
+    /// A cubic Bézier curve with two control points, then the point it ends
+    /// in.
+    Cubic((f32, f32), (f32, f32), (f32, f32)),
+    /// A straight line back to where the stroke started.
+    Close,
+}
+
+/// The strokes of a drawing as a list of commands, the geometry of an SVG
+/// path without its text.
+///
+/// Renderers other than SVG can walk [`Path::commands`], SVG takes
+/// [`Path::to_svg`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    commands: Vec<Command>,
+}
+
+/// Lines each cubic of [`Path::dashed`] is followed with.
+const CURVE_STEPS: usize = 8;
+
+/// The point at `t` along the cubic from `start` with the control points
+/// `first` and `second` to `end`.
+fn cubic_point(
+    start: (f32, f32),
+    first: (f32, f32),
+    second: (f32, f32),
+    end: (f32, f32),
+    t: f32,
+) -> (f32, f32) {
+    let u = 1.0 - t;
+    let bezier = |a: f32, b: f32, c: f32, d: f32| {
+        u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+    };
+    (
+        bezier(start.0, first.0, second.0, end.0),
+        bezier(start.1, first.1, second.1, end.1),
+    )
+}
+
+/// Rounds to hundredths of a pixel to keep SVG paths short, without writing
+/// a negative zero.
+fn number(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0 + 0.0
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path::default()
+    }
+
+    pub fn move_to(&mut self, point: (f32, f32)) {
+        self.commands.push(Command::Move(point));
+    }
+
+    pub fn line_to(&mut self, point: (f32, f32)) {
+        self.commands.push(Command::Line(point));
+    }
+
+    pub fn cubic_to(&mut self, first: (f32, f32), second: (f32, f32), point: (f32, f32)) {
+        self.commands.push(Command::Cubic(first, second, point));
+    }
+
+    pub fn close(&mut self) {
+        self.commands.push(Command::Close);
+    }
+
+    /// Adds the strokes of `other` after the ones of this path.
+    pub fn append(&mut self, other: Path) {
+        self.commands.extend(other.commands);
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// The points the commands end in, skipping control points and closes.
+    pub fn end_points(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.commands.iter().filter_map(|command| match command {
+            Command::Move(point) | Command::Line(point) | Command::Cubic(_, _, point) => {
+                Some(*point)
+            }
+            Command::Close => None,
+        })
+    }
+
+    /// The strokes of this path cut into dashes `dash` pixels long with gaps
+    /// of `gap` pixels, every stroke starting with a dash. Curves are followed
+    /// with short lines, so the dashes keep the wobble of the strokes.
+    pub fn dashed(&self, dash: f32, gap: f32) -> Path {
+        if dash <= 0.0 || gap <= 0.0 {
+            return self.clone();
+        }
+        let mut dashed = Path::new();
+        let mut stroke_start = (0.0, 0.0);
+        let mut current = (0.0, 0.0);
+        // Whether the pen is in a dash, how far the dash or gap goes on and
+        // whether the dash has been started in `dashed`.
+        let (mut on, mut left, mut drawing) = (true, dash, false);
+        for command in &self.commands {
+            let points: Vec<(f32, f32)> = match *command {
+                Command::Move(point) => {
+                    stroke_start = point;
+                    current = point;
+                    (on, left, drawing) = (true, dash, false);
+                    continue;
+                }
+                Command::Line(point) => vec![point],
+                Command::Cubic(first, second, end) => (1..=CURVE_STEPS)
+                    .map(|i| {
+                        cubic_point(current, first, second, end, i as f32 / CURVE_STEPS as f32)
+                    })
+                    .collect(),
+                Command::Close => vec![stroke_start],
+            };
+            for point in points {
+                let mut from = current;
+                let mut length = ((point.0 - from.0).powi(2) + (point.1 - from.1).powi(2)).sqrt();
+                while length > 0.0 {
+                    let step = left.min(length);
+                    let t = step / length;
+                    let to = (
+                        from.0 + t * (point.0 - from.0),
+                        from.1 + t * (point.1 - from.1),
+                    );
+                    if on {
+                        if !drawing {
+                            dashed.move_to(from);
+                            drawing = true;
+                        }
+                        dashed.line_to(to);
+                    }
+                    (from, length, left) = (to, length - step, left - step);
+                    if left <= 0.0 {
+                        on = !on;
+                        left = if on { dash } else { gap };
+                        drawing = false;
+                    }
+                }
+                current = point;
+            }
+        }
+        dashed
+    }
+
+    /// The path as the `d` attribute of an SVG `path`, to a hundredth of a
+    /// pixel.
+    pub fn to_svg(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, command) in self.commands.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            match command {
+                Command::Move((x, y)) => write!(f, "M {} {}", number(*x), number(*y))?,
+                Command::Line((x, y)) => write!(f, "L {} {}", number(*x), number(*y))?,
+                Command::Cubic((a, b), (c, d), (x, y)) => write!(
+                    f,
+                    "C {} {} {} {} {} {}",
+                    number(*a),
+                    number(*b),
+                    number(*c),
+                    number(*d),
+                    number(*x),
+                    number(*y)
+                )?,
+                Command::Close => f.write_str("Z")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_svg() {
+        let mut path = Path::new();
+        assert_eq!(path.to_svg(), "");
+        path.move_to((0.5, -0.001));
+        path.line_to((10.0, 2.346));
+        path.cubic_to((1.0, 2.0), (3.0, 4.0), (5.25, 6.0));
+        path.close();
+        assert_eq!(path.to_svg(), "M 0.5 0 L 10 2.35 C 1 2 3 4 5.25 6 Z");
+
+        let mut other = Path::new();
+        other.move_to((1.0, 1.0));
+        path.append(other);
+        assert_eq!(path.commands().len(), 5);
+        assert_eq!(
+            path.end_points().collect::<Vec<_>>(),
+            vec![(0.5, -0.001), (10.0, 2.346), (5.25, 6.0), (1.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_dashed() {
+        let mut path = Path::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((6.0, 0.0));
+        path.line_to((6.0, 4.0));
+        path.move_to((0.0, 10.0));
+        path.line_to((4.0, 10.0));
+        // Dashes run around corners, every stroke starts with one.
+        assert_eq!(
+            path.dashed(3.0, 2.0).to_svg(),
+            "M 0 0 L 3 0 M 5 0 L 6 0 L 6 2 M 0 10 L 3 10"
+        );
+        assert_eq!(path.dashed(0.0, 2.0), path);
+
+        let mut curve = Path::new();
+        curve.move_to((0.0, 0.0));
+        curve.cubic_to((0.0, 10.0), (10.0, 10.0), (10.0, 0.0));
+        let dashes = curve.dashed(1.0, 1.0);
+        assert!(dashes.commands().len() > 10);
+        // The dashes stay on the curve.
+        assert!(dashes
+            .end_points()
+            .all(|(x, y)| (-0.01..=10.01).contains(&x) && (-0.01..=7.6).contains(&y)));
+    }
+}
@@ -1 +1,14 @@
 import * as ib from "infrastructure_browser";
+
+async function exportPng() {
+  const png = ib.export_png(window.devicePixelRatio || 1, false);
+  const link = document.createElement("a");
+  link.href = URL.createObjectURL(new Blob([png], { type: "image/png" }));
+  link.download = "commitcanvas.png";
+  link.click();
+  URL.revokeObjectURL(link.href);
+}
+
+document.getElementById("exportPng").addEventListener("click", () => {
+  exportPng().catch((e) => console.error("failed to export PNG:", e));
+});